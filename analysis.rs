@@ -0,0 +1,371 @@
+// Numeric analysis of plotted functions: zeros, local extrema and
+// intersections over an interval (definite integrals use
+// `calculus::integrate`). Interval scans use a fixed number of subdivisions;
+// each bracket found is refined to the requested tolerance.
+
+use std::fmt;
+
+const MAX_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Roots,
+    Extrema,
+    Intersections,
+    Integral,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Roots, Tool::Extrema, Tool::Intersections, Tool::Integral];
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tool::Roots => "Miejsca zerowe",
+                Tool::Extrema => "Ekstrema lokalne",
+                Tool::Intersections => "Punkty przecięcia",
+                Tool::Integral => "Całka oznaczona",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub tolerance: f64,
+    pub subdivisions: usize,
+}
+
+/// Brent's method on a bracket with f(a) and f(b) of opposite signs.
+pub fn find_root(f: &mut dyn FnMut(f64) -> f64, mut a: f64, mut b: f64, tolerance: f64) -> Option<f64> {
+    let mut fa = f(a);
+    let mut fb = f(b);
+    if !fa.is_finite() || !fb.is_finite() || fa * fb > 0.0 {
+        return None;
+    }
+    if fa == 0.0 {
+        return Some(a);
+    }
+
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = b - a;
+    let mut e = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        if fb * fc > 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tol = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let m = 0.5 * (c - b);
+        if m.abs() <= tol || fb == 0.0 {
+            return Some(b);
+        }
+
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            // Inverse quadratic interpolation, or secant with two points.
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * m * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * m * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol { d } else { tol.copysign(m) };
+        fb = f(b);
+        if !fb.is_finite() {
+            return None;
+        }
+    }
+    Some(b)
+}
+
+/// Sample points of a scan over [a, b].
+fn scan(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64, subdivisions: usize) -> Vec<(f64, f64)> {
+    let step = (b - a) / subdivisions as f64;
+    (0..=subdivisions)
+        .map(|i| {
+            let x = a + i as f64 * step;
+            (x, f(x))
+        })
+        .collect()
+}
+
+/// All zeros in [a, b]: sign changes refined with Brent's method, plus
+/// touching zeros found as extrema lying on the axis.
+pub fn roots(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64, settings: &Settings) -> Vec<f64> {
+    let points = scan(f, a, b, settings.subdivisions);
+    let mut roots = Vec::new();
+
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if !y0.is_finite() || !y1.is_finite() {
+            continue;
+        }
+        if y0 == 0.0 {
+            roots.push(x0);
+        } else if y0 * y1 < 0.0 {
+            if let Some(root) = find_root(f, x0, x1, settings.tolerance) {
+                // A sign change across a pole (tan, 1/x) is not a zero.
+                if f(root).abs() <= y0.abs().min(y1.abs()) {
+                    roots.push(root);
+                }
+            }
+        }
+    }
+    if let Some(&(x, y)) = points.last() {
+        if y == 0.0 {
+            roots.push(x);
+        }
+    }
+
+    let touching = settings.tolerance.sqrt();
+    for extremum in extrema(f, a, b, settings) {
+        if extremum.y.abs() <= touching {
+            roots.push(extremum.x);
+        }
+    }
+
+    dedup(roots, (b - a) / settings.subdivisions as f64 / 2.0)
+}
+
+fn dedup(mut values: Vec<f64>, distance: f64) -> Vec<f64> {
+    values.sort_by(f64::total_cmp);
+    values.dedup_by(|next, kept| (*next - *kept).abs() <= distance);
+    values
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtremumKind {
+    Minimum,
+    Maximum,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Extremum {
+    pub x: f64,
+    pub y: f64,
+    pub kind: ExtremumKind,
+}
+
+/// Golden-section search for a minimum of `f` on [a, b].
+fn golden_minimum(f: &mut dyn FnMut(f64) -> f64, mut a: f64, mut b: f64, tolerance: f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    // Below a few ulps c and d stop moving, so a smaller tolerance is never met.
+    let tolerance = tolerance.max(4.0 * f64::EPSILON);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut fc, mut fd) = (f(c), f(d));
+
+    for _ in 0..MAX_ITERATIONS {
+        if (b - a).abs() <= tolerance * (1.0 + c.abs()) {
+            break;
+        }
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+    }
+    (a + b) / 2.0
+}
+
+/// Local minima and maxima strictly inside [a, b].
+pub fn extrema(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64, settings: &Settings) -> Vec<Extremum> {
+    let points = scan(f, a, b, settings.subdivisions);
+    let mut found = Vec::new();
+
+    for triple in points.windows(3) {
+        let ((x0, y0), (_, y1), (x2, y2)) = (triple[0], triple[1], triple[2]);
+        if !(y0.is_finite() && y1.is_finite() && y2.is_finite()) {
+            continue;
+        }
+
+        let kind = if y1 > y0 && y1 >= y2 {
+            ExtremumKind::Maximum
+        } else if y1 < y0 && y1 <= y2 {
+            ExtremumKind::Minimum
+        } else {
+            continue;
+        };
+
+        let x = match kind {
+            ExtremumKind::Minimum => golden_minimum(f, x0, x2, settings.tolerance),
+            ExtremumKind::Maximum => golden_minimum(&mut |x| -f(x), x0, x2, settings.tolerance),
+        };
+        let y = f(x);
+        if y.is_finite() {
+            found.push(Extremum { x, y, kind });
+        }
+    }
+
+    found.dedup_by(|next, kept| next.kind == kept.kind && (next.x - kept.x).abs() <= settings.tolerance.sqrt());
+    found
+}
+
+/// Selects a curve of the plot by index; shown as `f1`, `f2`, ...
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveChoice(pub usize);
+
+impl fmt::Display for CurveChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "f{}", self.0 + 1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Marker {
+    pub x: f64,
+    pub y: f64,
+    pub label: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Area {
+    pub curve: usize,
+    pub from: f64,
+    pub to: f64,
+    pub value: f64,
+    pub error: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisResult {
+    pub markers: Vec<Marker>,
+    pub area: Option<Area>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AnalysisField {
+    From,
+    To,
+    Tolerance,
+    Subdivisions,
+}
+
+pub struct AnalysisForm {
+    pub tool: Tool,
+    pub curve: CurveChoice,
+    pub other: CurveChoice,
+    pub from: String,
+    pub to: String,
+    pub tolerance: String,
+    pub subdivisions: String,
+    pub result: Option<Result<AnalysisResult, String>>,
+}
+
+impl Default for AnalysisForm {
+    fn default() -> Self {
+        Self {
+            tool: Tool::Roots,
+            curve: CurveChoice(0),
+            other: CurveChoice(1),
+            from: String::new(),
+            to: String::new(),
+            tolerance: String::from("1e-10"),
+            subdivisions: String::from("1000"),
+            result: None,
+        }
+    }
+}
+
+impl AnalysisForm {
+    pub fn field_mut(&mut self, field: AnalysisField) -> &mut String {
+        match field {
+            AnalysisField::From => &mut self.from,
+            AnalysisField::To => &mut self.to,
+            AnalysisField::Tolerance => &mut self.tolerance,
+            AnalysisField::Subdivisions => &mut self.subdivisions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const SETTINGS: Settings = Settings { tolerance: 1e-10, subdivisions: 1000 };
+
+    fn close(actual: &[f64], expected: &[f64]) -> bool {
+        actual.len() == expected.len() && actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-6)
+    }
+
+    #[test]
+    fn brent_root() {
+        let root = find_root(&mut |x| x * x - 2.0, 0.0, 2.0, 1e-12).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(find_root(&mut |x| x * x + 1.0, -1.0, 1.0, 1e-12), None);
+    }
+
+    #[test]
+    fn zeros_in_an_interval() {
+        assert!(close(&roots(&mut f64::sin, -4.0, 4.0, &SETTINGS), &[-PI, 0.0, PI]));
+        assert!(close(&roots(&mut |x| (x - 1.0).powi(2), -3.0, 3.0, &SETTINGS), &[1.0]));
+        assert!(roots(&mut |x| 1.0 / x, -1.0, 1.5, &SETTINGS).is_empty());
+    }
+
+    #[test]
+    fn local_extrema() {
+        let found = extrema(&mut f64::sin, 0.0, 2.0 * PI, &SETTINGS);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].kind, found[1].kind), (ExtremumKind::Maximum, ExtremumKind::Minimum));
+        assert!(close(&[found[0].x, found[1].x], &[PI / 2.0, 1.5 * PI]));
+    }
+
+    #[test]
+    fn tiny_tolerance_still_terminates() {
+        let x = golden_minimum(&mut |x| (x - 1e6).powi(2), 0.0, 2e6, 1e-20);
+        assert!((x - 1e6).abs() < 1e-3);
+
+        let settings = Settings { tolerance: 1e-300, subdivisions: 10 };
+        assert_eq!(extrema(&mut |x| x * x, -1.0, 1.0, &settings).len(), 1);
+    }
+}
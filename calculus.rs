@@ -0,0 +1,309 @@
+// Numeric calculus in the expression language. Each function binds a
+// variable of its own, as in `deriv(x^2, x, 3)` or `sum(1/k^2, k, 1, 100)`:
+// the expression is evaluated with that variable set, while the remaining
+// arguments are evaluated as usual.
+
+use crate::expr::{self, Context, Expr, Scope, Value};
+
+const ABSOLUTE_TOLERANCE: f64 = 1e-10;
+const RELATIVE_TOLERANCE: f64 = 1e-10;
+const MAX_INTERVALS: usize = 1000;
+const MAX_TERMS: f64 = 1e7;
+
+/// `body` as a real function of `var` that remembers the first evaluation
+/// error, so a failure can be reported instead of a bare NaN.
+fn real_function<'s>(
+    scope: &'s Scope,
+    body: &'s Expr,
+    var: &str,
+    error: &'s mut Option<String>,
+) -> impl FnMut(f64) -> f64 + 's {
+    let mut function = scope.function(body, var);
+    move |x| {
+        function(x).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            f64::NAN
+        })
+    }
+}
+
+/// Ridders' method: central differences with shrinking steps, extrapolated to
+/// a step of zero. Returns the derivative and an error estimate.
+pub fn derivative(f: &mut dyn FnMut(f64) -> f64, x: f64) -> (f64, f64) {
+    const SHRINK: f64 = 1.4;
+    const STEPS: usize = 10;
+
+    // Start with a step that stays inside the domain, e.g. for ln near 0.
+    let mut h = 0.1 * x.abs().max(1.0);
+    let mut table = [[0.0; STEPS]; STEPS];
+    table[0][0] = (f(x + h) - f(x - h)) / (2.0 * h);
+    while !table[0][0].is_finite() && h > 1e-10 * x.abs().max(1.0) {
+        h /= 10.0;
+        table[0][0] = (f(x + h) - f(x - h)) / (2.0 * h);
+    }
+    let mut best = table[0][0];
+    let mut error = f64::INFINITY;
+
+    for i in 1..STEPS {
+        h /= SHRINK;
+        table[0][i] = (f(x + h) - f(x - h)) / (2.0 * h);
+        let mut factor = SHRINK * SHRINK;
+        for j in 1..=i {
+            table[j][i] = (table[j - 1][i] * factor - table[j - 1][i - 1]) / (factor - 1.0);
+            factor *= SHRINK * SHRINK;
+            let estimate = (table[j][i] - table[j - 1][i]).abs().max((table[j][i] - table[j - 1][i - 1]).abs());
+            if estimate <= error {
+                error = estimate;
+                best = table[j][i];
+            }
+        }
+        // Higher orders only make it worse once rounding dominates.
+        if (table[i][i] - table[i - 1][i - 1]).abs() >= 2.0 * error {
+            break;
+        }
+    }
+    (best, error)
+}
+
+// Nodes and weights of the 7-point Gauss and 15-point Kronrod rules on [-1, 1];
+// the Gauss nodes are every other Kronrod node.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_18,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_83,
+];
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// The 15-point Kronrod estimate over [a, b] and its difference from the
+/// embedded 7-point Gauss estimate.
+fn kronrod(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let center = (a + b) / 2.0;
+    let half = (b - a) / 2.0;
+    let fc = f(center);
+    let mut kronrod = fc * KRONROD_WEIGHTS[7];
+    let mut gauss = fc * GAUSS_WEIGHTS[3];
+    for j in 0..7 {
+        let dx = half * KRONROD_NODES[j];
+        let pair = f(center - dx) + f(center + dx);
+        kronrod += KRONROD_WEIGHTS[j] * pair;
+        if j % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[j / 2] * pair;
+        }
+    }
+    (kronrod * half, ((kronrod - gauss) * half).abs())
+}
+
+/// Adaptive Gauss–Kronrod integration: the interval with the largest error is
+/// bisected until the total error is small enough. The endpoints themselves
+/// are never evaluated, so integrable singularities there are fine.
+pub fn integrate(f: &mut dyn FnMut(f64) -> f64, a: f64, b: f64) -> Result<(f64, f64), String> {
+    if a == b {
+        return Ok((0.0, 0.0));
+    }
+    let (value, error) = kronrod(f, a, b);
+    let mut intervals = vec![(a, b, value, error)];
+
+    loop {
+        let value: f64 = intervals.iter().map(|i| i.2).sum();
+        let error: f64 = intervals.iter().map(|i| i.3).sum();
+        if !value.is_finite() || !error.is_finite() {
+            return Err("Całka nie jest zbieżna w tym przedziale".to_string());
+        }
+        if error <= ABSOLUTE_TOLERANCE.max(RELATIVE_TOLERANCE * value.abs()) {
+            return Ok((value, error));
+        }
+        if intervals.len() >= MAX_INTERVALS {
+            // Accept what is reasonably accurate, e.g. near a singular endpoint.
+            return if error <= 1e-6 * value.abs().max(1.0) {
+                Ok((value, error))
+            } else {
+                Err(format!("Całka nie jest zbieżna (błąd ≈ {:.1e})", error))
+            };
+        }
+
+        let worst = (0..intervals.len())
+            .max_by(|&i, &j| intervals[i].3.total_cmp(&intervals[j].3))
+            .unwrap_or(0);
+        let (a, b, _, _) = intervals.swap_remove(worst);
+        let middle = (a + b) / 2.0;
+        let (left, left_error) = kronrod(f, a, middle);
+        let (right, right_error) = kronrod(f, middle, b);
+        intervals.push((a, middle, left, left_error));
+        intervals.push((middle, b, right, right_error));
+    }
+}
+
+/// `integral(f, x, a, b)`: the value and its error estimate.
+fn integral(scope: &Scope, args: &[Expr]) -> Result<(f64, f64), String> {
+    let [body, var, from, to] = args else {
+        return Err("Użycie: integral(f, x, a, b)".to_string());
+    };
+    let from = scope.eval(from)?.as_number()?;
+    let to = scope.eval(to)?.as_number()?;
+    if !from.is_finite() || !to.is_finite() {
+        return Err("Granice całkowania muszą być skończone".to_string());
+    }
+    let mut error = None;
+    let result = integrate(&mut real_function(scope, body, var.as_variable()?, &mut error), from, to);
+    match (result, error) {
+        (Ok(result), _) => Ok(result),
+        (Err(_), Some(error)) => Err(error),
+        (Err(error), None) => Err(error),
+    }
+}
+
+/// An `integral(...)` typed on the display, shown as `value ± error` so the
+/// accuracy of the result is visible. None for any other input, including
+/// integrals inside a larger expression, which evaluate to the plain value.
+pub fn integral_result(ctx: &Context, input: &str) -> Option<Result<String, String>> {
+    let Ok(Expr::Call(name, args)) = expr::parse(input) else {
+        return None;
+    };
+    if name != "integral" {
+        return None;
+    }
+    Some(integral(&Scope::new(ctx), &args).map(|(value, error)| format!("{} ± {:.1e}", value, error)))
+}
+
+/// Integer bounds of a sum or product.
+fn bounds(scope: &Scope, from: &Expr, to: &Expr) -> Result<(i64, i64), String> {
+    let from = scope.eval(from)?.as_number()?;
+    let to = scope.eval(to)?.as_number()?;
+    if from.fract() != 0.0 || to.fract() != 0.0 {
+        return Err("Granice sumy i iloczynu muszą być całkowite".to_string());
+    }
+    if to - from >= MAX_TERMS {
+        return Err("Za dużo wyrazów (najwyżej 10 milionów)".to_string());
+    }
+    Ok((from as i64, to as i64))
+}
+
+/// Evaluates `body` for each integer `var` in [from, to] and folds the results.
+fn series(
+    scope: &Scope,
+    body: &Expr,
+    var: &Expr,
+    (from, to): (&Expr, &Expr),
+    start: f64,
+    fold: fn(f64, f64) -> f64,
+) -> Result<Value, String> {
+    let (from, to) = bounds(scope, from, to)?;
+    let mut term = scope.function(body, var.as_variable()?);
+    let mut total = start;
+    for k in from..=to {
+        total = fold(total, term(k as f64)?);
+    }
+    Ok(Value::Num(total))
+}
+
+/// Adds `deriv`, `integral`, and the bound-variable forms of `sum` and `prod`.
+pub fn register(ctx: &mut Context) {
+    ctx.form("deriv", |scope, args| {
+        let [body, var, at] = args else {
+            return Err("Użycie: deriv(f, x, punkt)".to_string());
+        };
+        let at = scope.eval(at)?.as_number()?;
+        let mut error = None;
+        let (value, _) = derivative(&mut real_function(scope, body, var.as_variable()?, &mut error), at);
+        match error {
+            _ if value.is_finite() => Ok(Value::Num(value)),
+            Some(error) => Err(error),
+            None => Err(format!("Funkcja nie jest różniczkowalna w punkcie {}", at)),
+        }
+    });
+
+    ctx.form("integral", |scope, args| integral(scope, args).map(|(value, _)| Value::Num(value)));
+
+    // With a single list argument these remain the sum and product of a list.
+    ctx.form("sum", |scope, args| match args {
+        [body, var, from, to] => series(scope, body, var, (from, to), 0.0, |total, term| total + term),
+        [_] => scope.call("sum", args),
+        _ => Err("Użycie: sum([..]) lub sum(wyrażenie, k, od, do)".to_string()),
+    });
+    ctx.form("prod", |scope, args| match args {
+        [body, var, from, to] => series(scope, body, var, (from, to), 1.0, |total, term| total * term),
+        [list] => Ok(Value::Num(scope.eval(list)?.as_numbers()?.iter().product())),
+        _ => Err("Użycie: prod([..]) lub prod(wyrażenie, k, od, do)".to_string()),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<f64, String> {
+        let mut ctx = Context::new();
+        crate::stats::register(&mut ctx);
+        register(&mut ctx);
+        ctx.eval_str(input)?.as_number()
+    }
+
+    fn close(input: &str, expected: f64, tolerance: f64) -> bool {
+        eval(input).is_ok_and(|value| (value - expected).abs() <= tolerance)
+    }
+
+    #[test]
+    fn derivatives() {
+        assert!(close("deriv(x^3, x, 2)", 12.0, 1e-8));
+        assert!(close("deriv(sin(x), x, 0)", 1.0, 1e-10));
+        assert!(close("deriv(ln(x), x, 0.001)", 1000.0, 1e-4));
+        let (value, error) = derivative(&mut f64::exp, 1.0);
+        assert!((value - std::f64::consts::E).abs() < 1e-10 && error < 1e-8);
+        assert!(eval("deriv(x, 2, 1)").is_err());
+    }
+
+    #[test]
+    fn integrals() {
+        assert!(close("integral(x^2, x, 0, 3)", 9.0, 1e-10));
+        assert!(close("integral(sin(x), x, 0, pi)", 2.0, 1e-10));
+        assert!(close("integral(exp(-x^2), x, -5, 5)", std::f64::consts::PI.sqrt(), 1e-9));
+        assert!(close("integral(x, x, 1, 0)", -0.5, 1e-12));
+        // Singular at the endpoint, but integrable.
+        assert!(close("integral(1/sqrt(x), x, 0, 1)", 2.0, 1e-6));
+        assert!(eval("integral(1/x, x, 0, 1)").is_err());
+        assert!(eval("integral(x, x, 0, 1/0)").is_err());
+    }
+
+    #[test]
+    fn integral_shows_its_error_estimate() {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        let shown = integral_result(&ctx, "integral(x^2, x, 0, 3)").unwrap().unwrap();
+        let (value, error) = shown.split_once(" ± ").unwrap();
+        assert!((value.parse::<f64>().unwrap() - 9.0).abs() < 1e-10);
+        assert!(error.parse::<f64>().unwrap() < 1e-9);
+        assert!(integral_result(&ctx, "integral(1/x, x, 0, 1)").unwrap().is_err());
+        assert!(integral_result(&ctx, "2 * integral(x, x, 0, 1)").is_none());
+    }
+
+    #[test]
+    fn sums_and_products() {
+        assert!(close("sum(k, k, 1, 100)", 5050.0, 0.0));
+        assert!(close("sum(1/k^2, k, 1, 100000)", std::f64::consts::PI.powi(2) / 6.0, 1e-4));
+        assert!(close("prod(k, k, 1, 5)", 120.0, 0.0));
+        assert!(close("sum([1, 2, 3])", 6.0, 0.0));
+        assert!(close("prod([2, 3, 4])", 24.0, 0.0));
+        assert!(eval("sum(k, k, 1, 2.5)").is_err());
+        assert!(eval("sum(k, k, 0, 1e8)").is_err());
+    }
+}
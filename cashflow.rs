@@ -0,0 +1,319 @@
+// Investment appraisal over cash-flow lists. The first flow happens at t = 0
+// and is not discounted (the usual finance-textbook convention, unlike the
+// spreadsheet NPV which discounts every value). Rates are decimals.
+
+use crate::expr::{Context, Value};
+use crate::tvm::solve_rate;
+
+pub fn npv(rate: f64, flows: &[f64]) -> f64 {
+    flows
+        .iter()
+        .enumerate()
+        .map(|(t, cf)| cf / (1.0 + rate).powi(t as i32))
+        .sum()
+}
+
+/// Rates of return only exist when money goes both out and in.
+fn check_signs(flows: &[f64]) -> Result<(), String> {
+    if flows.iter().any(|cf| *cf < 0.0) && flows.iter().any(|cf| *cf > 0.0) {
+        Ok(())
+    } else {
+        Err("Przepływy muszą zawierać co najmniej jeden wydatek i jeden wpływ".to_string())
+    }
+}
+
+fn found(rate: f64, name: &str) -> Result<f64, String> {
+    if rate.is_finite() {
+        Ok(rate)
+    } else {
+        Err(format!("Nie udało się wyznaczyć {}", name))
+    }
+}
+
+pub fn irr(flows: &[f64], guess: f64) -> Result<f64, String> {
+    check_signs(flows)?;
+    found(solve_rate(|r| npv(r, flows), guess), "IRR")
+}
+
+/// NPV of flows on arbitrary dates (days since 1970-01-01), discounted from
+/// the first date on a 365-day year.
+pub fn xnpv(rate: f64, flows: &[f64], dates: &[f64]) -> f64 {
+    let start = dates.first().copied().unwrap_or(0.0);
+    flows
+        .iter()
+        .zip(dates)
+        .map(|(cf, date)| cf / (1.0 + rate).powf((date - start) / 365.0))
+        .sum()
+}
+
+pub fn xirr(flows: &[f64], dates: &[f64], guess: f64) -> Result<f64, String> {
+    check_signs(flows)?;
+    found(solve_rate(|r| xnpv(r, flows, dates), guess), "XIRR")
+}
+
+/// Modified IRR: outflows financed at `finance_rate`, inflows reinvested at
+/// `reinvest_rate` until the last period.
+pub fn mirr(flows: &[f64], finance_rate: f64, reinvest_rate: f64) -> Result<f64, String> {
+    check_signs(flows)?;
+    let n = flows.len() as i32 - 1;
+    let mut outflows = 0.0;
+    let mut inflows = 0.0;
+
+    for (t, cf) in flows.iter().enumerate() {
+        let t = t as i32;
+        if *cf < 0.0 {
+            outflows += cf / (1.0 + finance_rate).powi(t);
+        } else {
+            inflows += cf * (1.0 + reinvest_rate).powi(n - t);
+        }
+    }
+
+    found((inflows / -outflows).powf(1.0 / n as f64) - 1.0, "MIRR")
+}
+
+/// Periods until the cumulative cash flow turns non-negative, interpolated
+/// within the period; an error if the investment never pays back.
+pub fn payback(flows: &[f64]) -> Result<f64, String> {
+    let mut cumulative = 0.0;
+    for (t, cf) in flows.iter().enumerate() {
+        let previous = cumulative;
+        cumulative += cf;
+        if cumulative >= 0.0 && t > 0 && previous < 0.0 {
+            return Ok((t - 1) as f64 + -previous / cf);
+        }
+    }
+    Err("Inwestycja nie zwraca się w podanym okresie".to_string())
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parses `YYYY-MM-DD` into days since 1970-01-01.
+pub fn parse_date(input: &str) -> Option<i64> {
+    let mut parts = input.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Registers `npv`, `irr`, `xnpv`, `xirr`, `mirr`, `payback` and `date`.
+pub fn register(ctx: &mut Context) {
+    fn guess(args: &[Value], index: usize) -> Result<f64, String> {
+        args.get(index).map_or(Ok(0.1), Value::as_number)
+    }
+    fn arity(args: &[Value], min: usize, max: usize, name: &str) -> Result<(), String> {
+        if (min..=max).contains(&args.len()) {
+            Ok(())
+        } else {
+            Err(format!("Zła liczba argumentów funkcji {}", name))
+        }
+    }
+    fn dated(flows: &Value, dates: &Value) -> Result<(Vec<f64>, Vec<f64>), String> {
+        let flows = flows.as_numbers()?;
+        let dates = dates.as_numbers()?;
+        if flows.len() != dates.len() {
+            return Err("Liczba dat musi odpowiadać liczbie przepływów".to_string());
+        }
+        Ok((flows, dates))
+    }
+
+    ctx.func("npv", |args| {
+        arity(args, 2, 2, "npv")?;
+        Ok(Value::Num(npv(args[0].as_number()?, &args[1].as_numbers()?)))
+    });
+    ctx.func("irr", |args| {
+        arity(args, 1, 2, "irr")?;
+        irr(&args[0].as_numbers()?, guess(args, 1)?).map(Value::Num)
+    });
+    ctx.func("xnpv", |args| {
+        arity(args, 3, 3, "xnpv")?;
+        let (flows, dates) = dated(&args[1], &args[2])?;
+        Ok(Value::Num(xnpv(args[0].as_number()?, &flows, &dates)))
+    });
+    ctx.func("xirr", |args| {
+        arity(args, 2, 3, "xirr")?;
+        let (flows, dates) = dated(&args[0], &args[1])?;
+        xirr(&flows, &dates, guess(args, 2)?).map(Value::Num)
+    });
+    ctx.func("mirr", |args| {
+        arity(args, 3, 3, "mirr")?;
+        mirr(&args[0].as_numbers()?, args[1].as_number()?, args[2].as_number()?).map(Value::Num)
+    });
+    ctx.func("payback", |args| {
+        arity(args, 1, 1, "payback")?;
+        payback(&args[0].as_numbers()?).map(Value::Num)
+    });
+    ctx.funcn(
+        "date",
+        |a| days_from_civil(a[0] as i64, a[1] as i64, a[2] as i64) as f64,
+        3..=3,
+    );
+}
+
+pub struct CashFlowRow {
+    pub amount: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CashFlowField {
+    Rate,
+    FinanceRate,
+    ReinvestRate,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CashFlowSummary {
+    pub npv: f64,
+    // None when they do not exist for these flows.
+    pub irr: Option<f64>,
+    pub mirr: Option<f64>,
+    pub payback: Option<f64>,
+    // Only when every row has a date.
+    pub xnpv: Option<f64>,
+    pub xirr: Option<f64>,
+}
+
+/// The cash-flow editor: one row per period, rates in percent.
+pub struct CashFlowForm {
+    pub rate: String,
+    pub finance_rate: String,
+    pub reinvest_rate: String,
+    pub rows: Vec<CashFlowRow>,
+    pub summary: Option<Result<CashFlowSummary, String>>,
+}
+
+impl Default for CashFlowForm {
+    fn default() -> Self {
+        Self {
+            rate: String::new(),
+            finance_rate: String::new(),
+            reinvest_rate: String::new(),
+            rows: vec![
+                CashFlowRow { amount: String::new(), date: String::new() },
+                CashFlowRow { amount: String::new(), date: String::new() },
+            ],
+            summary: None,
+        }
+    }
+}
+
+impl CashFlowForm {
+    pub fn field_mut(&mut self, field: CashFlowField) -> &mut String {
+        match field {
+            CashFlowField::Rate => &mut self.rate,
+            CashFlowField::FinanceRate => &mut self.finance_rate,
+            CashFlowField::ReinvestRate => &mut self.reinvest_rate,
+        }
+    }
+}
+
+/// Everything the editor shows, from parsed flows and optional dates.
+pub fn summarize(
+    rate: f64,
+    flows: &[f64],
+    dates: Option<&[f64]>,
+    mirr_rates: Option<(f64, f64)>,
+) -> Result<CashFlowSummary, String> {
+    if flows.len() < 2 {
+        return Err("Podaj co najmniej dwa przepływy".to_string());
+    }
+
+    let finite = |value: f64| Some(value).filter(|v| v.is_finite());
+
+    Ok(CashFlowSummary {
+        npv: npv(rate, flows),
+        irr: irr(flows, 0.1).ok(),
+        mirr: mirr_rates.and_then(|(finance, reinvest)| mirr(flows, finance, reinvest).ok()),
+        payback: payback(flows).ok(),
+        xnpv: dates.and_then(|dates| finite(xnpv(rate, flows, dates))),
+        xirr: dates.and_then(|dates| xirr(flows, dates, 0.1).ok()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOWS: [f64; 4] = [-1000.0, 300.0, 400.0, 500.0];
+
+    #[test]
+    fn npv_and_irr() {
+        assert!((npv(0.1, &FLOWS) - -21.0368).abs() < 1e-4);
+        assert!((irr(&FLOWS, 0.1).unwrap() - 0.088963).abs() < 1e-6);
+        assert!((irr(&[-100.0, 110.0], 0.5).unwrap() - 0.1).abs() < 1e-12);
+        assert!(npv(irr(&FLOWS, 0.1).unwrap(), &FLOWS).abs() < 1e-9);
+    }
+
+    #[test]
+    fn degenerate_flows_are_errors() {
+        assert!(irr(&[1.0, 2.0, 3.0], 0.1).is_err());
+        assert!(mirr(&[1.0, 2.0, 3.0], 0.1, 0.1).is_err());
+        assert!(mirr(&[-1.0, -2.0], 0.1, 0.1).is_err());
+        assert!(xirr(&[-1.0, -2.0], &[0.0, 365.0], 0.1).is_err());
+
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        assert!(ctx.eval_str("irr([1, 2, 3])").is_err());
+        assert!(ctx.eval_str("mirr([1, 2, 3], 0.1, 0.1)").is_err());
+        assert!(ctx.eval_str("payback([-1000, 100, 100])").is_err());
+        assert!(ctx.eval_str("irr([-100, 110])").is_ok());
+
+        let summary = summarize(0.1, &[1.0, 2.0], None, Some((0.1, 0.1))).unwrap();
+        assert_eq!((summary.irr, summary.mirr, summary.payback), (None, None, None));
+    }
+
+    #[test]
+    fn spreadsheet_xirr_and_mirr() {
+        let dates = ["2008-01-01", "2008-03-01", "2008-10-30", "2009-02-15", "2009-04-01"]
+            .map(|d| parse_date(d).unwrap() as f64);
+        let flows = [-10_000.0, 2750.0, 4250.0, 3250.0, 2750.0];
+        assert!((xirr(&flows, &dates, 0.1).unwrap() - 0.373362535).abs() < 1e-6);
+
+        let flows = [-120_000.0, 39_000.0, 30_000.0, 21_000.0, 37_000.0, 46_000.0];
+        assert!((mirr(&flows, 0.1, 0.12).unwrap() - 0.126094).abs() < 1e-6);
+    }
+
+    #[test]
+    fn payback_period() {
+        assert_eq!(payback(&[-1000.0, 400.0, 400.0, 400.0]), Ok(2.5));
+        assert!(payback(&[-1000.0, 100.0, 100.0]).is_err());
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(parse_date("2000-03-01"), Some(11017));
+        assert_eq!(parse_date("2000-13-01"), None);
+        assert_eq!(parse_date("2023-02-31"), None);
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-02-29"), Some(19782));
+        assert_eq!(parse_date("1900-02-29"), None);
+        assert_eq!(parse_date("2000-02-29"), Some(11016));
+        assert_eq!(parse_date("2023-04-31"), None);
+        assert_eq!(parse_date("jutro"), None);
+    }
+}
@@ -0,0 +1,159 @@
+// User settings persisted between runs as a plain `key = value` file in the
+// platform config directory. Unknown keys are ignored so older builds can read
+// files written by newer ones.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::expr::AngleMode;
+use crate::rational::{NumberFormat, DEFAULT_MAX_DENOMINATOR};
+use crate::vat::{self, VatRate};
+
+pub struct Config {
+    pub vat_rates: Vec<VatRate>,
+    pub angle_mode: AngleMode,
+    pub number_format: NumberFormat,
+    /// Largest denominator shown when results are displayed as fractions.
+    pub max_denominator: i128,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            vat_rates: vat::default_rates(),
+            angle_mode: AngleMode::Radians,
+            number_format: NumberFormat::Decimal,
+            max_denominator: DEFAULT_MAX_DENOMINATOR,
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        let base = if cfg!(windows) {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        };
+
+        base.map(|dir| dir.join("astralcalc").join("config.txt"))
+    }
+
+    /// Loads the saved settings, falling back to defaults for anything missing.
+    pub fn load() -> Self {
+        match Self::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(contents) => Self::parse(&contents),
+            None => Self::default(),
+        }
+    }
+
+    fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+        let mut vat_rates = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            match key.trim() {
+                "vat_rate" => {
+                    if let Some(rate) = VatRate::parse(value.trim()) {
+                        vat_rates.push(rate);
+                    }
+                }
+                "angle_mode" => {
+                    config.angle_mode = match value.trim() {
+                        "deg" => AngleMode::Degrees,
+                        _ => AngleMode::Radians,
+                    };
+                }
+                "number_format" => {
+                    config.number_format = match value.trim() {
+                        "fraction" => NumberFormat::Fraction,
+                        "mixed" => NumberFormat::Mixed,
+                        _ => NumberFormat::Decimal,
+                    };
+                }
+                "max_denominator" => {
+                    if let Ok(max) = value.trim().parse::<i128>() {
+                        if max >= 1 {
+                            config.max_denominator = max;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !vat_rates.is_empty() {
+            config.vat_rates = vat_rates;
+        }
+        config
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "brak katalogu konfiguracji"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.contents())
+    }
+
+    fn contents(&self) -> String {
+        let mut contents = String::from("# astralcalc\n");
+        for rate in &self.vat_rates {
+            contents.push_str(&format!("vat_rate = {}\n", rate));
+        }
+        let angle_mode = if self.angle_mode == AngleMode::Degrees { "deg" } else { "rad" };
+        contents.push_str(&format!("angle_mode = {}\n", angle_mode));
+        let number_format = match self.number_format {
+            NumberFormat::Decimal => "decimal",
+            NumberFormat::Fraction => "fraction",
+            NumberFormat::Mixed => "mixed",
+        };
+        contents.push_str(&format!("number_format = {}\n", number_format));
+        contents.push_str(&format!("max_denominator = {}\n", self.max_denominator));
+        contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_survive_a_round_trip() {
+        let config = Config {
+            vat_rates: vec![
+                VatRate { name: "PL".to_string(), rate: 23.0 },
+                VatRate { name: "DE obniżona".to_string(), rate: 7.0 },
+            ],
+            angle_mode: AngleMode::Degrees,
+            number_format: NumberFormat::Mixed,
+            max_denominator: 100,
+        };
+        let loaded = Config::parse(&config.contents());
+        assert_eq!(loaded.vat_rates, config.vat_rates);
+        assert_eq!(loaded.angle_mode, AngleMode::Degrees);
+        assert_eq!(loaded.number_format, NumberFormat::Mixed);
+        assert_eq!(loaded.max_denominator, 100);
+    }
+
+    #[test]
+    fn missing_and_invalid_entries_fall_back_to_defaults() {
+        let config = Config::parse("# comment\nfuture_key = 1\nno equals sign\nmax_denominator = 0\nvat_rate = ?\n");
+        assert_eq!(config.vat_rates, vat::default_rates());
+        assert_eq!(config.angle_mode, AngleMode::Radians);
+        assert_eq!(config.number_format, NumberFormat::Decimal);
+        assert_eq!(config.max_denominator, DEFAULT_MAX_DENOMINATOR);
+    }
+}
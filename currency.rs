@@ -0,0 +1,278 @@
+// Offline currency conversion. Rates are kept as the value of one unit in the
+// base currency (PLN, as in NBP tables) and loaded from a local file:
+//
+// * NBP JSON (`[{"effectiveDate": "...", "rates": [{"code", "currency", "mid"}]}]`),
+// * CSV lines `CODE;rate` or `CODE;name;rate`, plus an optional `date;YYYY-MM-DD`.
+//
+// In expressions every code is a variable worth its rate in PLN, so `100 EUR`
+// is an amount in PLN and `... in USD` divides by the USD rate.
+
+use std::fs;
+use std::path::Path;
+
+use crate::cashflow::parse_date;
+use crate::config::Config;
+use crate::expr::Context;
+
+pub const BASE: &str = "PLN";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyRate {
+    pub code: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RateTable {
+    /// Effective date of the rates, `YYYY-MM-DD`.
+    pub date: Option<String>,
+    pub rates: Vec<CurrencyRate>,
+}
+
+impl RateTable {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("Nie można odczytać pliku: {}", e))?;
+        let table = if contents.trim_start().starts_with(['[', '{']) {
+            Self::from_json(&contents)?
+        } else {
+            Self::from_csv(&contents)?
+        };
+
+        if table.rates.is_empty() {
+            return Err("Plik nie zawiera kursów".to_string());
+        }
+        Ok(table)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        let json: serde_json::Value =
+            serde_json::from_str(contents).map_err(|e| format!("Niepoprawny JSON: {}", e))?;
+        // The NBP API wraps the table in a one-element array.
+        let table = match &json {
+            serde_json::Value::Array(tables) => tables.first().ok_or("Pusty plik JSON")?,
+            other => other,
+        };
+
+        let date = table["effectiveDate"].as_str().map(str::to_string);
+        let rates = table["rates"]
+            .as_array()
+            .ok_or("Brak listy \"rates\" w pliku JSON")?
+            .iter()
+            .enumerate()
+            .map(|(index, rate)| match (rate["code"].as_str(), rate["mid"].as_f64()) {
+                (Some(code), Some(mid)) if mid > 0.0 => Ok(CurrencyRate { code: code.to_uppercase(), rate: mid }),
+                _ => Err(format!("Niepoprawny kurs nr {} w pliku JSON", index + 1)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { date, rates })
+    }
+
+    pub fn from_csv(contents: &str) -> Result<Self, String> {
+        let mut table = Self::default();
+        let mut header = false;
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split([';', '\t']).map(str::trim).collect();
+            if fields[0].eq_ignore_ascii_case("date") || fields[0].eq_ignore_ascii_case("data") {
+                table.date = fields.get(1).map(|d| d.to_string());
+                continue;
+            }
+
+            let (code, rate) = match fields.as_slice() {
+                [code, rate] | [code, _, rate] => (code, rate),
+                _ => return Err(format!("Niepoprawny wiersz {}: {}", number + 1, line)),
+            };
+            let rate = match rate.replace(',', ".").parse::<f64>() {
+                Ok(rate) if rate.is_finite() && rate > 0.0 => rate,
+                // A column header may come once, before the first rate.
+                Err(_) if !header && table.rates.is_empty() => {
+                    header = true;
+                    continue;
+                }
+                _ => return Err(format!("Niepoprawny kurs w wierszu {}: {}", number + 1, line)),
+            };
+            table.rates.push(CurrencyRate { code: code.to_uppercase(), rate });
+        }
+
+        Ok(table)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        if let Some(date) = &self.date {
+            csv.push_str(&format!("date;{}\n", date));
+        }
+        for rate in &self.rates {
+            csv.push_str(&format!("{};{}\n", rate.code, rate.rate));
+        }
+        csv
+    }
+
+    /// The in-app copy of the table, kept next to the config file.
+    pub fn saved_path() -> Option<std::path::PathBuf> {
+        Config::path().map(|path| path.with_file_name("kursy.csv"))
+    }
+
+    pub fn load_saved() -> Self {
+        Self::saved_path()
+            .and_then(|path| Self::load(&path).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::saved_path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "brak katalogu konfiguracji")
+        })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_csv())
+    }
+
+    /// Value of one unit of `code` in PLN.
+    pub fn rate(&self, code: &str) -> Option<f64> {
+        if code.eq_ignore_ascii_case(BASE) {
+            return Some(1.0);
+        }
+        self.rates.iter().find(|r| r.code.eq_ignore_ascii_case(code)).map(|r| r.rate)
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Result<f64, String> {
+        let from_rate = self.rate(from).ok_or_else(|| format!("Brak kursu {}", from))?;
+        let to_rate = self.rate(to).ok_or_else(|| format!("Brak kursu {}", to))?;
+        Ok(amount * from_rate / to_rate)
+    }
+
+    pub fn codes(&self) -> Vec<String> {
+        let mut codes = vec![BASE.to_string()];
+        codes.extend(self.rates.iter().map(|r| r.code.clone()));
+        codes
+    }
+
+    /// Days between the table date and today, if the date is known.
+    pub fn age_days(&self) -> Option<i64> {
+        let date = parse_date(self.date.as_deref()?)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs() as i64;
+        Some(now / 86_400 - date)
+    }
+
+    pub fn register(&self, ctx: &mut Context) {
+        ctx.var(BASE, 1.0);
+        for rate in &self.rates {
+            ctx.var(&rate.code, rate.rate);
+        }
+    }
+}
+
+pub struct CurrencyRow {
+    pub code: String,
+    pub rate: String,
+}
+
+pub struct CurrencyForm {
+    pub table: RateTable,
+    pub rows: Vec<CurrencyRow>,
+    pub path: String,
+    pub amount: String,
+    pub from: String,
+    pub to: String,
+    pub status: Option<String>,
+}
+
+impl CurrencyForm {
+    pub fn new(table: RateTable) -> Self {
+        let mut form = Self {
+            table: RateTable::default(),
+            rows: Vec::new(),
+            path: String::new(),
+            amount: String::new(),
+            from: String::from("EUR"),
+            to: String::from(BASE),
+            status: None,
+        };
+        form.set_table(table);
+        form
+    }
+
+    pub fn set_table(&mut self, table: RateTable) {
+        self.rows = table
+            .rates
+            .iter()
+            .map(|r| CurrencyRow { code: r.code.clone(), rate: r.rate.to_string() })
+            .collect();
+        self.table = table;
+    }
+
+    /// Rebuilds the table from the edited rows; the date is kept.
+    pub fn apply_rows(&mut self) -> Result<(), String> {
+        let rates = self
+            .rows
+            .iter()
+            .filter(|row| !row.code.trim().is_empty())
+            .map(|row| match row.rate.trim().replace(',', ".").parse::<f64>() {
+                Ok(rate) if rate > 0.0 => Ok(CurrencyRate { code: row.code.trim().to_uppercase(), rate }),
+                _ => Err(format!("Niepoprawny kurs: {}", row.code)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.table.rates = rates;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NBP: &str = r#"[{"table": "A", "effectiveDate": "2024-05-10",
+        "rates": [{"currency": "euro", "code": "EUR", "mid": 4.3},
+                  {"currency": "dolar amerykański", "code": "USD", "mid": 4.0}]}]"#;
+
+    #[test]
+    fn reads_nbp_json() {
+        let table = RateTable::from_json(NBP).unwrap();
+        assert_eq!(table.date.as_deref(), Some("2024-05-10"));
+        assert_eq!(table.rate("eur"), Some(4.3));
+        assert_eq!(table.rate("PLN"), Some(1.0));
+        assert!(RateTable::from_json("{}").is_err());
+        let error = RateTable::from_json(r#"{"rates": [{"code": "EUR", "mid": 4.3}, {"code": "USD"}]}"#).unwrap_err();
+        assert!(error.contains("nr 2"), "{}", error);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let table = RateTable::from_csv("kod;nazwa;kurs\ndata;2024-05-10\neur;euro;4,3\nUSD;4\n").unwrap();
+        assert_eq!(table.codes(), ["PLN", "EUR", "USD"]);
+        assert_eq!(table.date.as_deref(), Some("2024-05-10"));
+
+        let again = RateTable::from_csv(&table.to_csv()).unwrap();
+        assert_eq!(again.rates, table.rates);
+        assert!(RateTable::from_csv("EUR").is_err());
+
+        let error = RateTable::from_csv("kod;kurs\nEUR;4,3\nUSD;?\n").unwrap_err();
+        assert!(error.contains("wierszu 3"), "{}", error);
+        assert!(RateTable::from_csv("kod;kurs\nwaluta;kurs\n").is_err());
+        assert!(RateTable::from_csv("EUR;0").is_err());
+    }
+
+    #[test]
+    fn conversion() {
+        let table = RateTable::from_json(NBP).unwrap();
+        assert!((table.convert(100.0, "EUR", "USD").unwrap() - 107.5).abs() < 1e-9);
+        assert!(table.convert(1.0, "EUR", "XYZ").is_err());
+
+        let mut ctx = Context::new();
+        table.register(&mut ctx);
+        let value = ctx.eval_str("100 EUR in USD").unwrap().as_number().unwrap();
+        assert!((value - 107.5).abs() < 1e-9);
+    }
+}
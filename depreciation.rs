@@ -0,0 +1,192 @@
+// Depreciation schedules for an asset with a cost, salvage value and a useful
+// life in whole years.
+
+use std::fmt;
+
+/// Longest useful life accepted, in years.
+const MAX_LIFE: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    StraightLine,
+    DecliningBalance,
+    DoubleDeclining,
+    SumOfYears,
+}
+
+impl Method {
+    pub const ALL: [Method; 4] = [
+        Method::StraightLine,
+        Method::DecliningBalance,
+        Method::DoubleDeclining,
+        Method::SumOfYears,
+    ];
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Method::StraightLine => "Liniowa",
+                Method::DecliningBalance => "Degresywna z przejściem na liniową",
+                Method::DoubleDeclining => "Podwójnie degresywna",
+                Method::SumOfYears => "Suma cyfr lat",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Asset {
+    pub cost: f64,
+    pub salvage: f64,
+    pub life: u32,
+    /// Multiplier of the straight-line rate for `DecliningBalance`.
+    pub factor: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DepreciationRow {
+    pub year: u32,
+    pub depreciation: f64,
+    pub accumulated: f64,
+    pub book_value: f64,
+}
+
+impl Asset {
+    pub fn schedule(&self, method: Method) -> Result<Vec<DepreciationRow>, String> {
+        if self.life == 0 {
+            return Err("Okres użytkowania musi wynosić co najmniej rok".to_string());
+        }
+        if self.life > MAX_LIFE {
+            return Err(format!("Okres użytkowania może wynosić najwyżej {} lat", MAX_LIFE));
+        }
+        if ![self.cost, self.salvage, self.factor].iter().all(|v| v.is_finite()) {
+            return Err("Wszystkie pola muszą być skończonymi liczbami".to_string());
+        }
+        if self.cost <= 0.0 || self.salvage < 0.0 || self.salvage > self.cost {
+            return Err("Wartość końcowa musi mieścić się między 0 a ceną nabycia".to_string());
+        }
+        if method == Method::DecliningBalance && self.factor <= 0.0 {
+            return Err("Współczynnik musi być dodatni".to_string());
+        }
+
+        let life = self.life as f64;
+        let depreciable = self.cost - self.salvage;
+        let digits_sum = life * (life + 1.0) / 2.0;
+
+        let mut book_value = self.cost;
+        let mut accumulated = 0.0;
+        let mut rows = Vec::with_capacity(self.life as usize);
+
+        for year in 1..=self.life {
+            let remaining_years = (self.life - year + 1) as f64;
+            let amount = match method {
+                Method::StraightLine => depreciable / life,
+                Method::SumOfYears => depreciable * remaining_years / digits_sum,
+                Method::DoubleDeclining => book_value * 2.0 / life,
+                Method::DecliningBalance => {
+                    // Switch once straight-line over the remaining life gives more.
+                    let declining = book_value * self.factor / life;
+                    let linear = (book_value - self.salvage) / remaining_years;
+                    declining.max(linear)
+                }
+            };
+
+            // Never depreciate below the salvage value.
+            let amount = amount.min(book_value - self.salvage).max(0.0);
+            book_value -= amount;
+            accumulated += amount;
+
+            rows.push(DepreciationRow { year, depreciation: amount, accumulated, book_value });
+        }
+
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DepreciationField {
+    Cost,
+    Salvage,
+    Life,
+    Factor,
+}
+
+pub struct DepreciationForm {
+    pub cost: String,
+    pub salvage: String,
+    pub life: String,
+    pub factor: String,
+    pub method: Method,
+    pub schedule: Option<Result<Vec<DepreciationRow>, String>>,
+}
+
+impl Default for DepreciationForm {
+    fn default() -> Self {
+        Self {
+            cost: String::new(),
+            salvage: String::new(),
+            life: String::new(),
+            factor: String::from("2"),
+            method: Method::StraightLine,
+            schedule: None,
+        }
+    }
+}
+
+impl DepreciationForm {
+    pub fn field_mut(&mut self, field: DepreciationField) -> &mut String {
+        match field {
+            DepreciationField::Cost => &mut self.cost,
+            DepreciationField::Salvage => &mut self.salvage,
+            DepreciationField::Life => &mut self.life,
+            DepreciationField::Factor => &mut self.factor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amounts(asset: Asset, method: Method) -> Vec<f64> {
+        let rows = asset.schedule(method).unwrap();
+        rows.iter().map(|row| (row.depreciation * 100.0).round() / 100.0).collect()
+    }
+
+    const ASSET: Asset = Asset { cost: 10_000.0, salvage: 1000.0, life: 5, factor: 2.0 };
+
+    #[test]
+    fn straight_line_and_sum_of_years() {
+        assert_eq!(amounts(ASSET, Method::StraightLine), [1800.0; 5]);
+        assert_eq!(amounts(ASSET, Method::SumOfYears), [3000.0, 2400.0, 1800.0, 1200.0, 600.0]);
+    }
+
+    #[test]
+    fn declining_balance_stops_at_salvage() {
+        assert_eq!(amounts(ASSET, Method::DoubleDeclining), [4000.0, 2400.0, 1440.0, 864.0, 296.0]);
+        let rows = ASSET.schedule(Method::DoubleDeclining).unwrap();
+        assert!((rows[4].book_value - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn declining_balance_switches_to_straight_line() {
+        let asset = Asset { salvage: 0.0, ..ASSET };
+        assert_eq!(amounts(asset, Method::DecliningBalance), [4000.0, 2400.0, 1440.0, 1080.0, 1080.0]);
+        assert_eq!(amounts(asset, Method::DoubleDeclining)[4], 518.4);
+    }
+
+    #[test]
+    fn rejects_invalid_assets() {
+        assert!(Asset { life: 0, ..ASSET }.schedule(Method::StraightLine).is_err());
+        assert!(Asset { salvage: 20_000.0, ..ASSET }.schedule(Method::StraightLine).is_err());
+        assert!(Asset { factor: 0.0, ..ASSET }.schedule(Method::DecliningBalance).is_err());
+        assert!(Asset { life: 100, ..ASSET }.schedule(Method::StraightLine).is_ok());
+        assert!(Asset { life: u32::MAX, ..ASSET }.schedule(Method::StraightLine).is_err());
+        assert!(Asset { cost: f64::INFINITY, ..ASSET }.schedule(Method::StraightLine).is_err());
+        assert!(Asset { factor: f64::NAN, ..ASSET }.schedule(Method::DecliningBalance).is_err());
+    }
+}
@@ -0,0 +1,584 @@
+// Probability distributions: density (or mass), cumulative distribution and
+// its inverse. The cumulative functions are built on the regularized
+// incomplete gamma and beta functions.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::expr::{Context, Value};
+
+const EPSILON: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 500;
+
+/// ln Γ(x) for x > 0 (Lanczos approximation, g = 7).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula.
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Regularized lower incomplete gamma function P(a, x).
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    incomplete_gamma(a, x).0
+}
+
+/// Regularized upper incomplete gamma function Q(a, x) = 1 − P(a, x),
+/// accurate in the far tail.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    incomplete_gamma(a, x).1
+}
+
+fn incomplete_gamma(a: f64, x: f64) -> (f64, f64) {
+    if x <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let prefix = (a * x.ln() - x - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        let p = (sum * prefix).min(1.0);
+        return (p, 1.0 - p);
+    }
+
+    // Continued fraction for Q(a, x) (modified Lentz).
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    let q = (prefix * h).min(1.0);
+    (1.0 - q, q)
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+pub fn beta_i(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let prefix = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly below the mean.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        prefix * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - prefix * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+    let clamp = |v: f64| if v.abs() < tiny { tiny } else { v };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        h *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    Normal,
+    StudentT,
+    ChiSquared,
+    Binomial,
+    Poisson,
+    Exponential,
+    Uniform,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 7] = [
+        Distribution::Normal,
+        Distribution::StudentT,
+        Distribution::ChiSquared,
+        Distribution::Binomial,
+        Distribution::Poisson,
+        Distribution::Exponential,
+        Distribution::Uniform,
+    ];
+
+    /// Parameter labels with their default values.
+    pub fn parameters(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Distribution::Normal => &[("Średnia μ", "0"), ("Odchylenie σ", "1")],
+            Distribution::StudentT => &[("Stopnie swobody ν", "10")],
+            Distribution::ChiSquared => &[("Stopnie swobody k", "5")],
+            Distribution::Binomial => &[("Liczba prób n", "10"), ("Prawdopodobieństwo p", "0.5")],
+            Distribution::Poisson => &[("Intensywność λ", "3")],
+            Distribution::Exponential => &[("Intensywność λ", "1")],
+            Distribution::Uniform => &[("Początek a", "0"), ("Koniec b", "1")],
+        }
+    }
+
+    pub fn is_discrete(&self) -> bool {
+        matches!(self, Distribution::Binomial | Distribution::Poisson)
+    }
+
+    pub fn validate(&self, p: &[f64]) -> Result<(), String> {
+        if p.len() != self.parameters().len() || p.iter().any(|v| !v.is_finite()) {
+            return Err("Niepoprawne parametry rozkładu".to_string());
+        }
+        let valid = match self {
+            Distribution::Normal => p[1] > 0.0,
+            Distribution::StudentT | Distribution::ChiSquared => p[0] > 0.0,
+            Distribution::Binomial => p[0] >= 0.0 && p[0].fract() == 0.0 && (0.0..=1.0).contains(&p[1]),
+            Distribution::Poisson | Distribution::Exponential => p[0] > 0.0,
+            Distribution::Uniform => p[0] < p[1],
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(match self {
+                Distribution::Normal => "Odchylenie musi być dodatnie",
+                Distribution::StudentT | Distribution::ChiSquared => "Liczba stopni swobody musi być dodatnia",
+                Distribution::Binomial => "n musi być naturalne, a p z przedziału [0, 1]",
+                Distribution::Poisson | Distribution::Exponential => "Intensywność musi być dodatnia",
+                Distribution::Uniform => "Początek przedziału musi być mniejszy od końca",
+            }
+            .to_string())
+        }
+    }
+
+    /// Density, or probability mass for discrete distributions.
+    pub fn pdf(&self, p: &[f64], x: f64) -> f64 {
+        match self {
+            Distribution::Normal => {
+                let z = (x - p[0]) / p[1];
+                (-0.5 * z * z).exp() / (p[1] * (2.0 * PI).sqrt())
+            }
+            Distribution::StudentT => {
+                let v = p[0];
+                (ln_gamma((v + 1.0) / 2.0) - ln_gamma(v / 2.0)).exp() / (v * PI).sqrt()
+                    * (1.0 + x * x / v).powf(-(v + 1.0) / 2.0)
+            }
+            Distribution::ChiSquared => {
+                if x < 0.0 {
+                    return 0.0;
+                }
+                let k = p[0] / 2.0;
+                ((k - 1.0) * x.ln() - x / 2.0 - k * 2f64.ln() - ln_gamma(k)).exp()
+            }
+            Distribution::Binomial => {
+                let (n, prob) = (p[0], p[1]);
+                if x < 0.0 || x > n || x.fract() != 0.0 {
+                    return 0.0;
+                }
+                if prob == 0.0 || prob == 1.0 {
+                    return if (prob == 0.0 && x == 0.0) || (prob == 1.0 && x == n) { 1.0 } else { 0.0 };
+                }
+                (ln_gamma(n + 1.0) - ln_gamma(x + 1.0) - ln_gamma(n - x + 1.0)
+                    + x * prob.ln()
+                    + (n - x) * (1.0 - prob).ln())
+                .exp()
+            }
+            Distribution::Poisson => {
+                if x < 0.0 || x.fract() != 0.0 {
+                    return 0.0;
+                }
+                (x * p[0].ln() - p[0] - ln_gamma(x + 1.0)).exp()
+            }
+            Distribution::Exponential => {
+                if x < 0.0 {
+                    0.0
+                } else {
+                    p[0] * (-p[0] * x).exp()
+                }
+            }
+            Distribution::Uniform => {
+                if x < p[0] || x > p[1] {
+                    0.0
+                } else {
+                    1.0 / (p[1] - p[0])
+                }
+            }
+        }
+    }
+
+    /// P(X ≤ x).
+    pub fn cdf(&self, p: &[f64], x: f64) -> f64 {
+        match self {
+            Distribution::Normal => {
+                let z = (x - p[0]) / p[1];
+                let tail = 0.5 * gamma_q(0.5, z * z / 2.0);
+                if z < 0.0 {
+                    tail
+                } else {
+                    1.0 - tail
+                }
+            }
+            Distribution::StudentT => {
+                let v = p[0];
+                let tail = 0.5 * beta_i(v / 2.0, 0.5, v / (v + x * x));
+                if x > 0.0 {
+                    1.0 - tail
+                } else {
+                    tail
+                }
+            }
+            Distribution::ChiSquared => gamma_p(p[0] / 2.0, x.max(0.0) / 2.0),
+            Distribution::Binomial => {
+                let (n, prob) = (p[0], p[1]);
+                let k = x.floor();
+                if k < 0.0 {
+                    0.0
+                } else if k >= n {
+                    1.0
+                } else {
+                    beta_i(n - k, k + 1.0, 1.0 - prob)
+                }
+            }
+            Distribution::Poisson => {
+                let k = x.floor();
+                if k < 0.0 {
+                    0.0
+                } else {
+                    gamma_q(k + 1.0, p[0])
+                }
+            }
+            Distribution::Exponential => {
+                if x < 0.0 {
+                    0.0
+                } else {
+                    1.0 - (-p[0] * x).exp()
+                }
+            }
+            Distribution::Uniform => ((x - p[0]) / (p[1] - p[0])).clamp(0.0, 1.0),
+        }
+    }
+
+    /// The smallest x with P(X ≤ x) ≥ q.
+    pub fn inverse(&self, p: &[f64], q: f64) -> f64 {
+        if !(0.0..=1.0).contains(&q) {
+            return f64::NAN;
+        }
+
+        match self {
+            Distribution::Exponential => return -(1.0 - q).ln() / p[0],
+            Distribution::Uniform => return p[0] + q * (p[1] - p[0]),
+            Distribution::Binomial | Distribution::Poisson => {
+                if *self == Distribution::Binomial && q == 1.0 {
+                    return p[0];
+                }
+                if q == 1.0 {
+                    return f64::INFINITY;
+                }
+                let mut hi = 1.0;
+                while self.cdf(p, hi) < q {
+                    hi *= 2.0;
+                }
+                // Binary search for the first integer reaching q.
+                let mut lo = -1.0;
+                while hi - lo > 1.0 {
+                    let mid = ((lo + hi) / 2.0).floor();
+                    if self.cdf(p, mid) >= q {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                    }
+                }
+                return hi;
+            }
+            _ => {}
+        }
+
+        if q == 0.0 {
+            return if *self == Distribution::ChiSquared { 0.0 } else { f64::NEG_INFINITY };
+        }
+        if q == 1.0 {
+            return f64::INFINITY;
+        }
+
+        // Expand a bracket around the quantile, then bisect.
+        let (mut lo, mut hi) = match self {
+            Distribution::Normal => (p[0] - p[1], p[0] + p[1]),
+            Distribution::ChiSquared => (0.0, p[0].max(1.0)),
+            _ => (-1.0, 1.0),
+        };
+        while self.cdf(p, lo) > q {
+            lo -= 2.0 * (hi - lo);
+        }
+        while self.cdf(p, hi) < q {
+            hi += 2.0 * (hi - lo);
+        }
+        for _ in 0..MAX_ITERATIONS {
+            let mid = (lo + hi) / 2.0;
+            if self.cdf(p, mid) < q {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+            if hi - lo <= EPSILON * hi.abs().max(1.0) {
+                break;
+            }
+        }
+        (lo + hi) / 2.0
+    }
+
+    /// P(a < X < b); a missing bound is unbounded.
+    pub fn interval(&self, p: &[f64], a: Option<f64>, b: Option<f64>) -> f64 {
+        let lower = a.map_or(0.0, |a| self.cdf(p, a));
+        let upper = match b {
+            // For discrete distributions exclude the mass at b itself.
+            Some(b) if self.is_discrete() => self.cdf(p, b.ceil() - 1.0),
+            Some(b) => self.cdf(p, b),
+            None => 1.0,
+        };
+        (upper - lower).max(0.0)
+    }
+
+    pub fn mean_variance(&self, p: &[f64]) -> (f64, f64) {
+        match self {
+            Distribution::Normal => (p[0], p[1] * p[1]),
+            Distribution::StudentT => (
+                if p[0] > 1.0 { 0.0 } else { f64::NAN },
+                if p[0] > 2.0 { p[0] / (p[0] - 2.0) } else { f64::INFINITY },
+            ),
+            Distribution::ChiSquared => (p[0], 2.0 * p[0]),
+            Distribution::Binomial => (p[0] * p[1], p[0] * p[1] * (1.0 - p[1])),
+            Distribution::Poisson => (p[0], p[0]),
+            Distribution::Exponential => (1.0 / p[0], 1.0 / (p[0] * p[0])),
+            Distribution::Uniform => ((p[0] + p[1]) / 2.0, (p[1] - p[0]).powi(2) / 12.0),
+        }
+    }
+
+    /// Prefix of the evaluator functions, e.g. `norm` for `normpdf`.
+    fn prefix(&self) -> &'static str {
+        match self {
+            Distribution::Normal => "norm",
+            Distribution::StudentT => "t",
+            Distribution::ChiSquared => "chi2",
+            Distribution::Binomial => "binom",
+            Distribution::Poisson => "poiss",
+            Distribution::Exponential => "exp",
+            Distribution::Uniform => "unif",
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Distribution::Normal => "Normalny",
+                Distribution::StudentT => "t-Studenta",
+                Distribution::ChiSquared => "Chi-kwadrat",
+                Distribution::Binomial => "Dwumianowy",
+                Distribution::Poisson => "Poissona",
+                Distribution::Exponential => "Wykładniczy",
+                Distribution::Uniform => "Jednostajny",
+            }
+        )
+    }
+}
+
+/// Registers `<prefix>pdf(x, ..)`, `<prefix>cdf(x, ..)` and `<prefix>inv(p, ..)`
+/// for every distribution, e.g. `normcdf(1.96)`, `tinv(0.975, 10)` or
+/// `binompdf(3, 10, 0.5)`. The normal distribution defaults to μ = 0, σ = 1.
+pub fn register(ctx: &mut Context) {
+    type Function = fn(&Distribution, &[f64], f64) -> f64;
+    let functions: [(&str, Function); 3] =
+        [("pdf", Distribution::pdf), ("cdf", Distribution::cdf), ("inv", Distribution::inverse)];
+
+    for dist in Distribution::ALL {
+        let count = dist.parameters().len();
+        let min_args = if dist == Distribution::Normal { 1 } else { count + 1 };
+
+        for (suffix, function) in functions {
+            let name = format!("{}{}", dist.prefix(), suffix);
+            let owned = name.clone();
+            ctx.func(&name, move |args| {
+                if !(min_args..=count + 1).contains(&args.len()) {
+                    return Err(format!("Zła liczba argumentów funkcji {}", owned));
+                }
+                let a = args.iter().map(Value::as_number).collect::<Result<Vec<_>, _>>()?;
+                let mut params = vec![0.0, 1.0];
+                params.truncate(count);
+                params[..a.len() - 1].copy_from_slice(&a[1..]);
+                dist.validate(&params)?;
+                if suffix == "inv" && !(0.0..=1.0).contains(&a[0]) {
+                    return Err("Prawdopodobieństwo musi należeć do przedziału [0, 1]".to_string());
+                }
+                Ok(Value::Num(function(&dist, &params, a[0])))
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DistributionField {
+    Parameter(usize),
+    Lower,
+    Upper,
+    Probability,
+}
+
+#[derive(Debug, Clone)]
+pub struct DistributionResult {
+    pub interval: f64,
+    pub quantile: Option<f64>,
+    pub mean: f64,
+    pub variance: f64,
+}
+
+pub struct DistributionForm {
+    pub distribution: Distribution,
+    pub parameters: Vec<String>,
+    pub lower: String,
+    pub upper: String,
+    pub probability: String,
+    pub result: Option<Result<DistributionResult, String>>,
+}
+
+impl Default for DistributionForm {
+    fn default() -> Self {
+        let mut form = Self {
+            distribution: Distribution::Normal,
+            parameters: Vec::new(),
+            lower: String::new(),
+            upper: String::new(),
+            probability: String::new(),
+            result: None,
+        };
+        form.select(Distribution::Normal);
+        form
+    }
+}
+
+impl DistributionForm {
+    /// Switches the distribution and resets its parameters to the defaults.
+    pub fn select(&mut self, distribution: Distribution) {
+        self.distribution = distribution;
+        self.parameters = distribution.parameters().iter().map(|(_, default)| default.to_string()).collect();
+        self.result = None;
+    }
+
+    pub fn field_mut(&mut self, field: DistributionField) -> &mut String {
+        match field {
+            DistributionField::Parameter(index) => &mut self.parameters[index],
+            DistributionField::Lower => &mut self.lower,
+            DistributionField::Upper => &mut self.upper,
+            DistributionField::Probability => &mut self.probability,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<f64, String> {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        ctx.eval_str(input)?.as_number()
+    }
+
+    fn close(input: &str, expected: f64, tolerance: f64) -> bool {
+        eval(input).is_ok_and(|value| (value - expected).abs() <= tolerance)
+    }
+
+    #[test]
+    fn special_functions() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+        assert!((gamma_p(1.0, 2.0) - (1.0 - (-2f64).exp())).abs() < 1e-12);
+        assert!((beta_i(2.0, 3.0, 0.4) - 0.5248).abs() < 1e-12);
+    }
+
+    #[test]
+    fn table_values() {
+        assert!(close("normcdf(1.96)", 0.975_002, 1e-6));
+        assert!(close("norminv(0.975)", 1.959_964, 1e-6));
+        assert!(close("normpdf(10, 10, 2)", 0.199_471, 1e-6));
+        assert!(close("tinv(0.975, 10)", 2.228_139, 1e-6));
+        assert!(close("chi2cdf(3.841459, 1)", 0.95, 1e-6));
+        assert!(close("poisspdf(2, 3)", 0.224_042, 1e-6));
+        assert!(close("expcdf(1, 1)", 1.0 - (-1f64).exp(), 1e-12));
+        assert!(close("unifcdf(0.25, 0, 1)", 0.25, 1e-12));
+    }
+
+    #[test]
+    fn discrete_distributions() {
+        assert!(close("binompdf(3, 10, 0.5)", 0.117_187_5, 1e-12));
+        assert!(close("binomcdf(3, 10, 0.5)", 0.171_875, 1e-12));
+        assert!(close("binominv(0.5, 10, 0.5)", 5.0, 0.0));
+        let p = [10.0, 0.5];
+        assert!((Distribution::Binomial.interval(&p, Some(3.0), Some(5.0)) - 0.205_078_125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn invalid_parameters_are_errors() {
+        assert!(eval("normcdf(0, 0, 0)").is_err());
+        assert!(eval("binompdf(1, 10, 1.5)").is_err());
+        assert!(eval("binompdf(1, 2.5, 0.5)").is_err());
+        assert!(eval("unifpdf(0, 1, 1)").is_err());
+        assert!(eval("norminv(1.5)").is_err());
+        assert!(eval("tcdf(1)").is_err());
+    }
+}
@@ -0,0 +1,320 @@
+// Exports the plot to SVG and PNG. The SVG is built from the sampled curves
+// and the PNG is rendered from it on the CPU, so no window or GPU is needed.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use resvg::{tiny_skia, usvg};
+
+use crate::plot::{grid_lines, grid_step, CurveKind, PlotForm};
+
+pub type Rgb = [f32; 3];
+
+/// Colors of the exported image, taken from the calculator theme.
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    pub marker: Rgb,
+    pub curves: Vec<Rgb>,
+}
+
+impl Palette {
+    fn curve(&self, index: usize) -> String {
+        hex(self.curves[index % self.curves.len()])
+    }
+}
+
+fn hex(color: Rgb) -> String {
+    let [r, g, b] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Decimals needed to print values on a grid with the given step.
+fn tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+/// Renders the plot as an SVG document of `width` × `height` pixels.
+pub fn svg(plot: &PlotForm, palette: &Palette, width: u32, height: u32) -> String {
+    let viewport = &plot.viewport;
+    let (w, h) = (width as f32, height as f32);
+    let point = |x: f64, y: f64| viewport.screen_point(x, y, w, h);
+    let foreground = hex(palette.foreground);
+    // Text and lines scale with the image so labels stay readable in large exports.
+    let scale = (w.min(h) / 300.0).max(1.0);
+    let font = 11.0 * scale;
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Segoe UI, DejaVu Sans, Liberation Sans, Arial, sans-serif">"#
+    );
+    let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="{}"/>"#, hex(palette.background));
+
+    // Grid, axes and tick labels
+    let (origin_x, origin_y) = point(0.0, 0.0);
+    let label_y = origin_y.clamp(0.0, h - font - 4.0);
+    let label_x = origin_x.clamp(0.0, w - 4.0 * font);
+
+    let x_step = grid_step(viewport.width(), 8.0);
+    for x in grid_lines(viewport.x_min, viewport.x_max, x_step) {
+        let px = point(x, 0.0).0;
+        let opacity = if x == 0.0 { 0.7 } else { 0.15 };
+        let _ = writeln!(
+            out,
+            r#"<line x1="{px}" y1="0" x2="{px}" y2="{h}" stroke="{foreground}" stroke-opacity="{opacity}" stroke-width="{scale}"/>"#
+        );
+        if x != 0.0 {
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-size="{font}" fill="{foreground}" fill-opacity="0.7">{}</text>"#,
+                px + 2.0,
+                label_y + font + 2.0,
+                tick(x, x_step)
+            );
+        }
+    }
+
+    let y_step = grid_step(viewport.height(), 6.0);
+    for y in grid_lines(viewport.y_min, viewport.y_max, y_step) {
+        let py = point(0.0, y).1;
+        let opacity = if y == 0.0 { 0.7 } else { 0.15 };
+        let _ = writeln!(
+            out,
+            r#"<line x1="0" y1="{py}" x2="{w}" y2="{py}" stroke="{foreground}" stroke-opacity="{opacity}" stroke-width="{scale}"/>"#
+        );
+        if y != 0.0 {
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-size="{font}" fill="{foreground}" fill-opacity="0.7">{}</text>"#,
+                label_x + 2.0,
+                py + font + 2.0,
+                tick(y, y_step)
+            );
+        }
+    }
+
+    // Integral area and analysis markers
+    if let Some(Ok(result)) = &plot.analysis.result {
+        if let Some(area) = &result.area {
+            for segment in plot.curves.get(area.curve).map_or(&[][..], |c| &c.segments[..]) {
+                let inside: Vec<_> = segment.iter().filter(|(x, _)| *x >= area.from && *x <= area.to).collect();
+                let (Some(first), Some(last)) = (inside.first(), inside.last()) else {
+                    continue;
+                };
+                let mut d = format!("M{:.2},{:.2}", point(first.0, 0.0).0, point(first.0, 0.0).1);
+                for &&(x, y) in &inside {
+                    let (px, py) = point(x, y);
+                    let _ = write!(d, " L{:.2},{:.2}", px, py);
+                }
+                let _ = write!(d, " L{:.2},{:.2} Z", point(last.0, 0.0).0, point(last.0, 0.0).1);
+                let _ = writeln!(out, r#"<path d="{d}" fill="{}" fill-opacity="0.3"/>"#, palette.curve(area.curve));
+            }
+        }
+    }
+
+    // Curves
+    for (index, curve) in plot.curves.iter().enumerate() {
+        for segment in &curve.segments {
+            let mut d = String::new();
+            for (i, &(x, y)) in segment.iter().enumerate() {
+                let (px, py) = point(x, y);
+                let _ = write!(d, "{}{:.2},{:.2}", if i == 0 { "M" } else { " L" }, px, py);
+            }
+            let _ = writeln!(
+                out,
+                r#"<path d="{d}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                palette.curve(index),
+                2.0 * scale
+            );
+        }
+    }
+
+    if let Some(Ok(result)) = &plot.analysis.result {
+        for marker in &result.markers {
+            let (px, py) = point(marker.x, marker.y);
+            let _ = writeln!(
+                out,
+                r#"<circle cx="{px}" cy="{py}" r="{}" fill="{}" stroke="{foreground}" stroke-width="{}"/>"#,
+                4.0 * scale,
+                hex(palette.marker),
+                scale
+            );
+        }
+    }
+
+    // Legend on a translucent box in the top-left corner
+    let legend_font = 13.0 * scale;
+    let entries: Vec<(usize, String)> = plot
+        .curves
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.input.trim().is_empty())
+        .map(|(index, curve)| {
+            let kind = match curve.kind {
+                CurveKind::Function => "",
+                CurveKind::Parametric => " (param.)",
+                CurveKind::Polar => " (bieg.)",
+            };
+            (index, format!("f{}: {}{}", index + 1, curve.input.trim(), kind))
+        })
+        .collect();
+    if !entries.is_empty() {
+        let longest = entries.iter().map(|(_, e)| e.chars().count()).max().unwrap_or(0) as f32;
+        let _ = writeln!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.8" rx="{}"/>"#,
+            4.0 * scale,
+            4.0 * scale,
+            longest * legend_font * 0.62 + 8.0 * scale,
+            entries.len() as f32 * (legend_font + 4.0) + 6.0 * scale,
+            hex(palette.background),
+            4.0 * scale
+        );
+    }
+    for (row, (index, entry)) in entries.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="{legend_font}" fill="{}">{}</text>"#,
+            8.0 * scale,
+            4.0 * scale + (row as f32 + 1.0) * (legend_font + 4.0) - 2.0,
+            palette.curve(*index),
+            escape(entry)
+        );
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Adds `extension` unless the path already ends with it.
+pub fn with_extension(path: &str, extension: &str) -> PathBuf {
+    let path = Path::new(path.trim());
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case(extension)) {
+        path.to_path_buf()
+    } else {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".");
+        name.push(extension);
+        PathBuf::from(name)
+    }
+}
+
+pub fn save_svg(svg: &str, path: &Path) -> Result<(), String> {
+    fs::write(path, svg).map_err(|e| format!("Nie udało się zapisać: {}", e))
+}
+
+/// Rasterizes the SVG at its own size and writes a PNG.
+pub fn save_png(svg: &str, path: &Path) -> Result<(), String> {
+    // Labels are drawn with the system fonts.
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| format!("Błąd SVG: {}", e))?;
+
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).ok_or("Niepoprawny rozmiar obrazu")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.save_png(path).map_err(|e| format!("Nie udało się zapisać: {}", e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportField {
+    Path,
+    Width,
+    Height,
+}
+
+pub struct ExportForm {
+    pub path: String,
+    pub width: String,
+    pub height: String,
+    pub status: Option<String>,
+}
+
+impl Default for ExportForm {
+    fn default() -> Self {
+        Self {
+            path: String::from("wykres"),
+            width: String::from("1200"),
+            height: String::from("800"),
+            status: None,
+        }
+    }
+}
+
+impl ExportForm {
+    pub fn field_mut(&mut self, field: ExportField) -> &mut String {
+        match field {
+            ExportField::Path => &mut self.path,
+            ExportField::Width => &mut self.width,
+            ExportField::Height => &mut self.height,
+        }
+    }
+
+    /// Image size in pixels, limited to 10000 × 10000.
+    pub fn size(&self) -> Result<(u32, u32), String> {
+        let parse = |value: &str| match value.trim().parse::<u32>() {
+            Ok(size) if (16..=10_000).contains(&size) => Ok(size),
+            _ => Err("Rozmiar obrazu musi wynosić od 16 do 10000 pikseli".to_string()),
+        };
+        Ok((parse(&self.width)?, parse(&self.height)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::{sample, Curve};
+
+    fn palette() -> Palette {
+        Palette { background: [1.0, 1.0, 1.0], foreground: [0.0, 0.0, 0.0], marker: [1.0, 0.0, 0.0], curves: vec![[0.0, 0.5, 1.0]] }
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(hex([1.0, 0.5, 0.0]), "#ff8000");
+        assert_eq!(escape("x<1 & y>2"), "x&lt;1 &amp; y&gt;2");
+        assert_eq!(tick(2.0, 2.0), "2");
+        assert_eq!(tick(0.25, 0.05), "0.25");
+        assert_eq!(with_extension("wykres", "png"), PathBuf::from("wykres.png"));
+        assert_eq!(with_extension(" a.PNG ", "png"), PathBuf::from("a.PNG"));
+    }
+
+    #[test]
+    fn image_size_is_limited() {
+        let mut form = ExportForm::default();
+        assert_eq!(form.size(), Ok((1200, 800)));
+        form.width = "10001".to_string();
+        assert!(form.size().is_err());
+    }
+
+    #[test]
+    fn svg_renders_to_png() {
+        let mut plot = PlotForm { curves: vec![Curve::new("y = x < 1")], ..Default::default() };
+        plot.curves[0].segments = sample(f64::sin, &plot.viewport, 100);
+
+        let svg = svg(&plot, &palette(), 300, 200);
+        assert!(svg.contains("<path d=\"M"));
+        assert!(svg.contains("f1: y = x &lt; 1"));
+
+        let path = std::env::temp_dir().join(format!("astralcalc-test-{}.png", std::process::id()));
+        save_png(&svg, &path).unwrap();
+        let png = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
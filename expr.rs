@@ -0,0 +1,858 @@
+// Expression language behind the display: numbers, lists (also used as
+// vectors and matrices), the usual operators with implicit multiplication
+// (`2π`, `3(x+1)`), variables and functions registered on a `Context`.
+// Functions such as `solve` receive their arguments unevaluated so they can
+// bind a variable of their own.
+
+use std::collections::HashMap;
+use std::f64::consts::{E, PI};
+use std::fmt;
+use std::ops::RangeBounds;
+
+use crate::matrix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Num(f64),
+    List(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_number(&self) -> Result<f64, String> {
+        match self {
+            Value::Num(n) => Ok(*n),
+            Value::List(_) => Err("Oczekiwano liczby, a nie listy".to_string()),
+        }
+    }
+
+    pub fn as_list(&self) -> Result<&[Value], String> {
+        match self {
+            Value::List(items) => Ok(items),
+            Value::Num(_) => Err("Oczekiwano listy [..]".to_string()),
+        }
+    }
+
+    /// A flat list of numbers, e.g. cash flows.
+    pub fn as_numbers(&self) -> Result<Vec<f64>, String> {
+        self.as_list()?.iter().map(Value::as_number).collect()
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(n) => write!(f, "{}", n),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Var(String),
+    List(Vec<Expr>),
+    Neg(Box<Expr>),
+    /// `x%`: x/100 on its own, a share of the left operand after `+`/`-`.
+    Percent(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    /// `lhs = rhs`, only meaningful to the equation solver.
+    Equation(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Names of the variables used, in order of first appearance.
+    pub fn variables(&self) -> Vec<&str> {
+        fn walk<'a>(expr: &'a Expr, out: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Num(_) => {}
+                Expr::Var(name) => {
+                    if !out.contains(&name.as_str()) {
+                        out.push(name);
+                    }
+                }
+                Expr::List(items) | Expr::Call(_, items) => items.iter().for_each(|item| walk(item, out)),
+                Expr::Neg(inner) | Expr::Percent(inner) => walk(inner, out),
+                Expr::Binary(_, lhs, rhs) | Expr::Equation(lhs, rhs) => {
+                    walk(lhs, out);
+                    walk(rhs, out);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        walk(self, &mut out);
+        out
+    }
+
+    /// The name of a plain variable, as passed to `solve(.., x, ..)`.
+    pub fn as_variable(&self) -> Result<&str, String> {
+        match self {
+            Expr::Var(name) => Ok(name),
+            _ => Err("Oczekiwano nazwy zmiennej".to_string()),
+        }
+    }
+}
+
+/// Unit of the angles taken by sin/cos/tan and returned by their inverses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleMode {
+    #[default]
+    Radians,
+    Degrees,
+}
+
+impl AngleMode {
+    pub fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => angle,
+            AngleMode::Degrees => angle.to_radians(),
+        }
+    }
+
+    pub fn radians_to_unit(self, angle: f64) -> f64 {
+        match self {
+            AngleMode::Radians => angle,
+            AngleMode::Degrees => angle.to_degrees(),
+        }
+    }
+
+    /// A full turn in this unit: 2π or 360.
+    pub fn full_turn(self) -> f64 {
+        self.radians_to_unit(2.0 * PI)
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            AngleMode::Radians => AngleMode::Degrees,
+            AngleMode::Degrees => AngleMode::Radians,
+        }
+    }
+}
+
+impl fmt::Display for AngleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if *self == AngleMode::Degrees { "DEG" } else { "RAD" })
+    }
+}
+
+/// sin of an angle in degrees, exact at multiples of 30° and 90°.
+fn sin_degrees(angle: f64) -> f64 {
+    let reduced = angle.rem_euclid(360.0);
+    let sign = if reduced < 180.0 { 1.0 } else { -1.0 };
+    let within = reduced % 180.0;
+    if within == 0.0 {
+        0.0
+    } else if within == 90.0 {
+        sign
+    } else if within == 30.0 || within == 150.0 {
+        sign * 0.5
+    } else {
+        reduced.to_radians().sin()
+    }
+}
+
+/// tan of an angle in degrees, exact at multiples of 45° and undefined at 90° + k·180°.
+fn tan_degrees(angle: f64) -> f64 {
+    let reduced = angle.rem_euclid(180.0);
+    if reduced == 0.0 {
+        0.0
+    } else if reduced == 45.0 {
+        1.0
+    } else if reduced == 90.0 {
+        f64::NAN
+    } else if reduced == 135.0 {
+        -1.0
+    } else {
+        reduced.to_radians().tan()
+    }
+}
+
+type Function = Box<dyn Fn(&[Value]) -> Result<Value, String>>;
+type Form = Box<dyn Fn(&Scope, &[Expr]) -> Result<Value, String>>;
+
+pub struct Context {
+    vars: HashMap<String, Value>,
+    funcs: HashMap<String, Function>,
+    forms: HashMap<String, Form>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Context {
+    /// A context with the constants and functions the calculator always had.
+    pub fn new() -> Self {
+        let mut ctx = Self::empty();
+        ctx.var("pi", PI);
+        ctx.var("π", PI);
+        ctx.var("e", E);
+
+        let unary = [
+            ("sqrt", f64::sqrt as fn(f64) -> f64),
+            ("exp", f64::exp),
+            ("ln", f64::ln),
+            ("log", f64::log10),
+            ("abs", f64::abs),
+            ("sin", f64::sin),
+            ("cos", f64::cos),
+            ("tan", f64::tan),
+            ("asin", f64::asin),
+            ("acos", f64::acos),
+            ("atan", f64::atan),
+            ("sinh", f64::sinh),
+            ("cosh", f64::cosh),
+            ("tanh", f64::tanh),
+            ("asinh", f64::asinh),
+            ("acosh", f64::acosh),
+            ("atanh", f64::atanh),
+            ("floor", f64::floor),
+            ("ceil", f64::ceil),
+            ("round", f64::round),
+        ];
+        for (name, f) in unary {
+            ctx.funcn(name, move |a| f(a[0]), 1..=1);
+        }
+
+        ctx.funcn("signum", |a| a[0].signum(), 1..=1);
+        ctx.funcn("atan2", |a| a[0].atan2(a[1]), 2..=2);
+        ctx.funcn("mod", |a| a[0] % a[1], 2..=2);
+        ctx.func("max", |args| extremum(args, f64::max));
+        ctx.func("min", |args| extremum(args, f64::min));
+        ctx
+    }
+
+    /// Switches the trigonometric functions to take and return angles in `mode`.
+    pub fn set_angle_mode(&mut self, mode: AngleMode) -> &mut Self {
+        match mode {
+            AngleMode::Radians => {
+                self.funcn("sin", |a| a[0].sin(), 1..=1);
+                self.funcn("cos", |a| a[0].cos(), 1..=1);
+                self.funcn("tan", |a| a[0].tan(), 1..=1);
+            }
+            AngleMode::Degrees => {
+                self.funcn("sin", |a| sin_degrees(a[0]), 1..=1);
+                self.funcn("cos", |a| sin_degrees(a[0] + 90.0), 1..=1);
+                self.funcn("tan", |a| tan_degrees(a[0]), 1..=1);
+            }
+        }
+
+        let inverse = [("asin", f64::asin as fn(f64) -> f64), ("acos", f64::acos), ("atan", f64::atan)];
+        for (name, f) in inverse {
+            self.funcn(name, move |a| mode.radians_to_unit(f(a[0])), 1..=1);
+        }
+        self.funcn("atan2", move |a| mode.radians_to_unit(a[0].atan2(a[1])), 2..=2);
+        self
+    }
+
+    pub fn empty() -> Self {
+        Self { vars: HashMap::new(), funcs: HashMap::new(), forms: HashMap::new() }
+    }
+
+    pub fn var(&mut self, name: &str, value: f64) -> &mut Self {
+        self.define(name, Value::Num(value))
+    }
+
+    /// Binds a variable to any value, e.g. a matrix.
+    pub fn define(&mut self, name: &str, value: Value) -> &mut Self {
+        self.vars.insert(name.to_string(), value);
+        self
+    }
+
+    /// Adds a function over arbitrary values (numbers or lists).
+    pub fn func<F>(&mut self, name: &str, func: F) -> &mut Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + 'static,
+    {
+        self.funcs.insert(name.to_string(), Box::new(func));
+        self
+    }
+
+    /// Adds a numeric function accepting `n_args` arguments.
+    pub fn funcn<F, R>(&mut self, name: &str, func: F, n_args: R) -> &mut Self
+    where
+        F: Fn(&[f64]) -> f64 + 'static,
+        R: RangeBounds<usize> + 'static,
+    {
+        let owned = name.to_string();
+        self.func(name, move |args| {
+            if !n_args.contains(&args.len()) {
+                return Err(format!("Zła liczba argumentów funkcji {}", owned));
+            }
+            let numbers = args.iter().map(Value::as_number).collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Num(func(&numbers)))
+        })
+    }
+
+    /// Adds a function taking its arguments unevaluated, for bound variables.
+    pub fn form<F>(&mut self, name: &str, form: F) -> &mut Self
+    where
+        F: Fn(&Scope, &[Expr]) -> Result<Value, String> + 'static,
+    {
+        self.forms.insert(name.to_string(), Box::new(form));
+        self
+    }
+
+    pub fn eval(&self, expr: &Expr) -> Result<Value, String> {
+        Scope::new(self).eval(expr)
+    }
+
+    pub fn eval_str(&self, input: &str) -> Result<Value, String> {
+        self.eval(&parse(input)?)
+    }
+}
+
+/// A context plus variables bound by an enclosing `solve(..)` or similar.
+pub struct Scope<'a> {
+    ctx: &'a Context,
+    locals: Vec<(String, Value)>,
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(ctx: &'a Context) -> Self {
+        Self { ctx, locals: Vec::new() }
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.locals.iter().any(|(local, _)| local == name) || self.ctx.vars.contains_key(name)
+    }
+
+    /// `body` as a function of `var`, which shadows any variable of that name.
+    pub fn function<'s>(&'s self, body: &'s Expr, var: &str) -> impl FnMut(f64) -> Result<f64, String> + 's {
+        let mut inner = Scope { ctx: self.ctx, locals: self.locals.clone() };
+        inner.locals.push((var.to_string(), Value::Num(0.0)));
+        move |x| {
+            if let Some(local) = inner.locals.last_mut() {
+                local.1 = Value::Num(x);
+            }
+            inner.eval(body)?.as_number()
+        }
+    }
+
+    pub fn eval(&self, expr: &Expr) -> Result<Value, String> {
+        match expr {
+            Expr::Num(n) => Ok(Value::Num(*n)),
+            Expr::Var(name) => self
+                .locals
+                .iter()
+                .rev()
+                .find(|(local, _)| local == name)
+                .map(|(_, value)| value)
+                .or_else(|| self.ctx.vars.get(name))
+                .cloned()
+                .ok_or_else(|| format!("Nieznana zmienna: {}", name)),
+            Expr::List(items) => Ok(Value::List(
+                items.iter().map(|item| self.eval(item)).collect::<Result<_, _>>()?,
+            )),
+            Expr::Neg(inner) => matrix::negate(&self.eval(inner)?),
+            Expr::Percent(inner) => Ok(Value::Num(self.eval(inner)?.as_number()? / 100.0)),
+            // Business-calculator percentages: 200 + 10% = 220, 200 - 10% = 180.
+            Expr::Binary(op @ (Op::Add | Op::Sub), lhs, rhs) if is_percentage(rhs) => {
+                let a = self.eval(lhs)?.as_number()?;
+                let share = a * self.eval(rhs)?.as_number()?;
+                Ok(Value::Num(if *op == Op::Add { a + share } else { a - share }))
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = match (self.eval(lhs)?, self.eval(rhs)?) {
+                    (Value::Num(a), Value::Num(b)) => (a, b),
+                    (a, b) => return matrix::binary(*op, &a, &b),
+                };
+                Ok(Value::Num(match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    Op::Pow => a.powf(b),
+                }))
+            }
+            Expr::Call(name, args) => match self.ctx.forms.get(name) {
+                Some(form) => form(self, args),
+                None => self.call(name, args),
+            },
+            Expr::Equation(..) => Err("Równanie można tylko rozwiązać: solve(.., x)".to_string()),
+        }
+    }
+
+    /// Calls an ordinary function, bypassing any form of the same name, so a
+    /// form can hand over the calls it does not handle itself.
+    pub fn call(&self, name: &str, args: &[Expr]) -> Result<Value, String> {
+        let func = self
+            .ctx
+            .funcs
+            .get(name)
+            .ok_or_else(|| format!("Nieznana funkcja: {}", name))?;
+        let args = args.iter().map(|arg| self.eval(arg)).collect::<Result<Vec<_>, _>>()?;
+        func(&args)
+    }
+}
+
+// max/min over numbers and lists alike: `max(1, 2)`, `max([1, 2, 3])`.
+fn extremum(args: &[Value], pick: fn(f64, f64) -> f64) -> Result<Value, String> {
+    let mut numbers = Vec::new();
+    for arg in args {
+        match arg {
+            Value::Num(n) => numbers.push(*n),
+            Value::List(_) => numbers.extend(arg.as_numbers()?),
+        }
+    }
+
+    numbers
+        .into_iter()
+        .reduce(pick)
+        .map(Value::Num)
+        .ok_or_else(|| "Brak argumentów".to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+fn superscript_digit(c: char) -> Option<u32> {
+    "⁰¹²³⁴⁵⁶⁷⁸⁹".chars().position(|s| s == c).map(|d| d as u32)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Exponent, only when followed by digits: `1e-3`, not `2e` (2·e).
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let literal: String = chars[start..i].iter().collect();
+                let value = literal
+                    .parse()
+                    .map_err(|_| format!("Niepoprawna liczba: {}", literal))?;
+                tokens.push(Token::Num(value));
+            }
+            '√' => {
+                tokens.push(Token::Ident("sqrt".to_string()));
+                i += 1;
+            }
+            // Factorizations are shown as `2³·3·5`.
+            '·' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            c if superscript_digit(c).is_some() => {
+                let mut exponent = 0.0;
+                while let Some(digit) = chars.get(i).and_then(|c| superscript_digit(*c)) {
+                    exponent = exponent * 10.0 + digit as f64;
+                    i += 1;
+                }
+                tokens.push(Token::Op('^'));
+                tokens.push(Token::Num(exponent));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' | '=' => {
+                // `**` is accepted as a synonym for `^`.
+                if c == '*' && chars.get(i + 1) == Some(&'*') {
+                    tokens.push(Token::Op('^'));
+                    i += 2;
+                } else {
+                    tokens.push(Token::Op(c));
+                    i += 1;
+                }
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                let continues = |c: char| (c.is_alphanumeric() && superscript_digit(c).is_none()) || c == '_';
+                while i < chars.len() && continues(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => return Err(format!("Nieoczekiwany znak: {}", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect_close(&mut self) -> Result<(), String> {
+        match self.next() {
+            Some(Token::RParen) => Ok(()),
+            _ => Err("Brak nawiasu zamykającego".to_string()),
+        }
+    }
+
+    // equation := conversion ('=' conversion)?
+    fn equation(&mut self) -> Result<Expr, String> {
+        let lhs = self.conversion()?;
+        if let Some(Token::Op('=')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.conversion()?;
+            return Ok(Expr::Equation(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    // conversion := sum (('in' | 'to' | 'na') unit)?
+    fn conversion(&mut self) -> Result<Expr, String> {
+        let expr = self.sum()?;
+        let keyword = match self.peek() {
+            Some(Token::Ident(keyword)) if is_keyword(keyword) => keyword.clone(),
+            _ => return Ok(expr),
+        };
+        self.pos += 1;
+        match self.next() {
+            Some(Token::Ident(unit)) => Ok(Expr::Binary(Op::Div, Box::new(expr), Box::new(Expr::Var(unit)))),
+            _ => Err(format!("Oczekiwano jednostki po '{}'", keyword)),
+        }
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.product()?;
+        while let Some(Token::Op(c @ ('+' | '-'))) = self.peek() {
+            let op = if *c == '+' { Op::Add } else { Op::Sub };
+            self.pos += 1;
+            let rhs = self.product()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // product := leading (('*' | '/') unary | implicit unary)*
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.leading()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op('*')) => Op::Mul,
+                Some(Token::Op('/')) => Op::Div,
+                // Implicit multiplication: `2π`, `2(x+1)`, `(a)(b)`.
+                Some(Token::Ident(name)) if is_keyword(name) => break,
+                Some(Token::Ident(_) | Token::LParen) => {
+                    let rhs = self.power()?;
+                    lhs = Expr::Binary(Op::Mul, Box::new(lhs), Box::new(rhs));
+                    continue;
+                }
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // leading := ('-' | '+') leading | mixed | unary
+    //
+    // A mixed number as shown in fraction mode, `1 1/2` = 1 + 1/2, is only
+    // recognised at the start of a term, so `2^1 1/2` is not 2^1.5.
+    fn leading(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.leading()?)))
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.leading()
+            }
+            _ => match self.tokens.get(self.pos..).unwrap_or_default() {
+                [Token::Num(whole), Token::Num(num), Token::Op('/'), Token::Num(den), ..]
+                    if [whole, num, den].iter().all(|v| v.fract() == 0.0) =>
+                {
+                    let fraction = Expr::Binary(Op::Div, Box::new(Expr::Num(*num)), Box::new(Expr::Num(*den)));
+                    let mixed = Expr::Binary(Op::Add, Box::new(Expr::Num(*whole)), Box::new(fraction));
+                    self.pos += 4;
+                    Ok(mixed)
+                }
+                _ => self.unary(),
+            },
+        }
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := primary '%'? ('^' unary)?   (right-associative, -2^2 = -4)
+    fn power(&mut self) -> Result<Expr, String> {
+        let mut base = self.primary()?;
+        if let Some(Token::Op('%')) = self.peek() {
+            self.pos += 1;
+            base = Expr::Percent(Box::new(base));
+        }
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(Op::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Ident(name)) => {
+                if let Some(Token::LParen) = self.peek() {
+                    self.pos += 1;
+                    let args = self.arguments(Token::RParen)?;
+                    Ok(Expr::Call(name, args))
+                } else {
+                    Ok(Expr::Var(name))
+                }
+            }
+            Some(Token::LParen) => {
+                let inner = self.conversion()?;
+                self.expect_close()?;
+                Ok(inner)
+            }
+            Some(Token::LBracket) => Ok(Expr::List(self.arguments(Token::RBracket)?)),
+            Some(token) => Err(format!("Nieoczekiwany symbol: {:?}", token)),
+            None => Err("Niekompletne wyrażenie".to_string()),
+        }
+    }
+
+    // Comma-separated expressions up to the closing token, which is consumed.
+    fn arguments(&mut self, close: Token) -> Result<Vec<Expr>, String> {
+        let mut items = Vec::new();
+        if self.peek() == Some(&close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.equation()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(token) if token == close => return Ok(items),
+                _ => return Err("Brak nawiasu zamykającego".to_string()),
+            }
+        }
+    }
+}
+
+/// Whether a term is a share of the left operand of `+`/`-`: a percentage,
+/// possibly scaled as in `10% * 2` or `-5%`. A percentage inside a function
+/// argument or other operand is just a number, as in `fv(5%, ...)`.
+fn is_percentage(expr: &Expr) -> bool {
+    match expr {
+        Expr::Percent(_) => true,
+        Expr::Neg(inner) => is_percentage(inner),
+        Expr::Binary(Op::Mul, lhs, rhs) => is_percentage(lhs) || is_percentage(rhs),
+        Expr::Binary(Op::Div, lhs, _) => is_percentage(lhs),
+        _ => false,
+    }
+}
+
+// Conversion keywords: `100 EUR in USD` divides by the value of `USD`.
+fn is_keyword(name: &str) -> bool {
+    matches!(name, "in" | "to" | "na")
+}
+
+pub fn parse(input: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0 };
+    let expr = parser.equation()?;
+    if parser.pos < parser.tokens.len() {
+        return Err("Nadmiarowe symbole na końcu wyrażenia".to_string());
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        let mut ctx = Context::new();
+        ctx.var("x", 3.0).var("EUR", 4.0).var("PLN", 1.0);
+        ctx.eval_str(input).unwrap().as_number().unwrap()
+    }
+
+    fn fails(input: &str) -> bool {
+        Context::new().eval_str(input).is_err()
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval("2+3*4"), 14.0);
+        assert_eq!(eval("(2+3)*4"), 20.0);
+        assert_eq!(eval("10-4-3"), 3.0);
+        assert_eq!(eval("8/4/2"), 1.0);
+        assert_eq!(eval("2^3^2"), 512.0);
+        assert_eq!(eval("2*3^2"), 18.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-2^2"), -4.0);
+        assert_eq!(eval("(-2)^2"), 4.0);
+        assert_eq!(eval("2^-1"), 0.5);
+        assert_eq!(eval("2*-3"), -6.0);
+        assert_eq!(eval("--3"), 3.0);
+        assert_eq!(eval("-x^2"), -9.0);
+    }
+
+    #[test]
+    fn implicit_multiplication() {
+        assert_eq!(eval("2π"), 2.0 * PI);
+        assert_eq!(eval("2(3+1)"), 8.0);
+        assert_eq!(eval("(1+1)(2+1)"), 6.0);
+        assert_eq!(eval("3x"), 9.0);
+        assert_eq!(eval("2x^2"), 18.0);
+        assert_eq!(eval("2sqrt(16)"), 8.0);
+    }
+
+    #[test]
+    fn superscripts_and_middle_dot() {
+        // A factorization shown on the display evaluates back to the number.
+        assert_eq!(eval("2³·3²·5"), 360.0);
+        assert_eq!(eval("x²"), 9.0);
+        assert_eq!(eval("2·x"), 6.0);
+    }
+
+    #[test]
+    fn functions_and_lists() {
+        assert_eq!(eval("max(1, 5, 3)"), 5.0);
+        assert_eq!(eval("atan2(0, -1)"), PI);
+        assert_eq!(
+            Context::new().eval_str("[1, 2+3]"),
+            Ok(Value::List(vec![Value::Num(1.0), Value::Num(5.0)]))
+        );
+    }
+
+    #[test]
+    fn conversion_keywords() {
+        assert_eq!(eval("100 EUR in PLN"), 400.0);
+        assert_eq!(eval("100 EUR to PLN"), 400.0);
+        assert_eq!(eval("100 PLN na EUR"), 25.0);
+        assert_eq!(eval("(100 EUR in PLN) * 2"), 800.0);
+        assert_eq!(eval("max(1 EUR na PLN, 2)"), 4.0);
+        assert!(parse("100 EUR in").is_err());
+        assert!(parse("100 EUR in 5").is_err());
+    }
+
+    #[test]
+    fn mixed_numbers_start_a_term() {
+        assert_eq!(eval("1 1/2"), 1.5);
+        assert_eq!(eval("-2 1/4"), -2.25);
+        assert_eq!(eval("3 + 1 1/2"), 4.5);
+        assert_eq!(eval("(1 1/2) * 2"), 3.0);
+        assert!(fails("2^1 1/2"));
+        assert!(fails("2 * 1 1/2"));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert!(fails("2+"));
+        assert!(fails("(2"));
+        assert!(fails("2)"));
+        assert!(fails("sqrt(1, 2)"));
+        assert!(fails("nieznana(1)"));
+        assert!(fails("y + 1"));
+    }
+
+    #[test]
+    fn degree_mode() {
+        let mut ctx = Context::new();
+        ctx.set_angle_mode(AngleMode::Degrees);
+        let eval = |input: &str| ctx.eval_str(input).unwrap().as_number().unwrap();
+        assert_eq!(eval("sin(30)"), 0.5);
+        assert_eq!(eval("cos(180)"), -1.0);
+        assert_eq!(eval("tan(135)"), -1.0);
+        assert!(eval("tan(90)").is_nan());
+        assert!((eval("asin(1)") - 90.0).abs() < 1e-12);
+        assert!((eval("atan2(1, 1)") - 45.0).abs() < 1e-12);
+
+        ctx.set_angle_mode(AngleMode::Radians);
+        assert!((ctx.eval_str("sin(π/2)").unwrap().as_number().unwrap() - 1.0).abs() < 1e-15);
+        assert_eq!(AngleMode::Degrees.full_turn(), 360.0);
+    }
+}
@@ -0,0 +1,320 @@
+// Systems of n linear equations in n unknowns, solved by Gauss–Jordan
+// elimination with partial pivoting. When every coefficient is a rational
+// number the elimination is exact; otherwise it runs in floating point and
+// pivots below a tolerance relative to the largest entry count as zero.
+
+use crate::rational::Rational;
+
+pub const SIZES: [usize; 5] = [2, 3, 4, 5, 6];
+
+/// What the elimination needs from a number type.
+trait Scalar: Copy {
+    fn zero() -> Self;
+    fn magnitude(&self) -> f64;
+    fn is_zero(&self, tolerance: f64) -> bool;
+    fn sub(&self, other: &Self) -> Option<Self>;
+    fn mul(&self, other: &Self) -> Option<Self>;
+    fn div(&self, other: &Self) -> Option<Self>;
+    fn to_f64(&self) -> f64;
+    fn format(&self) -> String;
+}
+
+impl Scalar for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+
+    fn is_zero(&self, tolerance: f64) -> bool {
+        self.abs() <= tolerance
+    }
+
+    fn sub(&self, other: &Self) -> Option<Self> {
+        Some(self - other)
+    }
+
+    fn mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        Some(self / other)
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+
+    /// Up to 10 decimals without trailing zeros.
+    fn format(&self) -> String {
+        let text = format!("{:.10}", self);
+        let text = text.trim_end_matches('0').trim_end_matches('.');
+        if text == "-0" {
+            String::from("0")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Scalar for Rational {
+    fn zero() -> Self {
+        Rational::ZERO
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs().to_f64()
+    }
+
+    fn is_zero(&self, _tolerance: f64) -> bool {
+        Rational::is_zero(self)
+    }
+
+    fn sub(&self, other: &Self) -> Option<Self> {
+        self.checked_sub(other)
+    }
+
+    fn mul(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(other)
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        self.checked_div(other)
+    }
+
+    fn to_f64(&self) -> f64 {
+        Rational::to_f64(*self)
+    }
+
+    fn format(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Solution {
+    /// The value of each unknown, as text and as a number.
+    Unique(Vec<(String, f64)>),
+    /// Each unknown expressed through the free parameters t1, t2, ...
+    Parametric { rank: usize, equations: Vec<String> },
+    Inconsistent,
+}
+
+#[derive(Debug, Clone)]
+pub struct SystemSolution {
+    pub solution: Solution,
+    /// Whether the elimination ran on exact fractions.
+    pub exact: bool,
+}
+
+/// `c + a·t1 + b·t2` with zero terms left out and signs merged.
+fn combination<S: Scalar>(constant: S, terms: &[(S, String)], tolerance: f64) -> String {
+    let mut out = String::new();
+    if !constant.is_zero(tolerance) || terms.iter().all(|(c, _)| c.is_zero(tolerance)) {
+        out = constant.format();
+    }
+    for (coefficient, name) in terms {
+        if coefficient.is_zero(tolerance) {
+            continue;
+        }
+        let text = coefficient.format();
+        let (negative, magnitude) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.to_string()),
+            None => (false, text),
+        };
+        let factor = if magnitude == "1" { name.clone() } else { format!("{}·{}", magnitude, name) };
+        out = match (out.is_empty(), negative) {
+            (true, false) => factor,
+            (true, true) => format!("−{}", factor),
+            (false, false) => format!("{} + {}", out, factor),
+            (false, true) => format!("{} − {}", out, factor),
+        };
+    }
+    out
+}
+
+/// Gauss–Jordan elimination of an n × (n + 1) augmented matrix; None on overflow.
+fn eliminate<S: Scalar>(mut m: Vec<Vec<S>>) -> Option<Solution> {
+    let n = m.len();
+    let largest = m.iter().flatten().map(Scalar::magnitude).fold(0.0, f64::max);
+    let tolerance = 1e-10 * largest.max(1.0);
+
+    let mut pivots = Vec::new();
+    for col in 0..n {
+        let row = pivots.len();
+        let pivot = (row..n).max_by(|&a, &b| m[a][col].magnitude().total_cmp(&m[b][col].magnitude()))?;
+        if m[pivot][col].is_zero(tolerance) {
+            continue;
+        }
+        m.swap(row, pivot);
+
+        let divisor = m[row][col];
+        for cell in m[row].iter_mut().skip(col) {
+            *cell = cell.div(&divisor)?;
+        }
+        let pivot_row = m[row].clone();
+        for (r, cells) in m.iter_mut().enumerate() {
+            let factor = cells[col];
+            if r == row || factor.is_zero(0.0) {
+                continue;
+            }
+            for (cell, p) in cells.iter_mut().zip(&pivot_row).skip(col) {
+                *cell = cell.sub(&factor.mul(p)?)?;
+            }
+        }
+        pivots.push(col);
+    }
+
+    let rank = pivots.len();
+    if m[rank..].iter().any(|cells| !cells[n].is_zero(tolerance)) {
+        return Some(Solution::Inconsistent);
+    }
+    if rank == n {
+        return Some(Solution::Unique(
+            m.iter().map(|cells| (cells[n].format(), cells[n].to_f64())).collect(),
+        ));
+    }
+
+    let free: Vec<usize> = (0..n).filter(|col| !pivots.contains(col)).collect();
+    let parameter = |col: usize| free.iter().position(|f| *f == col).map(|i| format!("t{}", i + 1));
+    let equations = (0..n)
+        .map(|col| {
+            let value = match pivots.iter().position(|p| *p == col) {
+                Some(row) => {
+                    let terms: Vec<(S, String)> = free
+                        .iter()
+                        .map(|&f| (S::zero().sub(&m[row][f]).unwrap_or(S::zero()), parameter(f).unwrap_or_default()))
+                        .collect();
+                    combination(m[row][n], &terms, tolerance)
+                }
+                None => parameter(col).unwrap_or_default(),
+            };
+            format!("x{} = {}", col + 1, value)
+        })
+        .collect();
+    Some(Solution::Parametric { rank, equations })
+}
+
+/// Solves exactly when every entry is a fraction and nothing overflows.
+pub fn solve_exact(matrix: Vec<Vec<Rational>>) -> Option<SystemSolution> {
+    eliminate(matrix).map(|solution| SystemSolution { solution, exact: true })
+}
+
+pub fn solve_approximate(matrix: Vec<Vec<f64>>) -> Result<SystemSolution, String> {
+    if matrix.iter().flatten().any(|v| !v.is_finite()) {
+        return Err("Współczynniki muszą być skończonymi liczbami".to_string());
+    }
+    eliminate(matrix)
+        .map(|solution| SystemSolution { solution, exact: false })
+        .ok_or_else(|| "Nie udało się rozwiązać układu".to_string())
+}
+
+pub struct LinearSystemForm {
+    pub size: usize,
+    /// Rows of coefficients followed by the right-hand side.
+    pub cells: Vec<Vec<String>>,
+    pub result: Option<Result<SystemSolution, String>>,
+}
+
+impl Default for LinearSystemForm {
+    fn default() -> Self {
+        let mut form = Self { size: 0, cells: Vec::new(), result: None };
+        form.resize(3);
+        form
+    }
+}
+
+impl LinearSystemForm {
+    /// Changes the number of unknowns, keeping the entries that still fit.
+    pub fn resize(&mut self, size: usize) {
+        let old = self.size;
+        let mut cells = vec![vec![String::new(); size + 1]; size];
+        for (row, cells_row) in cells.iter_mut().enumerate().take(old) {
+            for (col, cell) in cells_row.iter_mut().enumerate().take(size.min(old)) {
+                *cell = self.cells[row][col].clone();
+            }
+            cells_row[size] = self.cells[row][old].clone();
+        }
+        self.size = size;
+        self.cells = cells;
+        self.result = None;
+    }
+
+    pub fn cell_mut(&mut self, row: usize, col: usize) -> Option<&mut String> {
+        self.cells.get_mut(row)?.get_mut(col)
+    }
+
+    /// The matrix as fractions, with empty cells as zero; None if any entry
+    /// is not a plain integer, decimal or fraction.
+    pub fn rational_matrix(&self) -> Option<Vec<Vec<Rational>>> {
+        self.cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| if cell.trim().is_empty() { Some(Rational::ZERO) } else { Rational::parse(cell) })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(rows: &[&[i128]]) -> Solution {
+        let matrix = rows.iter().map(|row| row.iter().map(|v| Rational::integer(*v)).collect()).collect();
+        solve_exact(matrix).unwrap().solution
+    }
+
+    fn values(solution: &Solution) -> Vec<&str> {
+        match solution {
+            Solution::Unique(values) => values.iter().map(|(text, _)| text.as_str()).collect(),
+            Solution::Parametric { equations, .. } => equations.iter().map(String::as_str).collect(),
+            Solution::Inconsistent => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unique_solution_in_fractions() {
+        let solution = exact(&[&[1, 1, 3], &[1, -1, 1]]);
+        assert_eq!(values(&solution), ["2", "1"]);
+
+        let solution = exact(&[&[0, 3, 1], &[2, 0, 1]]);
+        assert_eq!(values(&solution), ["1/2", "1/3"]);
+    }
+
+    #[test]
+    fn dependent_and_inconsistent_systems() {
+        let solution = exact(&[&[1, 1, 2], &[2, 2, 4]]);
+        assert!(matches!(solution, Solution::Parametric { rank: 1, .. }));
+        assert_eq!(values(&solution), ["x1 = 2 − t1", "x2 = t1"]);
+
+        assert!(matches!(exact(&[&[1, 1, 2], &[1, 1, 3]]), Solution::Inconsistent));
+    }
+
+    #[test]
+    fn floating_point_fallback() {
+        let root = 2f64.sqrt();
+        let result = solve_approximate(vec![vec![root, 0.0, 2.0], vec![0.0, 1.0, -1.5]]).unwrap();
+        assert!(!result.exact);
+        assert_eq!(values(&result.solution), ["1.4142135624", "-1.5"]);
+        assert!(solve_approximate(vec![vec![f64::NAN, 1.0, 1.0], vec![1.0, 1.0, 1.0]]).is_err());
+    }
+
+    #[test]
+    fn form_keeps_entries_when_resized() {
+        let mut form = LinearSystemForm::default();
+        *form.cell_mut(0, 0).unwrap() = "1/2".to_string();
+        *form.cell_mut(0, 3).unwrap() = "4".to_string();
+        form.resize(2);
+        assert_eq!(form.cells[0], ["1/2", "", "4"]);
+        assert_eq!(form.rational_matrix().unwrap()[0][0], Rational::new(1, 2).unwrap());
+
+        *form.cell_mut(1, 1).unwrap() = "sqrt(2)".to_string();
+        assert!(form.rational_matrix().is_none());
+    }
+}
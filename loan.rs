@@ -0,0 +1,271 @@
+// Loan amortization: equal (annuity) and decreasing installments with an
+// optional extra principal payment every period.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [
+        Frequency::Monthly,
+        Frequency::Quarterly,
+        Frequency::SemiAnnual,
+        Frequency::Annual,
+    ];
+
+    pub fn per_year(self) -> u32 {
+        match self {
+            Frequency::Monthly => 12,
+            Frequency::Quarterly => 4,
+            Frequency::SemiAnnual => 2,
+            Frequency::Annual => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Frequency::Monthly => "Co miesiąc",
+                Frequency::Quarterly => "Co kwartał",
+                Frequency::SemiAnnual => "Co pół roku",
+                Frequency::Annual => "Co rok",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallmentKind {
+    Equal,
+    Decreasing,
+}
+
+impl InstallmentKind {
+    pub const ALL: [InstallmentKind; 2] = [InstallmentKind::Equal, InstallmentKind::Decreasing];
+}
+
+impl std::fmt::Display for InstallmentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                InstallmentKind::Equal => "Raty równe",
+                InstallmentKind::Decreasing => "Raty malejące",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Loan {
+    pub principal: f64,
+    pub annual_rate: f64,
+    pub years: f64,
+    pub frequency: Frequency,
+    pub kind: InstallmentKind,
+    pub extra_payment: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduleRow {
+    pub period: u32,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    /// Regular installment; for decreasing installments this is the first one.
+    pub installment: f64,
+    pub total_interest: f64,
+    pub total_paid: f64,
+    pub rows: Vec<ScheduleRow>,
+}
+
+// Balances below this are treated as fully repaid (rounding leftovers).
+const EPSILON: f64 = 1e-9;
+/// Longest term accepted, which keeps the schedule at most 1200 rows.
+const MAX_YEARS: f64 = 100.0;
+
+impl Loan {
+    pub fn periods(&self) -> u32 {
+        (self.years * self.frequency.per_year() as f64).round() as u32
+    }
+
+    pub fn periodic_rate(&self) -> f64 {
+        self.annual_rate / 100.0 / self.frequency.per_year() as f64
+    }
+
+    pub fn schedule(&self) -> Result<Schedule, String> {
+        if ![self.principal, self.annual_rate, self.years, self.extra_payment].iter().all(|v| v.is_finite()) {
+            return Err("Wszystkie pola muszą być skończonymi liczbami".to_string());
+        }
+        if self.principal <= 0.0 {
+            return Err("Kwota kredytu musi być dodatnia".to_string());
+        }
+        if self.years > MAX_YEARS {
+            return Err(format!("Okres kredytowania może wynosić najwyżej {} lat", MAX_YEARS));
+        }
+
+        let n = self.periods();
+        let r = self.periodic_rate();
+        if n == 0 {
+            return Err("Okres kredytowania jest za krótki".to_string());
+        }
+        if r < 0.0 || self.extra_payment < 0.0 {
+            return Err("Oprocentowanie i nadpłata nie mogą być ujemne".to_string());
+        }
+
+        let equal_installment = if r == 0.0 {
+            self.principal / n as f64
+        } else {
+            self.principal * r / (1.0 - (1.0 + r).powi(-(n as i32)))
+        };
+        let principal_part = self.principal / n as f64;
+
+        let mut balance = self.principal;
+        let mut rows = Vec::with_capacity(n as usize);
+
+        for period in 1..=n {
+            if balance <= EPSILON {
+                break;
+            }
+
+            let interest = balance * r;
+            let scheduled = match self.kind {
+                InstallmentKind::Equal => equal_installment - interest,
+                InstallmentKind::Decreasing => principal_part,
+            };
+            let principal = (scheduled + self.extra_payment).min(balance);
+            balance -= principal;
+
+            rows.push(ScheduleRow {
+                period,
+                payment: interest + principal,
+                interest,
+                principal,
+                balance: balance.max(0.0),
+            });
+        }
+
+        let total_interest = rows.iter().map(|row| row.interest).sum();
+        let total_paid = rows.iter().map(|row| row.payment).sum();
+        let installment = match self.kind {
+            InstallmentKind::Equal => equal_installment,
+            InstallmentKind::Decreasing => principal_part + self.principal * r,
+        };
+
+        Ok(Schedule { installment, total_interest, total_paid, rows })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum LoanField {
+    Principal,
+    Rate,
+    Years,
+    Extra,
+}
+
+/// Raw inputs of the loan form and the last computed schedule.
+pub struct LoanForm {
+    pub principal: String,
+    pub rate: String,
+    pub years: String,
+    pub extra: String,
+    pub frequency: Frequency,
+    pub kind: InstallmentKind,
+    pub schedule: Option<Result<Schedule, String>>,
+}
+
+impl Default for LoanForm {
+    fn default() -> Self {
+        Self {
+            principal: String::new(),
+            rate: String::new(),
+            years: String::new(),
+            extra: String::new(),
+            frequency: Frequency::Monthly,
+            kind: InstallmentKind::Equal,
+            schedule: None,
+        }
+    }
+}
+
+impl LoanForm {
+    pub fn field_mut(&mut self, field: LoanField) -> &mut String {
+        match field {
+            LoanField::Principal => &mut self.principal,
+            LoanField::Rate => &mut self.rate,
+            LoanField::Years => &mut self.years,
+            LoanField::Extra => &mut self.extra,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loan(principal: f64, annual_rate: f64, years: f64, kind: InstallmentKind) -> Loan {
+        Loan { principal, annual_rate, years, frequency: Frequency::Monthly, kind, extra_payment: 0.0 }
+    }
+
+    #[test]
+    fn equal_installments() {
+        let schedule = loan(100_000.0, 6.0, 30.0, InstallmentKind::Equal).schedule().unwrap();
+        assert!((schedule.installment - 599.55).abs() < 0.005);
+        assert_eq!(schedule.rows.len(), 360);
+        assert!(schedule.rows.last().unwrap().balance < 1e-6);
+        assert!((schedule.total_paid - 100_000.0 - schedule.total_interest).abs() < 1e-6);
+    }
+
+    #[test]
+    fn decreasing_installments() {
+        let schedule = loan(1200.0, 12.0, 1.0, InstallmentKind::Decreasing).schedule().unwrap();
+        assert!((schedule.installment - 112.0).abs() < 1e-9);
+        assert!((schedule.total_interest - 78.0).abs() < 1e-9);
+        assert!((schedule.rows[11].payment - 101.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_rate_and_extra_payments() {
+        let schedule = loan(1200.0, 0.0, 1.0, InstallmentKind::Equal).schedule().unwrap();
+        assert_eq!(schedule.installment, 100.0);
+        assert_eq!(schedule.total_interest, 0.0);
+
+        let mut early = loan(1200.0, 0.0, 1.0, InstallmentKind::Equal);
+        early.extra_payment = 100.0;
+        assert_eq!(early.schedule().unwrap().rows.len(), 6);
+    }
+
+    #[test]
+    fn rejects_invalid_loans() {
+        assert!(loan(0.0, 5.0, 1.0, InstallmentKind::Equal).schedule().is_err());
+        assert!(loan(1000.0, -1.0, 1.0, InstallmentKind::Equal).schedule().is_err());
+        assert!(loan(1000.0, 5.0, 0.0, InstallmentKind::Equal).schedule().is_err());
+    }
+
+    #[test]
+    fn rejects_unbounded_terms_and_non_finite_inputs() {
+        assert!(loan(1000.0, 5.0, 100.0, InstallmentKind::Equal).schedule().is_ok());
+        assert!(loan(1000.0, 5.0, 1e9, InstallmentKind::Equal).schedule().is_err());
+        assert!(loan(1000.0, f64::NAN, 1.0, InstallmentKind::Equal).schedule().is_err());
+        assert!(loan(f64::INFINITY, 5.0, 1.0, InstallmentKind::Equal).schedule().is_err());
+
+        let mut extra = loan(1000.0, 5.0, 1.0, InstallmentKind::Equal);
+        extra.extra_payment = f64::NAN;
+        assert!(extra.schedule().is_err());
+    }
+}
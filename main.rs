@@ -1,9 +1,57 @@
 use iced::{
-    widget::{button, column, container, row, text, pick_list, scrollable, text_input},
+    widget::{button, canvas, checkbox, column, container, radio, row, text, pick_list, scrollable, text_input},
     alignment::Alignment,
-    executor, Application, Command, Element, Length, Settings, Theme, Color,
+    event, executor, keyboard, mouse, subscription, Application, Color, Command, Element, Event, Length, Point,
+    Rectangle, Settings, Subscription, Theme,
 };
-use std::f64::consts::PI;
+mod analysis;
+mod calculus;
+mod cashflow;
+mod config;
+mod currency;
+mod depreciation;
+mod distributions;
+mod export;
+mod expr;
+mod linear;
+mod loan;
+mod matrix;
+mod number_theory;
+mod percent;
+mod physics;
+mod plot;
+mod pricing;
+mod rational;
+mod regression;
+mod savings;
+mod solver;
+mod stats;
+mod symbolic;
+mod tvm;
+mod vat;
+
+use analysis::{AnalysisField, AnalysisResult, CurveChoice};
+use cashflow::{CashFlowField, CashFlowForm, CashFlowRow};
+use config::Config;
+use currency::{CurrencyForm, CurrencyRow, RateTable};
+use depreciation::{Asset, DepreciationField, DepreciationForm};
+use distributions::{Distribution, DistributionField, DistributionForm, DistributionResult};
+use export::{ExportField, ExportFormat};
+use expr::{AngleMode, Context, Value};
+
+use linear::LinearSystemForm;
+use loan::{Frequency, InstallmentKind, Loan, LoanField, LoanForm};
+use matrix::{MatrixForm, MatrixName};
+use physics::{Category, FormulaForm, FORMULAS};
+use plot::{Curve, CurveField, CurveKind, PlotForm};
+use pricing::{BreakEvenField, PricingField, PricingForm};
+use regression::{RegressionField, RegressionForm};
+use savings::{Savings, SavingsField, SavingsForm};
+use solver::{Search, SolverField, SolverForm};
+use stats::StatsForm;
+use symbolic::{Operation, SymbolicField, SymbolicForm};
+use tvm::{TvmField, TvmForm, Worksheet};
+use vat::{VatForm, VatMode, VatRateRow};
 
 pub fn main() -> iced::Result {
     Calculator::run(Settings {
@@ -24,9 +72,104 @@ enum Message {
     ToggleScientific,
     TogglePhysics,
     ToggleEconomics,
+    ToggleStatistics,
+    TogglePlot,
+    ToggleSolver,
+    ToggleMatrix,
+    ToggleSymbolic,
     ToggleThemeEditor,
     ChangeTheme(ThemePreset),
     UpdateColor(ColorTarget, ColorChannel, String),
+    FormulaSearch(String),
+    ToggleCategory(Category),
+    OpenFormula(usize),
+    CloseFormula,
+    FormulaInput(usize, String),
+    SolveFormula,
+    InsertFormulaResult,
+    OpenTool(EconomicsTool),
+    CloseTool,
+    LoanInput(LoanField, String),
+    LoanFrequency(Frequency),
+    LoanKind(InstallmentKind),
+    CalculateLoan,
+    TvmInput(TvmField, String),
+    TvmDue(bool),
+    SolveTvm,
+    CashFlowInput(CashFlowField, String),
+    CashFlowAmount(usize, String),
+    CashFlowDate(usize, String),
+    AddCashFlow,
+    RemoveCashFlow(usize),
+    AnalyzeCashFlows,
+    VatAmount(String),
+    SelectVatMode(VatMode),
+    SelectVatRate(usize),
+    VatRateName(usize, String),
+    VatRateValue(usize, String),
+    AddVatRate,
+    RemoveVatRate(usize),
+    SaveVatRates,
+    ResetVatRates,
+    SavingsInput(SavingsField, String),
+    SavingsCompounding(Frequency),
+    SavingsContributionFrequency(Frequency),
+    CalculateSavings,
+    DepreciationInput(DepreciationField, String),
+    DepreciationMethod(depreciation::Method),
+    CalculateDepreciation,
+    PricingInput(PricingField, String),
+    BreakEvenInput(BreakEvenField, String),
+    CurrencyPath(String),
+    LoadCurrencyFile,
+    CurrencyAmount(String),
+    CurrencyFrom(String),
+    CurrencyTo(String),
+    CurrencyCode(usize, String),
+    CurrencyRate(usize, String),
+    AddCurrency,
+    RemoveCurrency(usize),
+    SaveCurrencies,
+    StatsInput(String),
+    InsertStatsList,
+    RegressionInput(RegressionField, String),
+    RegressionModel(regression::Model),
+    InsertPrediction,
+    SelectDistribution(Distribution),
+    DistributionInput(DistributionField, String),
+    CalculateDistribution,
+    ToggleAngleMode,
+    ToggleNumberFormat,
+    MaxDenominator(i128),
+    CurveInput(usize, CurveField, String),
+    SelectCurveKind(usize, CurveKind),
+    AddCurve,
+    RemoveCurve(usize),
+    PlotPan(f64, f64),
+    PlotZoom(f64, f64, f64),
+    ResetPlot,
+    AnalysisTool(analysis::Tool),
+    AnalysisCurve(CurveChoice),
+    AnalysisOtherCurve(CurveChoice),
+    AnalysisInput(AnalysisField, String),
+    RunAnalysis,
+    ExportInput(ExportField, String),
+    ExportPlot(ExportFormat),
+    SolverInput(SolverField, String),
+    Solve,
+    LinearSize(usize),
+    LinearInput(usize, usize, String),
+    SolveLinear,
+    MatrixRows(MatrixName, usize),
+    MatrixCols(MatrixName, usize),
+    MatrixCell(MatrixName, usize, usize, String),
+    MatrixExpression(String),
+    MatrixOperation(&'static str),
+    EvaluateMatrix,
+    LoadMatrixResult(MatrixName),
+    InsertMatrixResult,
+    SymbolicInput(SymbolicField, String),
+    RunSymbolic(Operation),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,6 +213,18 @@ impl std::fmt::Display for ThemePreset {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EconomicsTool {
+    Loan,
+    Tvm,
+    CashFlow,
+    Vat,
+    Savings,
+    Depreciation,
+    Pricing,
+    Currency,
+}
+
 #[derive(Debug, Clone, Copy)]
 enum ColorTarget {
     Background,
@@ -98,6 +253,18 @@ struct CalcTheme {
 }
 
 impl CalcTheme {
+    /// Color of the n-th plotted curve; after the theme's accents come lighter variants.
+    fn curve_color(&self, index: usize) -> Color {
+        let palette = [self.operator_btn, self.function_btn, self.display_text];
+        let base = palette[index % palette.len()];
+        let lighten = (index / palette.len()) as f32 * 0.35;
+        Color::from_rgb(
+            base.r + (1.0 - base.r) * lighten.min(0.9),
+            base.g + (1.0 - base.g) * lighten.min(0.9),
+            base.b + (1.0 - base.b) * lighten.min(0.9),
+        )
+    }
+
     fn dark() -> Self {
         Self {
             background: Color::from_rgb(0.1, 0.1, 0.12),
@@ -172,9 +339,35 @@ struct Calculator {
     show_scientific: bool,
     show_physics: bool,
     show_economics: bool,
+    show_statistics: bool,
+    show_plot: bool,
+    show_solver: bool,
+    show_matrix: bool,
+    show_symbolic: bool,
     show_theme_editor: bool,
     theme: CalcTheme,
     current_preset: ThemePreset,
+    formula_search: String,
+    expanded_categories: Vec<Category>,
+    formula_form: Option<FormulaForm>,
+    economics_tool: Option<EconomicsTool>,
+    loan: LoanForm,
+    tvm: TvmForm,
+    cashflow: CashFlowForm,
+    vat: VatForm,
+    savings: SavingsForm,
+    depreciation: DepreciationForm,
+    pricing: PricingForm,
+    currency: CurrencyForm,
+    stats: StatsForm,
+    regression: RegressionForm,
+    distribution: DistributionForm,
+    plot: PlotForm,
+    solver: SolverForm,
+    linear: LinearSystemForm,
+    matrix: MatrixForm,
+    symbolic: SymbolicForm,
+    config: Config,
 }
 
 impl Application for Calculator {
@@ -184,6 +377,8 @@ impl Application for Calculator {
     type Flags = ();
 
     fn new(_flags: ()) -> (Self, Command<Message>) {
+        let config = Config::load();
+
         (
             Self {
                 display: String::from("0"),
@@ -192,9 +387,35 @@ impl Application for Calculator {
                 show_scientific: false,
                 show_physics: false,
                 show_economics: false,
+                show_statistics: false,
+                show_plot: false,
+                show_solver: false,
+                show_matrix: false,
+                show_symbolic: false,
                 show_theme_editor: false,
                 theme: CalcTheme::dark(),
                 current_preset: ThemePreset::Dark,
+                formula_search: String::new(),
+                expanded_categories: vec![Category::Mechanics],
+                formula_form: None,
+                economics_tool: None,
+                loan: LoanForm::default(),
+                tvm: TvmForm::default(),
+                cashflow: CashFlowForm::default(),
+                vat: VatForm::new(&config.vat_rates),
+                savings: SavingsForm::default(),
+                depreciation: DepreciationForm::default(),
+                pricing: PricingForm::default(),
+                currency: CurrencyForm::new(RateTable::load_saved()),
+                stats: StatsForm::default(),
+                regression: RegressionForm::default(),
+                distribution: DistributionForm::default(),
+                plot: PlotForm::default(),
+                solver: SolverForm::default(),
+                linear: LinearSystemForm::default(),
+                matrix: MatrixForm::default(),
+                symbolic: SymbolicForm::default(),
+                config,
             },
             Command::none(),
         )
@@ -207,11 +428,7 @@ impl Application for Calculator {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::ButtonPressed(value) => {
-                if self.display == "0" || self.display == "Error" {
-                    self.display = value;
-                } else {
-                    self.display.push_str(&value);
-                }
+                self.insert(&value);
             }
             Message::Clear => {
                 self.display = String::from("0");
@@ -226,11 +443,32 @@ impl Application for Calculator {
                 }
             }
             Message::Calculate => {
-                if let Ok(result) = self.evaluate(&self.display) {
-                    self.display = format!("{}", result);
+                // `factor(360)` and `factorial(30)` are shown exactly, an
+                // `integral(...)` with its error estimate, symbolic
+                // commands such as `d/dx sin(x)^2` give an expression, and an
+                // equation such as `x^3 - 2x = 5` is solved for its unknown.
+                let result = if let Some(result) = number_theory::exact_result(&self.context(), &self.display) {
+                    result
+                } else if let Some(result) = calculus::integral_result(&self.context(), &self.display) {
+                    result
+                } else if symbolic::is_command(&self.display) {
+                    symbolic::run(&self.display)
+                } else if solver::is_equation(&self.display) {
+                    solver::solve_equation(&self.context(), &self.display, "", Search::Auto).map(|s| s.to_string())
                 } else {
-                    self.display = String::from("Error");
-                }
+                    self.evaluate_value(&self.display)
+                        .inspect(|value| {
+                            // Matrices are also shown as a grid in the matrix panel.
+                            if matrix::matrix_of(value).is_ok_and(|m| m.rows > 1) {
+                                self.matrix.result = Some(Ok(value.clone()));
+                            }
+                        })
+                        .map(|value| match value {
+                            Value::Num(n) => self.format_number(&self.display, n),
+                            other => matrix::format_value(&other, self.config.number_format, self.config.max_denominator),
+                        })
+                };
+                self.display = result.unwrap_or_else(|_| String::from("Error"));
             }
             Message::ToggleScientific => {
                 self.show_scientific = !self.show_scientific;
@@ -241,6 +479,24 @@ impl Application for Calculator {
             Message::ToggleEconomics => {
                 self.show_economics = !self.show_economics;
             }
+            Message::ToggleStatistics => {
+                self.show_statistics = !self.show_statistics;
+            }
+            Message::TogglePlot => {
+                self.show_plot = !self.show_plot;
+                if self.show_plot {
+                    self.resample_plot();
+                }
+            }
+            Message::ToggleSolver => {
+                self.show_solver = !self.show_solver;
+            }
+            Message::ToggleMatrix => {
+                self.show_matrix = !self.show_matrix;
+            }
+            Message::ToggleSymbolic => {
+                self.show_symbolic = !self.show_symbolic;
+            }
             Message::ToggleThemeEditor => {
                 self.show_theme_editor = !self.show_theme_editor;
             }
@@ -275,6 +531,388 @@ impl Application for Calculator {
                     self.current_preset = ThemePreset::Custom;
                 }
             }
+            Message::FormulaSearch(query) => {
+                self.formula_search = query;
+            }
+            Message::ToggleCategory(category) => {
+                if let Some(pos) = self.expanded_categories.iter().position(|c| *c == category) {
+                    self.expanded_categories.remove(pos);
+                } else {
+                    self.expanded_categories.push(category);
+                }
+            }
+            Message::OpenFormula(index) => {
+                self.formula_form = Some(FormulaForm::new(index));
+            }
+            Message::CloseFormula => {
+                self.formula_form = None;
+            }
+            Message::FormulaInput(index, value) => {
+                if let Some(form) = &mut self.formula_form {
+                    form.inputs[index] = value;
+                    form.result = None;
+                }
+            }
+            Message::SolveFormula => {
+                if let Some(form) = &self.formula_form {
+                    let result = self.solve_formula(form);
+                    if let Some(form) = &mut self.formula_form {
+                        form.result = Some(result);
+                    }
+                }
+            }
+            Message::InsertFormulaResult => {
+                if let Some(Some(Ok((_, value)))) = self.formula_form.as_ref().map(|f| &f.result) {
+                    self.insert(&value.to_string());
+                }
+            }
+            Message::OpenTool(tool) => {
+                self.economics_tool = Some(tool);
+            }
+            Message::CloseTool => {
+                self.economics_tool = None;
+            }
+            Message::LoanInput(field, value) => {
+                *self.loan.field_mut(field) = value;
+            }
+            Message::LoanFrequency(frequency) => {
+                self.loan.frequency = frequency;
+            }
+            Message::LoanKind(kind) => {
+                self.loan.kind = kind;
+            }
+            Message::CalculateLoan => {
+                self.loan.schedule = Some(self.calculate_loan());
+            }
+            Message::TvmInput(field, value) => {
+                *self.tvm.field_mut(field) = value;
+                self.tvm.result = None;
+            }
+            Message::TvmDue(due) => {
+                self.tvm.due = due;
+                self.tvm.result = None;
+            }
+            Message::SolveTvm => {
+                self.tvm.result = Some(self.solve_tvm());
+            }
+            Message::CashFlowInput(field, value) => {
+                *self.cashflow.field_mut(field) = value;
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::CashFlowAmount(index, value) => {
+                self.cashflow.rows[index].amount = value;
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::CashFlowDate(index, value) => {
+                self.cashflow.rows[index].date = value;
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::AddCashFlow => {
+                self.cashflow.rows.push(CashFlowRow { amount: String::new(), date: String::new() });
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::RemoveCashFlow(index) => {
+                self.cashflow.rows.remove(index);
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::AnalyzeCashFlows => {
+                self.cashflow.summary = Some(self.analyze_cashflows());
+            }
+            Message::VatAmount(value) => {
+                self.vat.amount = value;
+            }
+            Message::SelectVatMode(mode) => {
+                self.vat.mode = mode;
+            }
+            Message::SelectVatRate(index) => {
+                self.vat.selected = index;
+            }
+            Message::VatRateName(index, value) => {
+                self.vat.rows[index].name = value;
+                self.vat.status = None;
+            }
+            Message::VatRateValue(index, value) => {
+                self.vat.rows[index].rate = value;
+                self.vat.status = None;
+            }
+            Message::AddVatRate => {
+                self.vat.rows.push(VatRateRow { name: String::from("Nowa stawka"), rate: String::from("0") });
+                self.vat.status = None;
+            }
+            Message::RemoveVatRate(index) => {
+                self.vat.rows.remove(index);
+                self.vat.selected = self.vat.selected.min(self.vat.rows.len().saturating_sub(1));
+                self.vat.status = None;
+            }
+            Message::SaveVatRates => {
+                self.vat.status = Some(match self.vat.rates() {
+                    Ok(rates) => {
+                        self.config.vat_rates = rates;
+                        match self.config.save() {
+                            Ok(()) => String::from("Zapisano stawki"),
+                            Err(error) => format!("Nie udało się zapisać: {}", error),
+                        }
+                    }
+                    Err(error) => error,
+                });
+            }
+            Message::ResetVatRates => {
+                self.vat.set_rates(&vat::default_rates());
+                self.vat.status = None;
+            }
+            Message::SavingsInput(field, value) => {
+                *self.savings.field_mut(field) = value;
+            }
+            Message::SavingsCompounding(frequency) => {
+                self.savings.compounding = frequency;
+            }
+            Message::SavingsContributionFrequency(frequency) => {
+                self.savings.contribution_frequency = frequency;
+            }
+            Message::CalculateSavings => {
+                self.savings.result = Some(self.calculate_savings());
+            }
+            Message::DepreciationInput(field, value) => {
+                *self.depreciation.field_mut(field) = value;
+            }
+            Message::DepreciationMethod(method) => {
+                self.depreciation.method = method;
+                if self.depreciation.schedule.is_some() {
+                    self.depreciation.schedule = Some(self.calculate_depreciation());
+                }
+            }
+            Message::CalculateDepreciation => {
+                self.depreciation.schedule = Some(self.calculate_depreciation());
+            }
+            Message::PricingInput(field, value) => {
+                *self.pricing.field_mut(field) = value;
+                self.pricing.error = self.update_pricing(field).err();
+            }
+            Message::BreakEvenInput(field, value) => {
+                *self.pricing.break_even_field_mut(field) = value;
+            }
+            Message::CurrencyPath(path) => {
+                self.currency.path = path;
+            }
+            Message::LoadCurrencyFile => {
+                let path = std::path::PathBuf::from(self.currency.path.trim());
+                self.currency.status = Some(match RateTable::load(&path) {
+                    Ok(table) => {
+                        let count = table.rates.len();
+                        self.currency.set_table(table);
+                        match self.currency.table.save() {
+                            Ok(()) => format!("Wczytano {} kursów", count),
+                            Err(error) => format!("Wczytano {} kursów, ale nie zapisano kopii: {}", count, error),
+                        }
+                    }
+                    Err(error) => error,
+                });
+            }
+            Message::CurrencyAmount(value) => {
+                self.currency.amount = value;
+            }
+            Message::CurrencyFrom(code) => {
+                self.currency.from = code;
+            }
+            Message::CurrencyTo(code) => {
+                self.currency.to = code;
+            }
+            Message::CurrencyCode(index, value) => {
+                self.currency.rows[index].code = value;
+                self.currency.status = None;
+            }
+            Message::CurrencyRate(index, value) => {
+                self.currency.rows[index].rate = value;
+                self.currency.status = None;
+            }
+            Message::AddCurrency => {
+                self.currency.rows.push(CurrencyRow { code: String::new(), rate: String::new() });
+            }
+            Message::RemoveCurrency(index) => {
+                self.currency.rows.remove(index);
+                self.currency.status = None;
+            }
+            Message::SaveCurrencies => {
+                self.currency.status = Some(match self.currency.apply_rows() {
+                    Ok(()) => match self.currency.table.save() {
+                        Ok(()) => String::from("Zapisano kursy"),
+                        Err(error) => format!("Nie udało się zapisać: {}", error),
+                    },
+                    Err(error) => error,
+                });
+            }
+            Message::StatsInput(value) => {
+                self.stats.set_input(value);
+            }
+            Message::InsertStatsList => {
+                if !self.stats.data.is_empty() {
+                    let list = self.stats.list_literal();
+                    self.insert(&list);
+                }
+            }
+            Message::RegressionInput(field, value) => {
+                *self.regression.field_mut(field) = value;
+                self.update_regression();
+            }
+            Message::RegressionModel(model) => {
+                self.regression.model = model;
+                self.update_regression();
+            }
+            Message::InsertPrediction => {
+                if let Some(prediction) = self.regression.prediction {
+                    self.insert(&prediction.to_string());
+                }
+            }
+            Message::SelectDistribution(distribution) => {
+                self.distribution.select(distribution);
+            }
+            Message::DistributionInput(field, value) => {
+                *self.distribution.field_mut(field) = value;
+            }
+            Message::CalculateDistribution => {
+                self.distribution.result = Some(self.calculate_distribution());
+            }
+            Message::ToggleAngleMode => {
+                self.config.angle_mode = self.config.angle_mode.toggled();
+                // A failed save only means the mode is not remembered.
+                let _ = self.config.save();
+                self.resample_plot();
+            }
+            Message::ToggleNumberFormat => {
+                self.config.number_format = self.config.number_format.next();
+                let _ = self.config.save();
+                // S⇔D converts the number on the display right away.
+                if let Ok(Value::Num(n)) = self.evaluate_value(&self.display) {
+                    self.display = self.format_number(&self.display, n);
+                }
+            }
+            Message::MaxDenominator(max) => {
+                self.config.max_denominator = max;
+                let _ = self.config.save();
+            }
+            Message::CurveInput(index, field, value) => {
+                if let Some(curve) = self.plot.curves.get_mut(index) {
+                    *curve.field_mut(field) = value;
+                }
+                self.plot.analysis.result = None;
+                self.resample_plot();
+            }
+            Message::SelectCurveKind(index, kind) => {
+                if let Some(curve) = self.plot.curves.get_mut(index) {
+                    curve.kind = kind;
+                }
+                self.plot.analysis.result = None;
+                self.resample_plot();
+            }
+            Message::AddCurve => {
+                self.plot.curves.push(Curve::new(""));
+            }
+            Message::RemoveCurve(index) => {
+                if index < self.plot.curves.len() {
+                    self.plot.curves.remove(index);
+                    self.plot.analysis.curve = CurveChoice(0);
+                    self.plot.analysis.other = CurveChoice(1);
+                    self.plot.analysis.result = None;
+                }
+            }
+            Message::PlotPan(dx, dy) => {
+                self.plot.viewport.pan(dx, dy);
+                self.resample_plot();
+            }
+            Message::PlotZoom(factor, x, y) => {
+                self.plot.viewport.zoom(factor, x, y);
+                self.resample_plot();
+            }
+            Message::ResetPlot => {
+                self.plot.viewport = plot::Viewport::default();
+                self.resample_plot();
+            }
+            Message::AnalysisTool(tool) => {
+                self.plot.analysis.tool = tool;
+                self.plot.analysis.result = None;
+            }
+            Message::AnalysisCurve(curve) => {
+                self.plot.analysis.curve = curve;
+            }
+            Message::AnalysisOtherCurve(curve) => {
+                self.plot.analysis.other = curve;
+            }
+            Message::AnalysisInput(field, value) => {
+                *self.plot.analysis.field_mut(field) = value;
+            }
+            Message::RunAnalysis => {
+                self.plot.analysis.result = Some(self.run_analysis());
+            }
+            Message::ExportInput(field, value) => {
+                *self.plot.export.field_mut(field) = value;
+                self.plot.export.status = None;
+            }
+            Message::ExportPlot(format) => {
+                self.plot.export.status = Some(match self.export_plot(format) {
+                    Ok(path) => format!("Zapisano: {}", path.display()),
+                    Err(e) => e,
+                });
+            }
+            Message::SolverInput(field, value) => {
+                *self.solver.field_mut(field) = value;
+            }
+            Message::Solve => {
+                self.solver.result = Some(self.solve_equation());
+            }
+            Message::LinearSize(size) => {
+                self.linear.resize(size);
+            }
+            Message::LinearInput(row, col, value) => {
+                if let Some(cell) = self.linear.cell_mut(row, col) {
+                    *cell = value;
+                }
+            }
+            Message::SolveLinear => {
+                self.linear.result = Some(self.solve_linear_system());
+            }
+            Message::MatrixRows(name, rows) => {
+                let input = self.matrix.input_mut(name);
+                let cols = input.cols();
+                input.resize(rows, cols);
+            }
+            Message::MatrixCols(name, cols) => {
+                let input = self.matrix.input_mut(name);
+                let rows = input.rows();
+                input.resize(rows, cols);
+            }
+            Message::MatrixCell(name, row, col, value) => {
+                if let Some(cell) = self.matrix.input_mut(name).cells.get_mut(row).and_then(|r| r.get_mut(col)) {
+                    *cell = value;
+                }
+            }
+            Message::MatrixExpression(expression) => {
+                self.matrix.expression = expression;
+            }
+            Message::MatrixOperation(expression) => {
+                self.matrix.expression = expression.to_string();
+                self.matrix.result = Some(self.evaluate_matrix());
+            }
+            Message::EvaluateMatrix => {
+                self.matrix.result = Some(self.evaluate_matrix());
+            }
+            Message::LoadMatrixResult(name) => {
+                if let Some(Ok(value)) = &self.matrix.result {
+                    let value = value.clone();
+                    self.matrix.input_mut(name).load(&value);
+                }
+            }
+            Message::InsertMatrixResult => {
+                if let Some(Ok(value)) = &self.matrix.result {
+                    let value = value.to_string();
+                    self.insert(&value);
+                }
+            }
+            Message::SymbolicInput(field, value) => {
+                *self.symbolic.field_mut(field) = value;
+            }
+            Message::RunSymbolic(operation) => {
+                self.symbolic.result = Some(self.run_symbolic(operation));
+            }
         }
         Command::none()
     }
@@ -336,7 +974,36 @@ impl Application for Calculator {
         // Economics formulas
         if self.show_economics {
             let economics = self.create_economics_panel();
-            content = content.push(scrollable(economics).height(Length::Fixed(200.0)));
+            let height = if self.economics_tool.is_some() { 400.0 } else { 200.0 };
+            content = content.push(scrollable(economics).height(Length::Fixed(height)));
+        }
+
+        // Function plot; kept out of a scrollable so the wheel zooms the graph
+        if self.show_plot {
+            content = content.push(self.create_plot_panel());
+        }
+
+        // Equation solver
+        if self.show_solver {
+            let solver = self.create_solver_panel();
+            content = content.push(scrollable(solver).height(Length::Fixed(400.0)));
+        }
+
+        // Matrices
+        if self.show_matrix {
+            let matrices = self.create_matrix_panel();
+            content = content.push(scrollable(matrices).height(Length::Fixed(400.0)));
+        }
+
+        // Symbolic algebra
+        if self.show_symbolic {
+            content = content.push(self.create_symbolic_panel());
+        }
+
+        // Statistics
+        if self.show_statistics {
+            let statistics = self.create_statistics_panel();
+            content = content.push(scrollable(statistics).height(Length::Fixed(400.0)));
         }
 
         // Toggle buttons
@@ -350,6 +1017,21 @@ impl Application for Calculator {
             button(text(if self.show_economics { "💰 Ukryj" } else { "💰 Ekonomia" }).size(14))
                 .on_press(Message::ToggleEconomics)
                 .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            button(text(if self.show_statistics { "📊 Ukryj" } else { "📊 Statystyka" }).size(14))
+                .on_press(Message::ToggleStatistics)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            button(text(if self.show_plot { "📉 Ukryj" } else { "📉 Wykres" }).size(14))
+                .on_press(Message::TogglePlot)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            button(text(if self.show_solver { "🎯 Ukryj" } else { "🎯 Równania" }).size(14))
+                .on_press(Message::ToggleSolver)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            button(text(if self.show_matrix { "🔢 Ukryj" } else { "🔢 Macierze" }).size(14))
+                .on_press(Message::ToggleMatrix)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            button(text(if self.show_symbolic { "∂ Ukryj" } else { "∂ Algebra" }).size(14))
+                .on_press(Message::ToggleSymbolic)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
         ]
         .spacing(5);
 
@@ -363,6 +1045,28 @@ impl Application for Calculator {
             .into()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        // Typing edits the display unless a form field has the focus.
+        subscription::events_with(|event, status| {
+            if let event::Status::Captured = status {
+                return None;
+            }
+
+            match event {
+                Event::Keyboard(keyboard::Event::CharacterReceived(c)) if !c.is_control() => {
+                    Some(Message::ButtonPressed(c.to_string()))
+                }
+                Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => match key_code {
+                    keyboard::KeyCode::Enter | keyboard::KeyCode::NumpadEnter => Some(Message::Calculate),
+                    keyboard::KeyCode::Backspace => Some(Message::Delete),
+                    keyboard::KeyCode::Escape => Some(Message::Clear),
+                    _ => None,
+                },
+                _ => None,
+            }
+        })
+    }
+
     fn theme(&self) -> Theme {
         Theme::custom(iced::theme::Palette {
             background: self.theme.background,
@@ -466,6 +1170,7 @@ impl Calculator {
             self.calc_button("3", theme.number_btn),
             self.calc_button("-", theme.operator_btn),
             self.calc_button("^", theme.operator_btn),
+            self.calc_button("%", theme.operator_btn),
         ]
         .spacing(5);
 
@@ -474,6 +1179,10 @@ impl Calculator {
             self.calc_button(".", theme.number_btn),
             self.calc_button("π", theme.function_btn),
             self.calc_button("+", theme.operator_btn),
+            button(text("S⇔D").size(20))
+                .on_press(Message::ToggleNumberFormat)
+                .padding(15)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
             button(text("=").size(20))
                 .on_press(Message::Calculate)
                 .padding(15)
@@ -503,83 +1212,1505 @@ impl Calculator {
         ]
         .spacing(5);
 
-        column![row1, row2].spacing(5).into()
+        let row3 = row![
+            self.calc_button("[", theme.operator_btn),
+            self.calc_button("]", theme.operator_btn),
+            self.calc_button(",", theme.operator_btn),
+            self.calc_button("mod(", theme.function_btn),
+        ]
+        .spacing(5);
+
+        let row4 = row![
+            button(text(self.config.angle_mode.to_string()).size(20))
+                .on_press(Message::ToggleAngleMode)
+                .padding(15)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            self.calc_button("asin(", theme.function_btn),
+            self.calc_button("acos(", theme.function_btn),
+            self.calc_button("atan(", theme.function_btn),
+        ]
+        .spacing(5);
+
+        let row5 = row![
+            self.calc_button("deriv(", theme.function_btn),
+            self.calc_button("integral(", theme.function_btn),
+            self.calc_button("sum(", theme.function_btn),
+            self.calc_button("prod(", theme.function_btn),
+        ]
+        .spacing(5);
+
+        let row6 = row![
+            self.calc_button("gcd(", theme.function_btn),
+            self.calc_button("lcm(", theme.function_btn),
+            self.calc_button("factor(", theme.function_btn),
+            self.calc_button("isprime(", theme.function_btn),
+        ]
+        .spacing(5);
+
+        let row7 = row![
+            text("Maks. mianownik ułamka").size(14).style(theme.display_text),
+            pick_list(&rational::MAX_DENOMINATORS[..], Some(self.config.max_denominator), Message::MaxDenominator),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        column![row1, row2, row3, row4, row5, row6, row7].spacing(5).into()
     }
 
     fn create_physics_panel(&self) -> Element<Message> {
         let theme = self.theme;
-        
-        column![
+
+        if let Some(form) = &self.formula_form {
+            return self.create_formula_form(form);
+        }
+
+        let mut list = column![
             text("⚛️ WZORY FIZYCZNE").size(18).style(theme.display_text),
-            self.formula_button("E=mc² (energia)", "299792458^2*"),
-            self.formula_button("v=s/t (prędkość)", "/"),
-            self.formula_button("a=Δv/t (przyśp.)", "/"),
-            self.formula_button("F=ma (II zasada)", "*"),
-            self.formula_button("Ek=½mv² (en.kin.)", "0.5**"),
-            self.formula_button("Ep=mgh (en.pot.)", "**9.81*"),
-            self.formula_button("P=W/t (moc)", "/"),
-            self.formula_button("p=mv (pęd)", "*"),
+            text_input("Szukaj wzoru...", &self.formula_search)
+                .on_input(Message::FormulaSearch),
         ]
-        .spacing(5)
-        .into()
+        .spacing(5);
+
+        let searching = !self.formula_search.trim().is_empty();
+
+        for category in Category::ALL {
+            let formulas: Vec<(usize, &physics::Formula)> = FORMULAS
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| f.category == category && f.matches(&self.formula_search))
+                .collect();
+
+            if formulas.is_empty() {
+                continue;
+            }
+
+            // A search always shows its hits, whatever the collapse state.
+            let expanded = searching || self.expanded_categories.contains(&category);
+
+            list = list.push(
+                button(text(format!("{} {} ({})", if expanded { "▾" } else { "▸" }, category, formulas.len())).size(16))
+                    .on_press(Message::ToggleCategory(category))
+                    .padding(8)
+                    .width(Length::Fill)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            );
+
+            if !expanded {
+                continue;
+            }
+
+            for (index, formula) in formulas {
+                list = list.push(
+                    button(text(format!("{} ({})", formula.equation, formula.name)).size(14))
+                        .on_press(Message::OpenFormula(index))
+                        .padding(10)
+                        .width(Length::Fill)
+                        .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                );
+            }
+        }
+
+        list.into()
+    }
+
+    fn create_formula_form<'a>(&'a self, form: &'a FormulaForm) -> Element<'a, Message> {
+        let theme = self.theme;
+        let formula = form.formula();
+
+        let mut fields = column![
+            text(format!("{}: {}", formula.name, formula.equation)).size(18).style(theme.display_text),
+            text("Uzupełnij wszystkie pola poza szukanym").size(12).style(theme.display_text),
+        ]
+        .spacing(5);
+
+        for (index, variable) in formula.variables.iter().enumerate() {
+            fields = fields.push(
+                row![
+                    text(format!("{} ({})", variable.symbol, variable.name))
+                        .size(14)
+                        .style(theme.display_text)
+                        .width(Length::FillPortion(2)),
+                    text_input("?", &form.inputs[index])
+                        .on_input(move |v| Message::FormulaInput(index, v))
+                        .on_submit(Message::SolveFormula)
+                        .width(Length::FillPortion(2)),
+                    text(variable.unit).size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+        }
+
+        match &form.result {
+            Some(Ok((index, value))) => {
+                let variable = &formula.variables[*index];
+                fields = fields.push(
+                    text(format!("{} = {} {}", variable.symbol, value, variable.unit))
+                        .size(20)
+                        .style(theme.display_text),
+                );
+            }
+            Some(Err(error)) => {
+                fields = fields.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        fields = fields.push(
+            row![
+                button(text("Oblicz").size(14))
+                    .on_press(Message::SolveFormula)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                button(text("Wstaw").size(14))
+                    .on_press(Message::InsertFormulaResult)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("Wróć").size(14))
+                    .on_press(Message::CloseFormula)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+            ]
+            .spacing(5),
+        );
+
+        fields.into()
     }
 
     fn create_economics_panel(&self) -> Element<Message> {
         let theme = self.theme;
-        
+
+        if let Some(tool) = self.economics_tool {
+            return match tool {
+                EconomicsTool::Loan => self.create_loan_panel(),
+                EconomicsTool::Tvm => self.create_tvm_panel(),
+                EconomicsTool::CashFlow => self.create_cashflow_panel(),
+                EconomicsTool::Vat => self.create_vat_panel(),
+                EconomicsTool::Savings => self.create_savings_panel(),
+                EconomicsTool::Depreciation => self.create_depreciation_panel(),
+                EconomicsTool::Pricing => self.create_pricing_panel(),
+                EconomicsTool::Currency => self.create_currency_panel(),
+            };
+        }
+
         column![
             text("💰 WZORY EKONOMICZNE").size(18).style(theme.display_text),
-            self.formula_button("Odsetki: I=P*r*t", "**"),
-            self.formula_button("ROI: (gain-cost)/cost", "(-)/"),
-            self.formula_button("VAT: cena*1.23", "*1.23"),
-            self.formula_button("Marża: (cena-koszt)/cena", "(-)/"),
-            self.formula_button("Narzut: (cena-koszt)/koszt", "(-)/"),
-            self.formula_button("Deprecjacja liniowa", "/"),
+            self.tool_button("🏦 Kredyt i harmonogram spłat", EconomicsTool::Loan),
+            self.tool_button("⏳ Wartość pieniądza w czasie (TVM)", EconomicsTool::Tvm),
+            self.tool_button("📈 NPV / IRR przepływów", EconomicsTool::CashFlow),
+            self.tool_button("🐷 Procent składany i oszczędności", EconomicsTool::Savings),
+            self.formula_button("Odsetki proste: interest(kapitał, %, lata)", "interest("),
+            self.formula_button("ROI %: roi(przychód, koszt)", "roi("),
+            self.formula_button("Zmiana %: pctchange(stara, nowa)", "pctchange("),
+            self.formula_button("% z liczby: pctof(p, x)", "pctof("),
+            self.formula_button("Kwota przed dodaniem %: pctrev(x, p)", "pctrev("),
+            self.tool_button("🧾 VAT: netto / brutto / podatek", EconomicsTool::Vat),
+            self.tool_button("🏷️ Marża, narzut i próg rentowności", EconomicsTool::Pricing),
+            self.tool_button("💱 Przeliczanie walut", EconomicsTool::Currency),
+            self.tool_button("🏭 Amortyzacja środków trwałych", EconomicsTool::Depreciation),
         ]
         .spacing(5)
         .into()
     }
 
-    fn create_theme_editor(&self) -> Element<Message> {
+    fn create_loan_panel(&self) -> Element<Message> {
         let theme = self.theme;
-        
-        let bg_editor = self.color_editor("Tło", ColorTarget::Background, theme.background);
-        let disp_editor = self.color_editor("Wyświetlacz", ColorTarget::Display, theme.display);
-        let text_editor = self.color_editor("Tekst", ColorTarget::DisplayText, theme.display_text);
-        let num_editor = self.color_editor("Przyciski cyfr", ColorTarget::Number, theme.number_btn);
-        let op_editor = self.color_editor("Operatory", ColorTarget::Operator, theme.operator_btn);
-        let fn_editor = self.color_editor("Funkcje", ColorTarget::Function, theme.function_btn);
+        let form = &self.loan;
+
+        let mut panel = column![
+            text("🏦 KREDYT").size(18).style(theme.display_text),
+            self.labeled_input("Kwota kredytu", &form.principal, |v| Message::LoanInput(LoanField::Principal, v), Message::CalculateLoan),
+            self.labeled_input("Oprocentowanie roczne (%)", &form.rate, |v| Message::LoanInput(LoanField::Rate, v), Message::CalculateLoan),
+            self.labeled_input("Okres (lata)", &form.years, |v| Message::LoanInput(LoanField::Years, v), Message::CalculateLoan),
+            self.labeled_input("Nadpłata co ratę", &form.extra, |v| Message::LoanInput(LoanField::Extra, v), Message::CalculateLoan),
+            row![
+                pick_list(&Frequency::ALL[..], Some(form.frequency), Message::LoanFrequency),
+                pick_list(&InstallmentKind::ALL[..], Some(form.kind), Message::LoanKind),
+            ]
+            .spacing(5),
+            self.tool_actions(Message::CalculateLoan),
+        ]
+        .spacing(5);
 
-        container(
-            scrollable(
-                column![
-                    text("🎨 EDYTOR MOTYWU").size(20),
-                    bg_editor,
-                    disp_editor,
-                    text_editor,
-                    num_editor,
-                    op_editor,
-                    fn_editor,
-                ]
-                .spacing(10)
-            )
-            .height(Length::Fixed(250.0))
-        )
-        .padding(10)
-        .style(iced::theme::Container::Custom(Box::new(EditorStyle(theme))))
-        .into()
+        match &form.schedule {
+            Some(Ok(schedule)) => {
+                panel = panel.push(
+                    column![
+                        text(format!("Rata: {:.2}", schedule.installment)).size(16).style(theme.display_text),
+                        text(format!("Suma odsetek: {:.2}", schedule.total_interest)).size(16).style(theme.display_text),
+                        text(format!("Łącznie do spłaty: {:.2}", schedule.total_paid)).size(16).style(theme.display_text),
+                    ]
+                    .spacing(2),
+                );
+
+                panel = panel.push(self.table_row(&["Nr", "Rata", "Odsetki", "Kapitał", "Saldo"], 14));
+                for row in &schedule.rows {
+                    panel = panel.push(self.table_row(
+                        &[
+                            &row.period.to_string(),
+                            &format!("{:.2}", row.payment),
+                            &format!("{:.2}", row.interest),
+                            &format!("{:.2}", row.principal),
+                            &format!("{:.2}", row.balance),
+                        ],
+                        12,
+                    ));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
     }
 
-    fn color_editor(&self, label: &str, target: ColorTarget, color: Color) -> Element<Message> {
-        let r_val = (color.r * 255.0) as u8;
-        let g_val = (color.g * 255.0) as u8;
-        let b_val = (color.b * 255.0) as u8;
+    fn create_tvm_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.tvm;
 
-        column![
-            text(label).size(14),
-            row![
-                text("R:").size(12),
-                text_input(&r_val.to_string(), &r_val.to_string())
+        let mut panel = column![
+            text("⏳ TVM").size(18).style(theme.display_text),
+            text("Uzupełnij cztery z pięciu pól; wpłaty wpisuj ze znakiem minus").size(12).style(theme.display_text),
+        ]
+        .spacing(5);
+
+        for field in TvmField::SOLVABLE {
+            panel = panel.push(self.labeled_input(
+                field.label(),
+                form.field(field),
+                move |v| Message::TvmInput(field, v),
+                Message::SolveTvm,
+            ));
+        }
+
+        for field in [TvmField::PaymentsPerYear, TvmField::CompoundsPerYear] {
+            panel = panel.push(self.labeled_input(
+                field.label(),
+                form.field(field),
+                move |v| Message::TvmInput(field, v),
+                Message::SolveTvm,
+            ));
+        }
+
+        panel = panel
+            .push(checkbox("Płatność na początku okresu (BGN)", form.due, Message::TvmDue))
+            .push(self.tool_actions(Message::SolveTvm));
+
+        match &form.result {
+            Some(Ok((field, value))) => {
+                panel = panel.push(
+                    text(format!("{} = {:.4}", field.label(), value))
+                        .size(18)
+                        .style(theme.display_text),
+                );
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_cashflow_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.cashflow;
+
+        let mut panel = column![
+            text("📈 PRZEPŁYWY PIENIĘŻNE").size(18).style(theme.display_text),
+            self.labeled_input("Stopa dyskontowa (%)", &form.rate, |v| Message::CashFlowInput(CashFlowField::Rate, v), Message::AnalyzeCashFlows),
+            self.labeled_input("MIRR: koszt finansowania (%)", &form.finance_rate, |v| Message::CashFlowInput(CashFlowField::FinanceRate, v), Message::AnalyzeCashFlows),
+            self.labeled_input("MIRR: stopa reinwestycji (%)", &form.reinvest_rate, |v| Message::CashFlowInput(CashFlowField::ReinvestRate, v), Message::AnalyzeCashFlows),
+            self.table_row(&["Okres", "Kwota", "Data (RRRR-MM-DD)"], 14),
+        ]
+        .spacing(5);
+
+        for (index, flow) in form.rows.iter().enumerate() {
+            panel = panel.push(
+                row![
+                    text(format!("t={}", index)).size(14).style(theme.display_text).width(Length::Fixed(50.0)),
+                    text_input("0", &flow.amount)
+                        .on_input(move |v| Message::CashFlowAmount(index, v))
+                        .width(Length::Fill),
+                    text_input("opcjonalnie", &flow.date)
+                        .on_input(move |v| Message::CashFlowDate(index, v))
+                        .width(Length::Fill),
+                    button(text("✕").size(14))
+                        .on_press(Message::RemoveCashFlow(index))
+                        .style(iced::theme::Button::Custom(Box::new(ButtonStyle(Color::from_rgb(0.8, 0.3, 0.3))))),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+        }
+
+        panel = panel.push(
+            row![
+                button(text("+ Dodaj przepływ").size(14))
+                    .on_press(Message::AddCashFlow)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                button(text("Wróć").size(14))
+                    .on_press(Message::CloseTool)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+            ]
+            .spacing(5),
+        );
+
+        let percent = |value: f64| format!("{:.4}%", value * 100.0);
+
+        match &form.summary {
+            Some(Ok(summary)) => {
+                let mut results = column![
+                    text(format!("NPV: {:.2}", summary.npv)).size(16).style(theme.display_text),
+                    text(format!("IRR: {}", summary.irr.map_or(String::from("brak"), percent))).size(16).style(theme.display_text),
+                    text(format!(
+                        "Okres zwrotu: {}",
+                        summary.payback.map_or(String::from("brak"), |payback| format!("{:.2}", payback))
+                    ))
+                    .size(16)
+                    .style(theme.display_text),
+                ]
+                .spacing(2);
+
+                if let Some(mirr) = summary.mirr {
+                    results = results.push(text(format!("MIRR: {}", percent(mirr))).size(16).style(theme.display_text));
+                }
+                if let Some(xnpv) = summary.xnpv {
+                    results = results.push(text(format!("XNPV: {:.2}", xnpv)).size(16).style(theme.display_text));
+                }
+                if let Some(xirr) = summary.xirr {
+                    results = results.push(text(format!("XIRR: {}", percent(xirr))).size(16).style(theme.display_text));
+                }
+
+                panel = panel.push(results);
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_vat_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.vat;
+
+        let mut panel = column![
+            text("🧾 VAT").size(18).style(theme.display_text),
+            row![
+                text_input("Kwota", &form.amount)
+                    .on_input(Message::VatAmount)
+                    .width(Length::Fill),
+                pick_list(&VatMode::ALL[..], Some(form.mode), Message::SelectVatMode),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5);
+
+        let rates = form.rates();
+
+        if let Ok(rates) = &rates {
+            if let (Some(rate), Some(amount)) = (rates.get(form.selected), self.field_value(&form.amount, "kwota").ok().flatten()) {
+                match vat::breakdown(amount, rate.rate, form.mode) {
+                    Ok(breakdown) => {
+                        panel = panel.push(
+                            column![
+                                text(format!("Netto: {:.2}", breakdown.net)).size(16).style(theme.display_text),
+                                text(format!("VAT {}%: {:.2}", rate.rate, breakdown.tax)).size(16).style(theme.display_text),
+                                text(format!("Brutto: {:.2}", breakdown.gross)).size(16).style(theme.display_text),
+                            ]
+                            .spacing(2),
+                        );
+                    }
+                    Err(error) => {
+                        panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+                    }
+                }
+            }
+        }
+
+        panel = panel.push(text("Stawki").size(16).style(theme.display_text));
+
+        for (index, row) in form.rows.iter().enumerate() {
+            panel = panel.push(
+                row![
+                    radio("", index, Some(form.selected), Message::SelectVatRate),
+                    text_input("Nazwa", &row.name)
+                        .on_input(move |v| Message::VatRateName(index, v))
+                        .width(Length::FillPortion(3)),
+                    text_input("%", &row.rate)
+                        .on_input(move |v| Message::VatRateValue(index, v))
+                        .width(Length::FillPortion(1)),
+                    button(text("✕").size(14))
+                        .on_press(Message::RemoveVatRate(index))
+                        .style(iced::theme::Button::Custom(Box::new(ButtonStyle(Color::from_rgb(0.8, 0.3, 0.3))))),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+        }
+
+        panel = panel.push(
+            row![
+                button(text("+ Dodaj stawkę").size(14))
+                    .on_press(Message::AddVatRate)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                button(text("Zapisz").size(14))
+                    .on_press(Message::SaveVatRates)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("Domyślne").size(14))
+                    .on_press(Message::ResetVatRates)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                button(text("Wróć").size(14))
+                    .on_press(Message::CloseTool)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+            ]
+            .spacing(5),
+        );
+
+        if let Err(error) = &rates {
+            panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+        }
+        if let Some(status) = &form.status {
+            panel = panel.push(text(status).size(14).style(theme.display_text));
+        }
+
+        panel.into()
+    }
+
+    fn create_savings_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.savings;
+
+        let mut panel = column![
+            text("🐷 OSZCZĘDNOŚCI").size(18).style(theme.display_text),
+            self.labeled_input("Kapitał początkowy", &form.principal, |v| Message::SavingsInput(SavingsField::Principal, v), Message::CalculateSavings),
+            self.labeled_input("Oprocentowanie roczne (%)", &form.rate, |v| Message::SavingsInput(SavingsField::Rate, v), Message::CalculateSavings),
+            self.labeled_input("Okres (lata)", &form.years, |v| Message::SavingsInput(SavingsField::Years, v), Message::CalculateSavings),
+            self.labeled_input("Wpłata okresowa", &form.contribution, |v| Message::SavingsInput(SavingsField::Contribution, v), Message::CalculateSavings),
+            row![
+                text("Kapitalizacja").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                pick_list(&Frequency::ALL[..], Some(form.compounding), Message::SavingsCompounding).width(Length::FillPortion(1)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            row![
+                text("Wpłaty").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                pick_list(&Frequency::ALL[..], Some(form.contribution_frequency), Message::SavingsContributionFrequency).width(Length::FillPortion(1)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            self.labeled_input("Podatek od zysków (%)", &form.tax, |v| Message::SavingsInput(SavingsField::Tax, v), Message::CalculateSavings),
+            self.labeled_input("Inflacja roczna (%)", &form.inflation, |v| Message::SavingsInput(SavingsField::Inflation, v), Message::CalculateSavings),
+            self.tool_actions(Message::CalculateSavings),
+        ]
+        .spacing(5);
+
+        match &form.result {
+            Some(Ok(result)) => {
+                panel = panel.push(
+                    column![
+                        text(format!("Wpłacono łącznie: {:.2}", result.total_contributed)).size(16).style(theme.display_text),
+                        text(format!("Saldo przed podatkiem: {:.2}", result.gross_balance)).size(16).style(theme.display_text),
+                        text(format!("Podatek: {:.2}", result.tax)).size(16).style(theme.display_text),
+                        text(format!("Wartość nominalna: {:.2}", result.final_nominal)).size(16).style(theme.display_text),
+                        text(format!("Wartość realna: {:.2}", result.final_real)).size(16).style(theme.display_text),
+                    ]
+                    .spacing(2),
+                );
+
+                panel = panel.push(self.table_row(&["Rok", "Wpłaty", "Odsetki", "Saldo", "Realnie"], 14));
+                for row in &result.rows {
+                    panel = panel.push(self.table_row(
+                        &[
+                            &row.year.to_string(),
+                            &format!("{:.2}", row.contributions),
+                            &format!("{:.2}", row.interest),
+                            &format!("{:.2}", row.balance),
+                            &format!("{:.2}", row.real_balance),
+                        ],
+                        12,
+                    ));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_depreciation_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.depreciation;
+
+        let mut panel = column![
+            text("🏭 AMORTYZACJA").size(18).style(theme.display_text),
+            self.labeled_input("Cena nabycia", &form.cost, |v| Message::DepreciationInput(DepreciationField::Cost, v), Message::CalculateDepreciation),
+            self.labeled_input("Wartość końcowa", &form.salvage, |v| Message::DepreciationInput(DepreciationField::Salvage, v), Message::CalculateDepreciation),
+            self.labeled_input("Okres użytkowania (lata)", &form.life, |v| Message::DepreciationInput(DepreciationField::Life, v), Message::CalculateDepreciation),
+            pick_list(&depreciation::Method::ALL[..], Some(form.method), Message::DepreciationMethod),
+        ]
+        .spacing(5);
+
+        if form.method == depreciation::Method::DecliningBalance {
+            panel = panel.push(self.labeled_input(
+                "Współczynnik degresywny",
+                &form.factor,
+                |v| Message::DepreciationInput(DepreciationField::Factor, v),
+                Message::CalculateDepreciation,
+            ));
+        }
+
+        panel = panel.push(self.tool_actions(Message::CalculateDepreciation));
+
+        match &form.schedule {
+            Some(Ok(rows)) => {
+                panel = panel.push(self.table_row(&["Rok", "Odpis", "Umorzenie", "Wartość netto"], 14));
+                for row in rows {
+                    panel = panel.push(self.table_row(
+                        &[
+                            &row.year.to_string(),
+                            &format!("{:.2}", row.depreciation),
+                            &format!("{:.2}", row.accumulated),
+                            &format!("{:.2}", row.book_value),
+                        ],
+                        12,
+                    ));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_pricing_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.pricing;
+
+        let pricing_input = |label: &str, field: PricingField| {
+            row![
+                text(label).size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                text_input("0", form.field(field))
+                    .on_input(move |v| Message::PricingInput(field, v))
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+        };
+
+        let mut panel = column![
+            text("🏷️ CENY").size(18).style(theme.display_text),
+            text("Zmiana dowolnego pola przelicza pozostałe").size(12).style(theme.display_text),
+            pricing_input("Koszt", PricingField::Cost),
+            pricing_input("Cena", PricingField::Price),
+            pricing_input("Marża (% ceny)", PricingField::Margin),
+            pricing_input("Narzut (% kosztu)", PricingField::Markup),
+        ]
+        .spacing(5);
+
+        if let Some(error) = &form.error {
+            panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+        }
+
+        panel = panel.push(
+            column![
+                text("Próg rentowności").size(16).style(theme.display_text),
+                row![
+                    text("Koszty stałe").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                    text_input("0", &form.fixed_costs)
+                        .on_input(|v| Message::BreakEvenInput(BreakEvenField::FixedCosts, v))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+                row![
+                    text("Cena jednostkowa").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                    text_input("0", &form.unit_price)
+                        .on_input(|v| Message::BreakEvenInput(BreakEvenField::UnitPrice, v))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+                row![
+                    text("Koszt zmienny jednostki").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                    text_input("0", &form.unit_variable_cost)
+                        .on_input(|v| Message::BreakEvenInput(BreakEvenField::UnitVariableCost, v))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            ]
+            .spacing(5),
+        );
+
+        match self.calculate_break_even() {
+            Some(Ok(result)) => {
+                panel = panel.push(
+                    column![
+                        text(format!("Marża jednostkowa: {:.2}", result.contribution)).size(16).style(theme.display_text),
+                        text(format!("Próg rentowności: {:.2} szt.", result.units)).size(16).style(theme.display_text),
+                        text(format!("Przychód w progu: {:.2}", result.revenue)).size(16).style(theme.display_text),
+                    ]
+                    .spacing(2),
+                );
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel
+            .push(
+                button(text("Wróć").size(14))
+                    .on_press(Message::CloseTool)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+            )
+            .into()
+    }
+
+    fn create_currency_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.currency;
+
+        let freshness = match (&form.table.date, form.table.age_days()) {
+            (Some(date), Some(age)) => format!("Kursy z dnia {} ({} dni temu)", date, age),
+            (Some(date), None) => format!("Kursy z dnia {}", date),
+            (None, _) => String::from("Data kursów nieznana"),
+        };
+
+        let codes = form.table.codes();
+        let conversion = self
+            .field_value(&form.amount, "kwota")
+            .and_then(|amount| amount.map(|a| form.table.convert(a, &form.from, &form.to)).transpose());
+
+        let mut panel = column![
+            text("💱 WALUTY").size(18).style(theme.display_text),
+            text(freshness).size(14).style(theme.display_text),
+            row![
+                text_input("Ścieżka do pliku JSON/CSV (np. tabela NBP)", &form.path)
+                    .on_input(Message::CurrencyPath)
+                    .on_submit(Message::LoadCurrencyFile)
+                    .width(Length::Fill),
+                button(text("Wczytaj").size(14))
+                    .on_press(Message::LoadCurrencyFile)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            ]
+            .spacing(5),
+            row![
+                text_input("Kwota", &form.amount)
+                    .on_input(Message::CurrencyAmount)
+                    .width(Length::Fill),
+                pick_list(codes.clone(), Some(form.from.clone()), Message::CurrencyFrom),
+                text("→").size(16).style(theme.display_text),
+                pick_list(codes, Some(form.to.clone()), Message::CurrencyTo),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            text("W wyrażeniach: 100 EUR in PLN").size(12).style(theme.display_text),
+        ]
+        .spacing(5);
+
+        match conversion {
+            Ok(Some(value)) => {
+                panel = panel.push(text(format!("= {:.2} {}", value, form.to)).size(20).style(theme.display_text));
+            }
+            Ok(None) => {}
+            Err(error) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+        }
+
+        panel = panel.push(self.table_row(&["Kod", "Kurs (PLN za 1)"], 14));
+        for (index, row) in form.rows.iter().enumerate() {
+            panel = panel.push(
+                row![
+                    text_input("EUR", &row.code)
+                        .on_input(move |v| Message::CurrencyCode(index, v))
+                        .width(Length::Fill),
+                    text_input("0", &row.rate)
+                        .on_input(move |v| Message::CurrencyRate(index, v))
+                        .width(Length::Fill),
+                    button(text("✕").size(14))
+                        .on_press(Message::RemoveCurrency(index))
+                        .style(iced::theme::Button::Custom(Box::new(ButtonStyle(Color::from_rgb(0.8, 0.3, 0.3))))),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+        }
+
+        panel = panel.push(
+            row![
+                button(text("+ Dodaj walutę").size(14))
+                    .on_press(Message::AddCurrency)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                button(text("Zapisz").size(14))
+                    .on_press(Message::SaveCurrencies)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("Wróć").size(14))
+                    .on_press(Message::CloseTool)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+            ]
+            .spacing(5),
+        );
+
+        if let Some(status) = &form.status {
+            panel = panel.push(text(status).size(14).style(theme.display_text));
+        }
+
+        panel.into()
+    }
+
+    fn tool_button(&self, label: &str, tool: EconomicsTool) -> Element<Message> {
+        let theme = self.theme;
+        button(text(label).size(14))
+            .on_press(Message::OpenTool(tool))
+            .padding(10)
+            .width(Length::Fill)
+            .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn))))
+            .into()
+    }
+
+    fn create_statistics_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.stats;
+
+        let mut panel = column![
+            text("📊 STATYSTYKA").size(18).style(theme.display_text),
+            text("Wpisz lub wklej wartości oddzielone spacjami, przecinkami lub średnikami").size(12).style(theme.display_text),
+            text_input("np. 4, 8, 15, 16, 23, 42", &form.input)
+                .on_input(Message::StatsInput)
+                .on_submit(Message::InsertStatsList),
+            row![
+                button(text("Wstaw jako listę").size(14))
+                    .on_press(Message::InsertStatsList)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                self.formula_button("mean(", "mean("),
+                self.formula_button("stdev(", "stdev("),
+                self.formula_button("median(", "median("),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5);
+
+        match &form.summary {
+            Some(Ok(summary)) => {
+                let modes = if summary.modes.is_empty() {
+                    String::from("brak")
+                } else {
+                    summary.modes.iter().map(|m| format!("{:.4}", m)).collect::<Vec<_>>().join("; ")
+                };
+                // A single value has no sample variance.
+                let sample = |value: Option<f64>| value.map_or(String::from("—"), |v| format!("{:.4}", v));
+                let rows = [
+                    ("Liczebność", summary.count.to_string()),
+                    ("Suma", format!("{:.4}", summary.sum)),
+                    ("Średnia", format!("{:.4}", summary.mean)),
+                    ("Mediana", format!("{:.4}", summary.median)),
+                    ("Dominanta", modes),
+                    ("Minimum", format!("{:.4}", summary.min)),
+                    ("Maksimum", format!("{:.4}", summary.max)),
+                    ("Rozstęp", format!("{:.4}", summary.range)),
+                    ("Wariancja (próba)", sample(summary.sample_variance)),
+                    ("Wariancja (populacja)", format!("{:.4}", summary.population_variance)),
+                    ("Odchylenie std. (próba)", sample(summary.sample_stdev)),
+                    ("Odchylenie std. (populacja)", format!("{:.4}", summary.population_stdev)),
+                    ("Kwartyl Q1", format!("{:.4}", summary.q1)),
+                    ("Kwartyl Q3", format!("{:.4}", summary.q3)),
+                    ("Rozstęp międzykwartylowy", format!("{:.4}", summary.iqr)),
+                ];
+                for (label, value) in rows {
+                    panel = panel.push(self.table_row(&[label, &value], 14));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel
+            .push(self.create_regression_panel())
+            .push(self.create_distribution_panel())
+            .into()
+    }
+
+    fn create_plot_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let viewport = self.plot.viewport;
+        let center = ((viewport.x_min + viewport.x_max) / 2.0, (viewport.y_min + viewport.y_max) / 2.0);
+
+        let graph = canvas(PlotCanvas { plot: &self.plot, theme })
+            .width(Length::Fill)
+            .height(Length::Fixed(300.0));
+
+        let mut panel = column![text("📉 WYKRES FUNKCJI").size(18).style(theme.display_text), graph].spacing(5);
+
+        for (index, curve) in self.plot.curves.iter().enumerate() {
+            panel = panel.push(
+                row![
+                    text(format!("■ f{}", index + 1)).size(16).style(theme.curve_color(index)),
+                    pick_list(&CurveKind::ALL[..], Some(curve.kind), move |kind| Message::SelectCurveKind(index, kind)),
+                    text_input(curve.kind.placeholder(), &curve.input)
+                        .on_input(move |v| Message::CurveInput(index, CurveField::Input, v))
+                        .width(Length::Fill),
+                    button(text("✕").size(14))
+                        .on_press(Message::RemoveCurve(index))
+                        .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center),
+            );
+
+            if curve.kind != CurveKind::Function {
+                let (parameter, to) = match curve.kind {
+                    CurveKind::Polar if self.config.angle_mode == AngleMode::Degrees => ("θ°", "360"),
+                    CurveKind::Polar => ("θ", "2π"),
+                    _ => ("t", "10"),
+                };
+                panel = panel.push(
+                    row![
+                        text(format!("{} od", parameter)).size(14).style(theme.display_text),
+                        text_input("0", &curve.from).on_input(move |v| Message::CurveInput(index, CurveField::From, v)),
+                        text("do").size(14).style(theme.display_text),
+                        text_input(to, &curve.to).on_input(move |v| Message::CurveInput(index, CurveField::To, v)),
+                        text("krok").size(14).style(theme.display_text),
+                        text_input("auto", &curve.step).on_input(move |v| Message::CurveInput(index, CurveField::Step, v)),
+                    ]
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+                );
+            }
+            if let Some(error) = &curve.error {
+                panel = panel.push(text(error).size(12).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+        }
+
+        panel = panel.push(
+            row![
+                button(text("+ Funkcja").size(14))
+                    .on_press(Message::AddCurve)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                button(text("🔍+").size(14))
+                    .on_press(Message::PlotZoom(0.8, center.0, center.1))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("🔍−").size(14))
+                    .on_press(Message::PlotZoom(1.25, center.0, center.1))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("Reset").size(14))
+                    .on_press(Message::ResetPlot)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                text(format!(
+                    "x: [{:.3}; {:.3}]  y: [{:.3}; {:.3}]",
+                    viewport.x_min, viewport.x_max, viewport.y_min, viewport.y_max
+                ))
+                .size(12)
+                .style(theme.display_text),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+        );
+
+        let form = &self.plot.export;
+        panel = panel.push(
+            row![
+                text("Eksport").size(14).style(theme.display_text),
+                text_input("plik", &form.path)
+                    .on_input(|v| Message::ExportInput(ExportField::Path, v))
+                    .width(Length::Fill),
+                text_input("szer.", &form.width)
+                    .on_input(|v| Message::ExportInput(ExportField::Width, v))
+                    .width(Length::Fixed(60.0)),
+                text("×").size(14).style(theme.display_text),
+                text_input("wys.", &form.height)
+                    .on_input(|v| Message::ExportInput(ExportField::Height, v))
+                    .width(Length::Fixed(60.0)),
+                button(text("SVG").size(14))
+                    .on_press(Message::ExportPlot(ExportFormat::Svg))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+                button(text("PNG").size(14))
+                    .on_press(Message::ExportPlot(ExportFormat::Png))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+        );
+        if let Some(status) = &form.status {
+            panel = panel.push(text(status).size(12).style(theme.display_text));
+        }
+
+        panel.push(self.create_analysis_panel()).into()
+    }
+
+    fn create_analysis_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.plot.analysis;
+        let choices: Vec<CurveChoice> = (0..self.plot.curves.len()).map(CurveChoice).collect();
+
+        let mut selection = row![
+            pick_list(&analysis::Tool::ALL[..], Some(form.tool), Message::AnalysisTool),
+            pick_list(choices.clone(), Some(form.curve), Message::AnalysisCurve),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+        if form.tool == analysis::Tool::Intersections {
+            selection = selection
+                .push(text("i").size(14).style(theme.display_text))
+                .push(pick_list(choices, Some(form.other), Message::AnalysisOtherCurve));
+        }
+
+        let mut panel = column![
+            text("🔎 ANALIZA WYKRESU").size(16).style(theme.display_text),
+            selection,
+            self.labeled_input("Od x (puste = lewa krawędź)", &form.from, |v| Message::AnalysisInput(AnalysisField::From, v), Message::RunAnalysis),
+            self.labeled_input("Do x (puste = prawa krawędź)", &form.to, |v| Message::AnalysisInput(AnalysisField::To, v), Message::RunAnalysis),
+            self.labeled_input("Tolerancja", &form.tolerance, |v| Message::AnalysisInput(AnalysisField::Tolerance, v), Message::RunAnalysis),
+            self.labeled_input("Liczba podprzedziałów", &form.subdivisions, |v| Message::AnalysisInput(AnalysisField::Subdivisions, v), Message::RunAnalysis),
+            button(text("Analizuj").size(14))
+                .on_press(Message::RunAnalysis)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+        ]
+        .spacing(5);
+
+        match &form.result {
+            Some(Ok(result)) => {
+                if let Some(area) = &result.area {
+                    panel = panel.push(self.analysis_value(
+                        format!("∫ od {:.4} do {:.4} = {:.10} (błąd ≈ {:.1e})", area.from, area.to, area.value, area.error),
+                        area.value,
+                    ));
+                } else if result.markers.is_empty() {
+                    panel = panel.push(text("Nie znaleziono punktów w przedziale").size(14).style(theme.display_text));
+                }
+                for marker in &result.markers {
+                    panel = panel.push(self.analysis_value(
+                        format!("{}: x = {:.10}, y = {:.10}", marker.label, marker.x, marker.y),
+                        marker.x,
+                    ));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    /// A result line with a button inserting `value` into the display.
+    fn analysis_value(&self, label: String, value: f64) -> Element<Message> {
+        row![
+            text(label).size(13).style(self.theme.display_text).width(Length::Fill),
+            button(text("Wstaw").size(12))
+                .on_press(Message::ButtonPressed(value.to_string()))
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(self.theme.number_btn)))),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn create_solver_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.solver;
+
+        let mut panel = column![
+            text("🎯 RÓWNANIA").size(18).style(theme.display_text),
+            text("Wielomiany: wszystkie pierwiastki rzeczywiste. Inne równania: pierwiastki w przedziale (domyślnie od −100 do 100) lub najbliższy punktu startowego.")
+                .size(12)
+                .style(theme.display_text),
+            text_input("np. x^3 - 2x = 5", &form.equation)
+                .on_input(|v| Message::SolverInput(SolverField::Equation, v))
+                .on_submit(Message::Solve),
+            self.labeled_input("Niewiadoma (puste = automatycznie)", &form.variable, |v| Message::SolverInput(SolverField::Variable, v), Message::Solve),
+            self.labeled_input("Od", &form.from, |v| Message::SolverInput(SolverField::From, v), Message::Solve),
+            self.labeled_input("Do", &form.to, |v| Message::SolverInput(SolverField::To, v), Message::Solve),
+            self.labeled_input("Punkt startowy", &form.start, |v| Message::SolverInput(SolverField::Start, v), Message::Solve),
+            row![
+                button(text("Rozwiąż").size(14))
+                    .on_press(Message::Solve)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                self.formula_button("solve(", "solve("),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5);
+
+        match &form.result {
+            Some(Ok(solution)) => {
+                for (x, residual) in &solution.roots {
+                    panel = panel.push(self.analysis_value(
+                        format!("{} = {:.12}  (różnica stron {:.1e})", solution.variable, x, residual),
+                        *x,
+                    ));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.push(self.create_linear_system_panel()).into()
+    }
+
+    fn create_linear_system_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.linear;
+
+        let mut header: Vec<String> = (1..=form.size).map(|i| format!("x{}", i)).collect();
+        header.push(String::from("= b"));
+        let header: Vec<&str> = header.iter().map(String::as_str).collect();
+
+        let mut panel = column![
+            text("🧮 UKŁAD RÓWNAŃ LINIOWYCH").size(18).style(theme.display_text),
+            row![
+                text("Liczba niewiadomych").size(14).style(theme.display_text),
+                pick_list(&linear::SIZES[..], Some(form.size), Message::LinearSize),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            text("Ułamki (1/3) i liczby dziesiętne dają wynik dokładny; puste pole oznacza 0").size(12).style(theme.display_text),
+            self.table_row(&header, 14),
+        ]
+        .spacing(5);
+
+        for (i, cells) in form.cells.iter().enumerate() {
+            let mut inputs = row![].spacing(5);
+            for (j, cell) in cells.iter().enumerate() {
+                inputs = inputs.push(
+                    text_input("0", cell)
+                        .on_input(move |v| Message::LinearInput(i, j, v))
+                        .on_submit(Message::SolveLinear)
+                        .width(Length::Fill),
+                );
+            }
+            panel = panel.push(inputs);
+        }
+
+        panel = panel.push(
+            button(text("Rozwiąż układ").size(14))
+                .on_press(Message::SolveLinear)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+        );
+
+        match &form.result {
+            Some(Ok(result)) => {
+                match &result.solution {
+                    linear::Solution::Unique(values) => {
+                        for (i, (value, number)) in values.iter().enumerate() {
+                            panel = panel.push(self.analysis_value(format!("x{} = {}", i + 1, value), *number));
+                        }
+                    }
+                    linear::Solution::Parametric { rank, equations } => {
+                        panel = panel.push(
+                            text(format!("Układ nieoznaczony (rząd {}) – nieskończenie wiele rozwiązań:", rank))
+                                .size(14)
+                                .style(theme.display_text),
+                        );
+                        for equation in equations {
+                            panel = panel.push(text(equation).size(14).style(theme.display_text));
+                        }
+                    }
+                    linear::Solution::Inconsistent => {
+                        panel = panel.push(text("Układ sprzeczny – brak rozwiązań").size(14).style(theme.display_text));
+                    }
+                }
+                if !result.exact {
+                    panel = panel.push(text("Wynik przybliżony (obliczenia zmiennoprzecinkowe)").size(12).style(theme.display_text));
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_matrix_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.matrix;
+
+        let mut editors = row![].spacing(20);
+        for name in MatrixName::ALL {
+            let input = form.input(name);
+            let mut editor = column![row![
+                text(format!("{} =", name)).size(16).style(theme.display_text),
+                pick_list(&matrix::SIZES[..], Some(input.rows()), move |rows| Message::MatrixRows(name, rows)),
+                text("×").size(14).style(theme.display_text),
+                pick_list(&matrix::SIZES[..], Some(input.cols()), move |cols| Message::MatrixCols(name, cols)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)]
+            .spacing(5)
+            .width(Length::Fill);
+
+            for (i, cells) in input.cells.iter().enumerate() {
+                let mut inputs = row![].spacing(5);
+                for (j, cell) in cells.iter().enumerate() {
+                    inputs = inputs.push(
+                        text_input("0", cell)
+                            .on_input(move |v| Message::MatrixCell(name, i, j, v))
+                            .on_submit(Message::EvaluateMatrix)
+                            .width(Length::Fill),
+                    );
+                }
+                editor = editor.push(inputs);
+            }
+            editors = editors.push(editor);
+        }
+
+        let operations = [
+            ("A+B", "A+B"),
+            ("A−B", "A-B"),
+            ("A×B", "A*B"),
+            ("Aᵀ", "transpose(A)"),
+            ("det A", "det(A)"),
+            ("A⁻¹", "inv(A)"),
+            ("rząd A", "rank(A)"),
+            ("tr A", "trace(A)"),
+            ("eig A", "eigenvalues(A)"),
+        ];
+        let mut buttons = row![].spacing(5);
+        for (label, expression) in operations {
+            buttons = buttons.push(
+                button(text(label).size(13))
+                    .on_press(Message::MatrixOperation(expression))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.function_btn)))),
+            );
+        }
+
+        let mut panel = column![
+            text("🔢 MACIERZE I WEKTORY").size(18).style(theme.display_text),
+            text("Puste pole oznacza 0. W wyrażeniu A i B to macierze z edytorów, np. inv(A)*B, det(A)^2, cross([1,0,0], [0,1,0])")
+                .size(12)
+                .style(theme.display_text),
+            editors,
+            buttons,
+            row![
+                text_input("np. A*B", &form.expression)
+                    .on_input(Message::MatrixExpression)
+                    .on_submit(Message::EvaluateMatrix)
+                    .width(Length::Fill),
+                button(text("Oblicz").size(14))
+                    .on_press(Message::EvaluateMatrix)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5);
+
+        match &form.result {
+            Some(Ok(value)) => {
+                let mut grid = column![].spacing(2);
+                for cells in matrix::grid(value) {
+                    let cells: Vec<String> = cells.into_iter().map(matrix::format_entry).collect();
+                    let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+                    grid = grid.push(self.table_row(&cells, 16));
+                }
+                panel = panel.push(
+                    container(grid)
+                        .padding(10)
+                        .width(Length::Fill)
+                        .style(iced::theme::Container::Custom(Box::new(DisplayStyle(theme.display)))),
+                );
+                panel = panel.push(
+                    row![
+                        button(text("Wstaw").size(13))
+                            .on_press(Message::InsertMatrixResult)
+                            .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                        button(text("→ A").size(13))
+                            .on_press(Message::LoadMatrixResult(MatrixName::A))
+                            .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                        button(text("→ B").size(13))
+                            .on_press(Message::LoadMatrixResult(MatrixName::B))
+                            .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                    ]
+                    .spacing(5),
+                );
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_symbolic_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.symbolic;
+
+        let mut buttons = row![].spacing(5);
+        for operation in Operation::ALL {
+            buttons = buttons.push(
+                button(text(operation.to_string()).size(14))
+                    .on_press(Message::RunSymbolic(operation))
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            );
+        }
+
+        let mut panel = column![
+            text("∂ ALGEBRA SYMBOLICZNA").size(18).style(theme.display_text),
+            text("Na wyświetlaczu: simplify(..), expand(..), factor(..), diff(f, x) lub d/dx f. Funkcje trygonometryczne w radianach.")
+                .size(12)
+                .style(theme.display_text),
+            text_input("np. sin(x)^2 lub (x+1)^3", &form.expression)
+                .on_input(|v| Message::SymbolicInput(SymbolicField::Expression, v))
+                .on_submit(Message::RunSymbolic(Operation::Simplify)),
+            self.labeled_input(
+                "Zmienna (puste = pierwsza w wyrażeniu)",
+                &form.variable,
+                |v| Message::SymbolicInput(SymbolicField::Variable, v),
+                Message::RunSymbolic(Operation::Differentiate),
+            ),
+            buttons,
+        ]
+        .spacing(5);
+
+        match &form.result {
+            Some(Ok(result)) => {
+                panel = panel.push(
+                    container(
+                        row![
+                            text(result).size(16).style(theme.display_text).width(Length::Fill),
+                            button(text("Wstaw").size(12))
+                                .on_press(Message::ButtonPressed(result.clone()))
+                                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+                        ]
+                        .spacing(5)
+                        .align_items(Alignment::Center),
+                    )
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(iced::theme::Container::Custom(Box::new(DisplayStyle(theme.display)))),
+                );
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_distribution_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.distribution;
+
+        let mut panel = column![
+            text("🎲 ROZKŁADY PRAWDOPODOBIEŃSTWA").size(18).style(theme.display_text),
+            pick_list(&Distribution::ALL[..], Some(form.distribution), Message::SelectDistribution).width(Length::Fill),
+        ]
+        .spacing(5);
+
+        for (index, ((label, _), value)) in form.distribution.parameters().iter().zip(&form.parameters).enumerate() {
+            panel = panel.push(self.labeled_input(
+                label,
+                value,
+                move |v| Message::DistributionInput(DistributionField::Parameter(index), v),
+                Message::CalculateDistribution,
+            ));
+        }
+
+        panel = panel
+            .push(self.labeled_input("Dolna granica a (puste = −∞)", &form.lower, |v| Message::DistributionInput(DistributionField::Lower, v), Message::CalculateDistribution))
+            .push(self.labeled_input("Górna granica b (puste = +∞)", &form.upper, |v| Message::DistributionInput(DistributionField::Upper, v), Message::CalculateDistribution))
+            .push(self.labeled_input("Kwantyl dla p =", &form.probability, |v| Message::DistributionInput(DistributionField::Probability, v), Message::CalculateDistribution))
+            .push(
+                button(text("Oblicz").size(14))
+                    .on_press(Message::CalculateDistribution)
+                    .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            );
+
+        match &form.result {
+            Some(Ok(result)) => {
+                panel = panel.push(text(format!("P(a < X < b) = {:.6}", result.interval)).size(16).style(theme.display_text));
+                if let Some(quantile) = result.quantile {
+                    panel = panel.push(text(format!("x dla P(X ≤ x) = p: {:.6}", quantile)).size(16).style(theme.display_text));
+                }
+                panel = panel.push(
+                    text(format!("Wartość oczekiwana: {:.4}, wariancja: {:.4}", result.mean, result.variance))
+                        .size(14)
+                        .style(theme.display_text),
+                );
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn create_regression_panel(&self) -> Element<Message> {
+        let theme = self.theme;
+        let form = &self.regression;
+
+        let mut panel = column![
+            text("📈 REGRESJA (x, y)").size(18).style(theme.display_text),
+            row![
+                text("Wartości x").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                text_input("np. 1 2 3 4", &form.x)
+                    .on_input(|v| Message::RegressionInput(RegressionField::X, v))
+                    .width(Length::FillPortion(3)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            row![
+                text("Wartości y").size(14).style(theme.display_text).width(Length::FillPortion(1)),
+                text_input("np. 2.1 3.9 6.2 8", &form.y)
+                    .on_input(|v| Message::RegressionInput(RegressionField::Y, v))
+                    .width(Length::FillPortion(3)),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+            pick_list(&regression::Model::ALL[..], Some(form.model), Message::RegressionModel).width(Length::Fill),
+        ]
+        .spacing(5);
+
+        if form.model == regression::Model::Polynomial {
+            panel = panel.push(self.labeled_input(
+                "Stopień wielomianu",
+                &form.degree,
+                |v| Message::RegressionInput(RegressionField::Degree, v),
+                Message::InsertPrediction,
+            ));
+        }
+
+        match &form.fit {
+            Some(Ok(fit)) => {
+                panel = panel.push(text(fit.equation()).size(16).style(theme.display_text));
+                panel = panel.push(text(format!("R² = {:.6}", fit.r_squared)).size(14).style(theme.display_text));
+                if let Some(r) = form.correlation {
+                    panel = panel.push(text(format!("Korelacja Pearsona r = {:.6}", r)).size(14).style(theme.display_text));
+                }
+
+                panel = panel.push(self.labeled_input(
+                    "Prognoza y dla x =",
+                    &form.predict,
+                    |v| Message::RegressionInput(RegressionField::Predict, v),
+                    Message::InsertPrediction,
+                ));
+                if let Some(prediction) = form.prediction {
+                    panel = panel.push(
+                        row![
+                            text(format!("y = {:.6}", prediction)).size(16).style(theme.display_text).width(Length::Fill),
+                            button(text("Wstaw").size(14))
+                                .on_press(Message::InsertPrediction)
+                                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+                        ]
+                        .spacing(5)
+                        .align_items(Alignment::Center),
+                    );
+                }
+            }
+            Some(Err(error)) => {
+                panel = panel.push(text(error).size(14).style(Color::from_rgb(0.8, 0.3, 0.3)));
+            }
+            None => {}
+        }
+
+        panel.into()
+    }
+
+    fn tool_actions(&self, calculate: Message) -> Element<Message> {
+        let theme = self.theme;
+        row![
+            button(text("Oblicz").size(14))
+                .on_press(calculate)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.operator_btn)))),
+            button(text("Wróć").size(14))
+                .on_press(Message::CloseTool)
+                .style(iced::theme::Button::Custom(Box::new(ButtonStyle(theme.number_btn)))),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    fn labeled_input<'a>(
+        &self,
+        label: &str,
+        value: &str,
+        on_input: impl Fn(String) -> Message + 'a,
+        on_submit: Message,
+    ) -> Element<'a, Message> {
+        row![
+            text(label).size(14).style(self.theme.display_text).width(Length::FillPortion(1)),
+            text_input("0", value)
+                .on_input(on_input)
+                .on_submit(on_submit)
+                .width(Length::FillPortion(1)),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn table_row(&self, cells: &[&str], size: u16) -> Element<Message> {
+        let mut cells_row = row![].spacing(5);
+        for cell in cells {
+            cells_row = cells_row.push(text(cell).size(size).style(self.theme.display_text).width(Length::Fill));
+        }
+        cells_row.into()
+    }
+
+    fn create_theme_editor(&self) -> Element<Message> {
+        let theme = self.theme;
+        
+        let bg_editor = self.color_editor("Tło", ColorTarget::Background, theme.background);
+        let disp_editor = self.color_editor("Wyświetlacz", ColorTarget::Display, theme.display);
+        let text_editor = self.color_editor("Tekst", ColorTarget::DisplayText, theme.display_text);
+        let num_editor = self.color_editor("Przyciski cyfr", ColorTarget::Number, theme.number_btn);
+        let op_editor = self.color_editor("Operatory", ColorTarget::Operator, theme.operator_btn);
+        let fn_editor = self.color_editor("Funkcje", ColorTarget::Function, theme.function_btn);
+
+        container(
+            scrollable(
+                column![
+                    text("🎨 EDYTOR MOTYWU").size(20),
+                    bg_editor,
+                    disp_editor,
+                    text_editor,
+                    num_editor,
+                    op_editor,
+                    fn_editor,
+                ]
+                .spacing(10)
+            )
+            .height(Length::Fixed(250.0))
+        )
+        .padding(10)
+        .style(iced::theme::Container::Custom(Box::new(EditorStyle(theme))))
+        .into()
+    }
+
+    fn color_editor(&self, label: &str, target: ColorTarget, color: Color) -> Element<Message> {
+        let r_val = (color.r * 255.0) as u8;
+        let g_val = (color.g * 255.0) as u8;
+        let b_val = (color.b * 255.0) as u8;
+
+        column![
+            text(label).size(14),
+            row![
+                text("R:").size(12),
+                text_input(&r_val.to_string(), &r_val.to_string())
                     .on_input(move |v| Message::UpdateColor(target, ColorChannel::Red, v))
                     .width(Length::Fixed(50.0)),
                 text("G:").size(12),
@@ -620,16 +2751,750 @@ impl Calculator {
             .into()
     }
 
+    fn insert(&mut self, value: &str) {
+        if self.display == "0" || self.display == "Error" {
+            self.display = value.to_string();
+        } else {
+            self.display.push_str(value);
+        }
+    }
+
+    fn solve_formula(&self, form: &FormulaForm) -> Result<(usize, f64), String> {
+        let formula = form.formula();
+        let mut values = Vec::with_capacity(form.inputs.len());
+
+        for (input, variable) in form.inputs.iter().zip(formula.variables) {
+            values.push(self.field_value(input, variable.symbol)?);
+        }
+
+        formula.solve(&values)
+    }
+
+    fn calculate_loan(&self) -> Result<loan::Schedule, String> {
+        let form = &self.loan;
+        let loan = Loan {
+            principal: self.required_value(&form.principal, "kwota kredytu")?,
+            annual_rate: self.required_value(&form.rate, "oprocentowanie")?,
+            years: self.required_value(&form.years, "okres")?,
+            frequency: form.frequency,
+            kind: form.kind,
+            extra_payment: self.field_value(&form.extra, "nadpłata")?.unwrap_or(0.0),
+        };
+
+        loan.schedule()
+    }
+
+    fn solve_tvm(&self) -> Result<(TvmField, f64), String> {
+        let form = &self.tvm;
+        let worksheet = Worksheet {
+            n: self.field_value(&form.n, "N")?,
+            rate: self.field_value(&form.rate, "I/Y")?,
+            pv: self.field_value(&form.pv, "PV")?,
+            pmt: self.field_value(&form.pmt, "PMT")?,
+            fv: self.field_value(&form.fv, "FV")?,
+            payments_per_year: self.required_value(&form.payments_per_year, "P/Y")?,
+            compounds_per_year: self.required_value(&form.compounds_per_year, "C/Y")?,
+            due: form.due,
+        };
+
+        worksheet.solve()
+    }
+
+    fn calculate_savings(&self) -> Result<savings::SavingsResult, String> {
+        let form = &self.savings;
+        let years = self.required_value(&form.years, "okres")?;
+        if years < 0.0 {
+            return Err("Okres nie może być ujemny".to_string());
+        }
+
+        let savings = Savings {
+            principal: self.field_value(&form.principal, "kapitał")?.unwrap_or(0.0),
+            annual_rate: self.required_value(&form.rate, "oprocentowanie")?,
+            compounding: form.compounding,
+            contribution: self.field_value(&form.contribution, "wpłata")?.unwrap_or(0.0),
+            contribution_frequency: form.contribution_frequency,
+            years: years.round() as u32,
+            tax_rate: self.field_value(&form.tax, "podatek")?.unwrap_or(0.0),
+            inflation: self.field_value(&form.inflation, "inflacja")?.unwrap_or(0.0),
+        };
+
+        savings.simulate()
+    }
+
+    fn calculate_depreciation(&self) -> Result<Vec<depreciation::DepreciationRow>, String> {
+        let form = &self.depreciation;
+        let life = self.required_value(&form.life, "okres użytkowania")?;
+        if life < 0.0 {
+            return Err("Okres użytkowania nie może być ujemny".to_string());
+        }
+
+        let asset = Asset {
+            cost: self.required_value(&form.cost, "cena nabycia")?,
+            salvage: self.field_value(&form.salvage, "wartość końcowa")?.unwrap_or(0.0),
+            life: life.round() as u32,
+            factor: self.field_value(&form.factor, "współczynnik")?.unwrap_or(2.0),
+        };
+
+        asset.schedule(form.method)
+    }
+
+    /// Recomputes the pricing fields after `field` was edited.
+    fn update_pricing(&mut self, field: PricingField) -> Result<(), String> {
+        let Some(value) = self.field_value(self.pricing.field(field), "wartość")? else {
+            return Ok(());
+        };
+        let Some(partner) = self.pricing.edited(field) else {
+            return Ok(());
+        };
+        let Some(partner_value) = self.field_value(self.pricing.field(partner), "wartość")? else {
+            return Ok(());
+        };
+
+        let prices = pricing::solve((field, value), (partner, partner_value))?;
+        self.pricing.fill(&prices, field);
+        Ok(())
+    }
+
+    /// Break-even for the entered values, `None` until all are filled in.
+    fn calculate_break_even(&self) -> Option<Result<pricing::BreakEven, String>> {
+        let form = &self.pricing;
+        let fields = [
+            (&form.fixed_costs, "koszty stałe"),
+            (&form.unit_price, "cena jednostkowa"),
+            (&form.unit_variable_cost, "koszt zmienny"),
+        ];
+
+        let mut values = [0.0; 3];
+        for (slot, (input, label)) in values.iter_mut().zip(fields) {
+            match self.field_value(input, label) {
+                Ok(Some(value)) => *slot = value,
+                Ok(None) => return None,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        Some(pricing::break_even(values[0], values[1], values[2]))
+    }
+
+    fn analyze_cashflows(&self) -> Result<cashflow::CashFlowSummary, String> {
+        let form = &self.cashflow;
+        let rate = self.required_value(&form.rate, "stopa dyskontowa")? / 100.0;
+
+        let mut flows = Vec::with_capacity(form.rows.len());
+        for (index, row) in form.rows.iter().enumerate() {
+            flows.push(self.field_value(&row.amount, &format!("t={}", index))?.unwrap_or(0.0));
+        }
+
+        // Dated analysis only once every row has a date.
+        let dates = if form.rows.iter().all(|row| !row.date.trim().is_empty()) {
+            let mut dates = Vec::with_capacity(form.rows.len());
+            for (index, row) in form.rows.iter().enumerate() {
+                let date = cashflow::parse_date(&row.date)
+                    .ok_or_else(|| format!("Niepoprawna data: t={}", index))?;
+                dates.push(date as f64);
+            }
+            Some(dates)
+        } else {
+            None
+        };
+
+        let finance = self.field_value(&form.finance_rate, "koszt finansowania")?;
+        let reinvest = self.field_value(&form.reinvest_rate, "stopa reinwestycji")?;
+        let mirr_rates = finance.zip(reinvest).map(|(f, r)| (f / 100.0, r / 100.0));
+
+        cashflow::summarize(rate, &flows, dates.as_deref(), mirr_rates)
+    }
+
+    fn update_regression(&mut self) {
+        self.regression.refit();
+        if let Some(Ok(fit)) = &self.regression.fit {
+            let x = self.field_value(&self.regression.predict, "x").ok().flatten();
+            self.regression.prediction = x.map(|x| fit.predict(x)).filter(|y| y.is_finite());
+        }
+    }
+
+    fn calculate_distribution(&self) -> Result<DistributionResult, String> {
+        let form = &self.distribution;
+        let distribution = form.distribution;
+
+        let params = form
+            .parameters
+            .iter()
+            .zip(distribution.parameters())
+            .map(|(input, (label, _))| self.required_value(input, label))
+            .collect::<Result<Vec<_>, _>>()?;
+        distribution.validate(&params)?;
+
+        let lower = self.field_value(&form.lower, "dolna granica")?;
+        let upper = self.field_value(&form.upper, "górna granica")?;
+        let probability = self.field_value(&form.probability, "prawdopodobieństwo")?;
+        if probability.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+            return Err("Prawdopodobieństwo musi należeć do przedziału [0, 1]".to_string());
+        }
+
+        let (mean, variance) = distribution.mean_variance(&params);
+        Ok(DistributionResult {
+            interval: distribution.interval(&params, lower, upper),
+            quantile: probability.map(|p| distribution.inverse(&params, p)),
+            mean,
+            variance,
+        })
+    }
+
+    /// Evaluates a form field; an empty field yields `None`.
+    fn field_value(&self, input: &str, label: &str) -> Result<Option<f64>, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        self.evaluate(input)
+            .map(Some)
+            .map_err(|_| format!("Niepoprawna wartość: {}", label))
+    }
+
+    fn required_value(&self, input: &str, label: &str) -> Result<f64, String> {
+        self.field_value(input, label)?
+            .ok_or_else(|| format!("Uzupełnij pole: {}", label))
+    }
+
     fn evaluate(&self, expr: &str) -> Result<f64, String> {
-        let expr = expr
-            .replace("π", &PI.to_string())
-            .replace("√", "sqrt");
+        self.evaluate_value(expr)?.as_number()
+    }
+
+    /// Samples every curve over the current viewport.
+    fn resample_plot(&mut self) {
+        let mut ctx = self.context();
+        let viewport = self.plot.viewport;
+        let angle_mode = self.config.angle_mode;
+
+        for curve in &mut self.plot.curves {
+            curve.segments.clear();
+            curve.error = None;
+            if curve.expression().is_empty() {
+                continue;
+            }
+
+            match sample_curve(&mut ctx, curve, &viewport, angle_mode) {
+                Ok(segments) => curve.segments = segments,
+                Err(error) => curve.error = Some(error),
+            }
+        }
+    }
+
+    fn run_analysis(&self) -> Result<AnalysisResult, String> {
+        let form = &self.plot.analysis;
+        let viewport = self.plot.viewport;
+
+        let tolerance = self.required_value(&form.tolerance, "tolerancja")?;
+        let subdivisions = self.required_value(&form.subdivisions, "liczba podprzedziałów")?;
+        if tolerance <= 0.0 || subdivisions < 2.0 {
+            return Err("Tolerancja musi być dodatnia, a podprzedziałów co najmniej 2".to_string());
+        }
+        let settings = analysis::Settings { tolerance, subdivisions: subdivisions as usize };
+
+        let from = self.field_value(&form.from, "od x")?.unwrap_or(viewport.x_min);
+        let to = self.field_value(&form.to, "do x")?.unwrap_or(viewport.x_max);
+        if from >= to {
+            return Err("Początek przedziału musi być mniejszy od końca".to_string());
+        }
+
+        let parse_curve = |choice: CurveChoice| -> Result<expr::Expr, String> {
+            let curve = self.plot.curves.get(choice.0).ok_or_else(|| "Wybierz funkcję".to_string())?;
+            if curve.kind != CurveKind::Function {
+                return Err("Analiza dotyczy tylko wykresów y = f(x)".to_string());
+            }
+            if curve.expression().is_empty() {
+                return Err(format!("Funkcja {} jest pusta", choice));
+            }
+            expr::parse(curve.expression())
+        };
+        let f = parse_curve(form.curve)?;
+
+        let mut ctx = self.context();
+        let mut eval = |e: &expr::Expr, x: f64| {
+            ctx.var("x", x);
+            ctx.eval(e).and_then(|v| v.as_number()).unwrap_or(f64::NAN)
+        };
+
+        let mut result = AnalysisResult::default();
+        match form.tool {
+            analysis::Tool::Roots => {
+                for x in analysis::roots(&mut |x| eval(&f, x), from, to, &settings) {
+                    result.markers.push(analysis::Marker { x, y: 0.0, label: String::from("Zero") });
+                }
+            }
+            analysis::Tool::Extrema => {
+                for extremum in analysis::extrema(&mut |x| eval(&f, x), from, to, &settings) {
+                    let label = match extremum.kind {
+                        analysis::ExtremumKind::Minimum => "Minimum",
+                        analysis::ExtremumKind::Maximum => "Maksimum",
+                    };
+                    result.markers.push(analysis::Marker { x: extremum.x, y: extremum.y, label: label.to_string() });
+                }
+            }
+            analysis::Tool::Intersections => {
+                if form.other == form.curve {
+                    return Err("Wybierz dwie różne funkcje".to_string());
+                }
+                let g = parse_curve(form.other)?;
+                let xs = analysis::roots(&mut |x| eval(&f, x) - eval(&g, x), from, to, &settings);
+                for x in xs {
+                    let y = eval(&f, x);
+                    result.markers.push(analysis::Marker { x, y, label: String::from("Przecięcie") });
+                }
+            }
+            analysis::Tool::Integral => {
+                let (value, error) = calculus::integrate(&mut |x| eval(&f, x), from, to)?;
+                result.area = Some(analysis::Area { curve: form.curve.0, from, to, value, error });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn solve_equation(&self) -> Result<solver::Solution, String> {
+        let form = &self.solver;
+        if form.equation.trim().is_empty() {
+            return Err("Wpisz równanie".to_string());
+        }
+
+        let from = self.field_value(&form.from, "od")?;
+        let to = self.field_value(&form.to, "do")?;
+        let start = self.field_value(&form.start, "punkt startowy")?;
+        let search = match (from, to, start) {
+            (Some(a), Some(b), _) => Search::Interval(a, b),
+            (None, None, Some(x0)) => Search::Guess(x0),
+            (None, None, None) => Search::Auto,
+            _ => return Err("Podaj oba końce przedziału".to_string()),
+        };
+
+        solver::solve_equation(&self.context(), &form.equation, &form.variable, search)
+    }
+
+    /// Exact elimination when every cell is a plain fraction, otherwise the
+    /// cells are evaluated as expressions and solved in floating point.
+    fn solve_linear_system(&self) -> Result<linear::SystemSolution, String> {
+        let form = &self.linear;
+        if let Some(solution) = form.rational_matrix().and_then(linear::solve_exact) {
+            return Ok(solution);
+        }
+
+        let mut matrix = Vec::with_capacity(form.size);
+        for (i, cells) in form.cells.iter().enumerate() {
+            let mut values = Vec::with_capacity(cells.len());
+            for (j, cell) in cells.iter().enumerate() {
+                let label = if j == form.size { format!("b{}", i + 1) } else { format!("a{}{}", i + 1, j + 1) };
+                values.push(self.field_value(cell, &label)?.unwrap_or(0.0));
+            }
+            matrix.push(values);
+        }
+        linear::solve_approximate(matrix)
+    }
+
+    /// Evaluates the matrix panel's expression with A and B taken from the editors.
+    fn evaluate_matrix(&self) -> Result<Value, String> {
+        let form = &self.matrix;
+        let mut ctx = self.context();
+        for name in MatrixName::ALL {
+            let mut rows = Vec::new();
+            for (i, cells) in form.input(name).cells.iter().enumerate() {
+                let mut row = Vec::new();
+                for (j, cell) in cells.iter().enumerate() {
+                    let label = format!("{}[{}, {}]", name, i + 1, j + 1);
+                    row.push(self.field_value(cell, &label)?.unwrap_or(0.0));
+                }
+                rows.push(row);
+            }
+            ctx.define(&name.to_string(), matrix::Matrix::from_rows(&rows)?.to_value());
+        }
+
+        if form.expression.trim().is_empty() {
+            return Err("Wpisz wyrażenie, np. A*B".to_string());
+        }
+        ctx.eval_str(&form.expression)
+    }
+
+    fn run_symbolic(&self, operation: Operation) -> Result<String, String> {
+        let form = &self.symbolic;
+        if form.expression.trim().is_empty() {
+            return Err("Wpisz wyrażenie, np. (x+1)^2".to_string());
+        }
+        let input = expr::parse(&form.expression)?;
+        symbolic::apply(operation, &input, &form.variable, 1).map(|result| result.to_string())
+    }
+
+    /// Writes the current plot in the theme's colors; returns the path written.
+    fn export_plot(&self, format: ExportFormat) -> Result<std::path::PathBuf, String> {
+        let form = &self.plot.export;
+        if form.path.trim().is_empty() {
+            return Err("Podaj nazwę pliku".to_string());
+        }
+        let (width, height) = form.size()?;
+
+        let theme = self.theme;
+        let rgb = |c: Color| [c.r, c.g, c.b];
+        let palette = export::Palette {
+            background: rgb(theme.display),
+            foreground: rgb(theme.display_text),
+            marker: rgb(theme.operator_btn),
+            curves: (0..self.plot.curves.len().max(1)).map(|i| rgb(theme.curve_color(i))).collect(),
+        };
+        let svg = export::svg(&self.plot, &palette, width, height);
+
+        let path = match format {
+            ExportFormat::Svg => export::with_extension(&form.path, "svg"),
+            ExportFormat::Png => export::with_extension(&form.path, "png"),
+        };
+        match format {
+            ExportFormat::Svg => export::save_svg(&svg, &path)?,
+            ExportFormat::Png => export::save_png(&svg, &path)?,
+        }
+        Ok(path)
+    }
 
-        match meval::eval_str(&expr) {
-            Ok(result) => Ok(result),
-            Err(_) => Err("Error".to_string()),
+    /// A numeric result in the chosen format; fractions are exact when the
+    /// input is made of fractions alone, otherwise recovered from the decimal.
+    fn format_number(&self, input: &str, value: f64) -> String {
+        self.config
+            .number_format
+            .format(value, symbolic::exact_value(input), self.config.max_denominator)
+    }
+
+    fn evaluate_value(&self, expr: &str) -> Result<Value, String> {
+        self.context().eval_str(expr)
+    }
+
+    /// The evaluator with every calculator function registered.
+    fn context(&self) -> Context {
+        let mut ctx = Context::new();
+        ctx.set_angle_mode(self.config.angle_mode);
+        tvm::register(&mut ctx);
+        cashflow::register(&mut ctx);
+        savings::register(&mut ctx);
+        percent::register(&mut ctx);
+        stats::register(&mut ctx);
+        distributions::register(&mut ctx);
+        solver::register(&mut ctx);
+        matrix::register(&mut ctx);
+        number_theory::register(&mut ctx);
+        // After stats: sum() of a single list falls back to its function.
+        calculus::register(&mut ctx);
+        self.currency.table.register(&mut ctx);
+        ctx
+    }
+}
+
+const PLOT_SAMPLES: usize = 800;
+const MAX_PARAMETER_STEPS: f64 = 100_000.0;
+
+/// Samples one curve. Points outside the domain are expected, so evaluation
+/// errors are only reported for a curve that could not be drawn at all.
+fn sample_curve(
+    ctx: &mut Context,
+    curve: &Curve,
+    viewport: &plot::Viewport,
+    angle_mode: AngleMode,
+) -> Result<Vec<plot::Segment>, String> {
+    let mut error = None;
+    let mut eval = |ctx: &Context, e: &expr::Expr| {
+        ctx.eval(e).and_then(|v| v.as_number()).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            f64::NAN
+        })
+    };
+
+    let segments = if curve.kind == CurveKind::Function {
+        let f = expr::parse(curve.expression())?;
+        plot::sample(
+            |x| {
+                ctx.var("x", x);
+                eval(ctx, &f)
+            },
+            viewport,
+            PLOT_SAMPLES,
+        )
+    } else {
+        let range_value = |input: &str, default: f64, label: &str| -> Result<f64, String> {
+            if input.trim().is_empty() {
+                return Ok(default);
+            }
+            ctx.eval_str(input)
+                .and_then(|v| v.as_number())
+                .map_err(|_| format!("Niepoprawna wartość: {}", label))
+        };
+        let default_to = if curve.kind == CurveKind::Polar { angle_mode.full_turn() } else { 10.0 };
+        let from = range_value(&curve.from, 0.0, "początek zakresu")?;
+        let to = range_value(&curve.to, default_to, "koniec zakresu")?;
+        let step = range_value(&curve.step, (to - from) / 1000.0, "krok")?;
+        if from >= to || step <= 0.0 {
+            return Err("Zakres parametru musi być rosnący, a krok dodatni".to_string());
+        }
+        if (to - from) / step > MAX_PARAMETER_STEPS {
+            return Err("Zbyt mały krok dla tego zakresu".to_string());
+        }
+
+        if curve.kind == CurveKind::Parametric {
+            let (x, y) = curve
+                .parametric_parts()
+                .ok_or_else(|| "Podaj x(t) i y(t) oddzielone przecinkiem".to_string())?;
+            let (x, y) = (expr::parse(x)?, expr::parse(y)?);
+            plot::sample_parametric(
+                |t| {
+                    ctx.var("t", t);
+                    (eval(ctx, &x), eval(ctx, &y))
+                },
+                from,
+                to,
+                step,
+                viewport,
+            )
+        } else {
+            let r = expr::parse(curve.expression())?;
+            plot::sample_parametric(
+                |theta| {
+                    ctx.var("θ", theta).var("theta", theta);
+                    let radius = eval(ctx, &r);
+                    let angle = angle_mode.to_radians(theta);
+                    (radius * angle.cos(), radius * angle.sin())
+                },
+                from,
+                to,
+                step,
+                viewport,
+            )
+        }
+    };
+
+    match error {
+        Some(error) if segments.is_empty() => Err(error),
+        _ => Ok(segments),
+    }
+}
+
+/// Draws the curves of a `PlotForm`; dragging pans and the wheel zooms.
+struct PlotCanvas<'a> {
+    plot: &'a PlotForm,
+    theme: CalcTheme,
+}
+
+#[derive(Default)]
+struct PlotInteraction {
+    drag_from: Option<Point>,
+}
+
+impl<'a> canvas::Program<Message> for PlotCanvas<'a> {
+    type State = PlotInteraction;
+
+    fn update(
+        &self,
+        state: &mut PlotInteraction,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        let viewport = &self.plot.viewport;
+        let Some(position) = cursor.position_in(bounds) else {
+            state.drag_from = None;
+            return (canvas::event::Status::Ignored, None);
+        };
+
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                state.drag_from = Some(position);
+                (canvas::event::Status::Captured, None)
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.drag_from = None;
+                (canvas::event::Status::Captured, None)
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let Some(from) = state.drag_from else {
+                    return (canvas::event::Status::Ignored, None);
+                };
+                state.drag_from = Some(position);
+                let dx = -(position.x - from.x) as f64 / bounds.width as f64 * viewport.width();
+                let dy = (position.y - from.y) as f64 / bounds.height as f64 * viewport.height();
+                (canvas::event::Status::Captured, Some(Message::PlotPan(dx, dy)))
+            }
+            canvas::Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / 50.0,
+                };
+                let (x, y) = viewport.graph_point(position.x, position.y, bounds.width, bounds.height);
+                (canvas::event::Status::Captured, Some(Message::PlotZoom(0.9f64.powf(lines as f64), x, y)))
+            }
+            _ => (canvas::event::Status::Ignored, None),
         }
     }
+
+    fn draw(
+        &self,
+        _state: &PlotInteraction,
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let theme = self.theme;
+        let viewport = &self.plot.viewport;
+        let (width, height) = (bounds.width, bounds.height);
+        let point = |x: f64, y: f64| {
+            let (px, py) = viewport.screen_point(x, y, width, height);
+            Point::new(px, py)
+        };
+
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), theme.display);
+
+        let grid = Color { a: 0.15, ..theme.display_text };
+        let axis = Color { a: 0.7, ..theme.display_text };
+        let label = |content: String, position: Point, color: Color| canvas::Text {
+            content,
+            position,
+            color,
+            size: 11.0,
+            ..Default::default()
+        };
+
+        // Labels sit along the axes, or along the edge when an axis is off-screen.
+        let origin = point(0.0, 0.0);
+        let label_y = origin.y.clamp(0.0, height - 14.0);
+        let label_x = origin.x.clamp(0.0, width - 40.0);
+
+        let x_step = plot::grid_step(viewport.width(), 8.0);
+        for x in plot::grid_lines(viewport.x_min, viewport.x_max, x_step) {
+            let px = point(x, 0.0).x;
+            let color = if x == 0.0 { axis } else { grid };
+            frame.stroke(
+                &canvas::Path::line(Point::new(px, 0.0), Point::new(px, height)),
+                canvas::Stroke::default().with_color(color).with_width(1.0),
+            );
+            if x != 0.0 {
+                frame.fill_text(label(format_tick(x, x_step), Point::new(px + 2.0, label_y + 2.0), axis));
+            }
+        }
+
+        let y_step = plot::grid_step(viewport.height(), 6.0);
+        for y in plot::grid_lines(viewport.y_min, viewport.y_max, y_step) {
+            let py = point(0.0, y).y;
+            let color = if y == 0.0 { axis } else { grid };
+            frame.stroke(
+                &canvas::Path::line(Point::new(0.0, py), Point::new(width, py)),
+                canvas::Stroke::default().with_color(color).with_width(1.0),
+            );
+            if y != 0.0 {
+                frame.fill_text(label(format_tick(y, y_step), Point::new(label_x + 2.0, py + 2.0), axis));
+            }
+        }
+
+        frame.fill_text(label(String::from("x"), Point::new(width - 12.0, label_y - 16.0), theme.display_text));
+        frame.fill_text(label(String::from("y"), Point::new(label_x + 6.0, 2.0), theme.display_text));
+
+        for (index, curve) in self.plot.curves.iter().enumerate() {
+            let stroke = canvas::Stroke::default().with_color(theme.curve_color(index)).with_width(2.0);
+            for segment in &curve.segments {
+                let path = canvas::Path::new(|builder| {
+                    let mut points = segment.iter().map(|&(x, y)| point(x, y));
+                    if let Some(first) = points.next() {
+                        builder.move_to(first);
+                    }
+                    for next in points {
+                        builder.line_to(next);
+                    }
+                });
+                frame.stroke(&path, stroke.clone());
+            }
+
+            // Legend
+            frame.fill_text(canvas::Text {
+                content: format!("f{}: {}", index + 1, curve.input),
+                position: Point::new(width - 150.0, 4.0 + index as f32 * 16.0),
+                color: theme.curve_color(index),
+                size: 13.0,
+                ..Default::default()
+            });
+        }
+
+        // Analysis results
+        if let Some(Ok(result)) = &self.plot.analysis.result {
+            if let Some(area) = &result.area {
+                let fill = Color { a: 0.3, ..theme.curve_color(area.curve) };
+                for segment in self.plot.curves.get(area.curve).map_or(&[][..], |c| &c.segments[..]) {
+                    let inside: Vec<_> = segment.iter().filter(|(x, _)| *x >= area.from && *x <= area.to).collect();
+                    let (Some(first), Some(last)) = (inside.first(), inside.last()) else {
+                        continue;
+                    };
+                    let path = canvas::Path::new(|builder| {
+                        builder.move_to(point(first.0, 0.0));
+                        for &&(x, y) in &inside {
+                            builder.line_to(point(x, y));
+                        }
+                        builder.line_to(point(last.0, 0.0));
+                        builder.close();
+                    });
+                    frame.fill(&path, fill);
+                }
+            }
+            for marker in &result.markers {
+                let center = point(marker.x, marker.y);
+                frame.fill(&canvas::Path::circle(center, 5.0), theme.display_text);
+                frame.fill(&canvas::Path::circle(center, 3.0), theme.operator_btn);
+            }
+        }
+
+        // Trace the curve passing closest to the cursor.
+        if let Some(position) = cursor.position_in(bounds) {
+            let (cx, cy) = viewport.graph_point(position.x, position.y, width, height);
+            let tolerance = viewport.width() / 100.0;
+            let traced = self
+                .plot
+                .curves
+                .iter()
+                .enumerate()
+                .filter_map(|(index, curve)| {
+                    let nearest = if curve.kind == CurveKind::Function {
+                        plot::nearest(&curve.segments, cx).filter(|(x, _)| (x - cx).abs() <= tolerance)
+                    } else {
+                        plot::nearest_point(&curve.segments, cx, cy, viewport)
+                            .filter(|(x, y)| (x - cx).abs() <= viewport.width() / 20.0 && (y - cy).abs() <= viewport.height() / 20.0)
+                    };
+                    nearest.map(|p| (index, p))
+                })
+                .min_by(|a, b| (a.1 .1 - cy).abs().total_cmp(&(b.1 .1 - cy).abs()));
+
+            if let Some((index, (x, y))) = traced {
+                let marker = point(x, y);
+                frame.fill(&canvas::Path::circle(marker, 4.0), theme.curve_color(index));
+                frame.fill_text(canvas::Text {
+                    content: format!("({:.4}; {:.4})", x, y),
+                    position: Point::new(marker.x + 8.0, marker.y - 18.0),
+                    color: theme.display_text,
+                    size: 12.0,
+                    ..Default::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, state: &PlotInteraction, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if state.drag_from.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+/// Formats a grid value with as many decimals as the grid step needs.
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, value)
 }
 
 struct EditorStyle(CalcTheme);
@@ -0,0 +1,689 @@
+// Matrices and vectors in the expression language. They are written as
+// nested lists: `[1, 2, 3]` is a vector and `[[1, 2], [3, 4]]` a matrix given
+// row by row. The evaluator hands operators on lists over to `binary`.
+
+use std::fmt;
+
+use crate::expr::{Context, Op, Value};
+use crate::rational::{NumberFormat, Rational};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self { rows, cols, data: vec![0.0; rows * cols] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m.data[i * n + i] = 1.0;
+        }
+        m
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.data[row * self.cols + col]
+    }
+
+    fn set(&mut self, row: usize, col: usize, value: f64) {
+        self.data[row * self.cols + col] = value;
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.data.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+
+    pub fn from_rows(rows: &[Vec<f64>]) -> Result<Self, String> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if cols == 0 || rows.iter().any(|row| row.len() != cols) {
+            return Err("Wiersze macierzy muszą mieć tę samą, niezerową długość".to_string());
+        }
+        Ok(Self { rows: rows.len(), cols, data: rows.concat() })
+    }
+
+    pub fn to_value(&self) -> Value {
+        Value::List(
+            (0..self.rows)
+                .map(|r| Value::List((0..self.cols).map(|c| Value::Num(self.get(r, c))).collect()))
+                .collect(),
+        )
+    }
+
+    fn map(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self { rows: self.rows, cols: self.cols, data: self.data.iter().map(|v| f(*v)).collect() }
+    }
+
+    fn zip(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Result<Self, String> {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            return Err(format!(
+                "Niezgodne wymiary: {}×{} i {}×{}",
+                self.rows, self.cols, other.rows, other.cols
+            ));
+        }
+        let data = self.data.iter().zip(&other.data).map(|(a, b)| f(*a, *b)).collect();
+        Ok(Self { rows: self.rows, cols: self.cols, data })
+    }
+
+    pub fn multiply(&self, other: &Self) -> Result<Self, String> {
+        if self.cols != other.rows {
+            return Err(format!(
+                "Nie można pomnożyć macierzy {}×{} przez {}×{}",
+                self.rows, self.cols, other.rows, other.cols
+            ));
+        }
+        let mut product = Self::zeros(self.rows, other.cols);
+        for r in 0..self.rows {
+            for c in 0..other.cols {
+                product.set(r, c, (0..self.cols).map(|k| self.get(r, k) * other.get(k, c)).sum());
+            }
+        }
+        Ok(product)
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                t.set(c, r, self.get(r, c));
+            }
+        }
+        t
+    }
+
+    fn require_square(&self, operation: &str) -> Result<usize, String> {
+        if self.rows == self.cols {
+            Ok(self.rows)
+        } else {
+            Err(format!("{}: macierz musi być kwadratowa ({}×{})", operation, self.rows, self.cols))
+        }
+    }
+
+    /// Entries below this size relative to the largest one count as zero.
+    fn tolerance(&self) -> f64 {
+        1e-12 * self.data.iter().fold(0.0, |max: f64, v| max.max(v.abs())).max(1.0)
+    }
+
+    pub fn trace(&self) -> Result<f64, String> {
+        let n = self.require_square("trace")?;
+        Ok((0..n).map(|i| self.get(i, i)).sum())
+    }
+
+    /// LU decomposition with partial pivoting.
+    pub fn determinant(&self) -> Result<f64, String> {
+        let n = self.require_square("det")?;
+        let mut m = self.clone();
+        let mut det = 1.0;
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|&a, &b| m.get(a, col).abs().total_cmp(&m.get(b, col).abs()))
+                .unwrap_or(col);
+            if m.get(pivot, col) == 0.0 {
+                return Ok(0.0);
+            }
+            if pivot != col {
+                m.swap_rows(pivot, col);
+                det = -det;
+            }
+            det *= m.get(col, col);
+            for row in col + 1..n {
+                let factor = m.get(row, col) / m.get(col, col);
+                for c in col..n {
+                    m.set(row, c, m.get(row, c) - factor * m.get(col, c));
+                }
+            }
+        }
+        Ok(det)
+    }
+
+    /// Gauss–Jordan elimination on [A | I].
+    pub fn inverse(&self) -> Result<Self, String> {
+        let n = self.require_square("inv")?;
+        let tolerance = self.tolerance();
+        let mut m = self.clone();
+        let mut inverse = Self::identity(n);
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|&a, &b| m.get(a, col).abs().total_cmp(&m.get(b, col).abs()))
+                .unwrap_or(col);
+            if m.get(pivot, col).abs() <= tolerance {
+                return Err("Macierz jest osobliwa – nie ma odwrotności".to_string());
+            }
+            m.swap_rows(pivot, col);
+            inverse.swap_rows(pivot, col);
+
+            let divisor = m.get(col, col);
+            for c in 0..n {
+                m.set(col, c, m.get(col, c) / divisor);
+                inverse.set(col, c, inverse.get(col, c) / divisor);
+            }
+            for row in (0..n).filter(|r| *r != col) {
+                let factor = m.get(row, col);
+                for c in 0..n {
+                    m.set(row, c, m.get(row, c) - factor * m.get(col, c));
+                    inverse.set(row, c, inverse.get(row, c) - factor * inverse.get(col, c));
+                }
+            }
+        }
+        Ok(inverse)
+    }
+
+    pub fn rank(&self) -> usize {
+        let tolerance = self.tolerance();
+        let mut m = self.clone();
+        let mut rank = 0;
+        for col in 0..m.cols {
+            if rank == m.rows {
+                break;
+            }
+            let pivot = (rank..m.rows)
+                .max_by(|&a, &b| m.get(a, col).abs().total_cmp(&m.get(b, col).abs()))
+                .unwrap_or(rank);
+            if m.get(pivot, col).abs() <= tolerance {
+                continue;
+            }
+            m.swap_rows(pivot, rank);
+            for row in rank + 1..m.rows {
+                let factor = m.get(row, col) / m.get(rank, col);
+                for c in col..m.cols {
+                    m.set(row, c, m.get(row, c) - factor * m.get(rank, c));
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+
+    /// Integer powers by repeated squaring; negative powers use the inverse.
+    pub fn power(&self, exponent: f64) -> Result<Self, String> {
+        let n = self.require_square("potęga")?;
+        if exponent.fract() != 0.0 || exponent.abs() > 1e9 {
+            return Err("Macierz można podnieść tylko do potęgi całkowitej".to_string());
+        }
+        let mut base = if exponent < 0.0 { self.inverse()? } else { self.clone() };
+        let mut remaining = exponent.abs() as u64;
+        let mut result = Self::identity(n);
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.multiply(&base)?;
+            }
+            base = base.multiply(&base)?;
+            remaining >>= 1;
+        }
+        Ok(result)
+    }
+
+    /// Eigenvalues by the shifted QR algorithm on the Hessenberg form, in
+    /// decreasing order. Complex pairs are reported in the error.
+    pub fn eigenvalues(&self) -> Result<Vec<f64>, String> {
+        let n = self.require_square("eig")?;
+        let mut h = self.hessenberg();
+        let mut real = Vec::new();
+        let mut complex = Vec::new();
+
+        let mut size = n;
+        let mut iterations = 0;
+        while size > 0 {
+            let last = size - 1;
+            if size == 1 {
+                real.push(h.get(0, 0));
+                break;
+            }
+
+            let small = |h: &Matrix, i: usize| {
+                h.get(i, i - 1).abs() <= f64::EPSILON * (h.get(i, i).abs() + h.get(i - 1, i - 1).abs()).max(1e-300)
+            };
+            if small(&h, last) {
+                real.push(h.get(last, last));
+                size -= 1;
+                iterations = 0;
+                continue;
+            }
+            if size == 2 || small(&h, last - 1) {
+                let (a, b, c, d) = (h.get(last - 1, last - 1), h.get(last - 1, last), h.get(last, last - 1), h.get(last, last));
+                let half_trace = (a + d) / 2.0;
+                let discriminant = ((a - d) / 2.0).powi(2) + b * c;
+                if discriminant >= 0.0 {
+                    real.push(half_trace + discriminant.sqrt());
+                    real.push(half_trace - discriminant.sqrt());
+                } else {
+                    complex.push((half_trace, (-discriminant).sqrt()));
+                }
+                size -= 2;
+                iterations = 0;
+                continue;
+            }
+
+            iterations += 1;
+            if iterations > 500 {
+                return Err("Obliczanie wartości własnych nie jest zbieżne".to_string());
+            }
+            // Wilkinson shift, with an occasional exceptional shift against cycling.
+            let shift = if iterations % 11 == 0 {
+                h.get(last, last) + h.get(last, last - 1).abs()
+            } else {
+                let (a, b, c, d) = (h.get(last - 1, last - 1), h.get(last - 1, last), h.get(last, last - 1), h.get(last, last));
+                let delta = (a - d) / 2.0;
+                let discriminant = delta * delta + b * c;
+                if discriminant >= 0.0 {
+                    let root = discriminant.sqrt();
+                    let (mu1, mu2) = ((a + d) / 2.0 + root, (a + d) / 2.0 - root);
+                    if (mu1 - d).abs() < (mu2 - d).abs() { mu1 } else { mu2 }
+                } else {
+                    (a + d) / 2.0
+                }
+            };
+            h.qr_step(size, shift);
+        }
+
+        if !complex.is_empty() {
+            let pairs: Vec<String> = complex.iter().map(|(re, im)| format!("{:.6} ± {:.6}i", re, im)).collect();
+            return Err(format!("Macierz ma zespolone wartości własne: {}", pairs.join("; ")));
+        }
+        // Rounding noise around a zero eigenvalue is shown as 0.
+        let largest = real.iter().fold(0.0, |max: f64, v| max.max(v.abs()));
+        for value in &mut real {
+            if value.abs() <= 1e-12 * largest {
+                *value = 0.0;
+            }
+        }
+        real.sort_by(|a, b| b.total_cmp(a));
+        Ok(real)
+    }
+
+    /// Similar upper Hessenberg matrix by Householder reflections.
+    fn hessenberg(&self) -> Self {
+        let n = self.rows;
+        let mut h = self.clone();
+        for k in 0..n.saturating_sub(2) {
+            let x: Vec<f64> = (k + 1..n).map(|r| h.get(r, k)).collect();
+            let alpha = -x[0].signum() * x.iter().map(|v| v * v).sum::<f64>().sqrt();
+            let mut v = x.clone();
+            v[0] -= alpha;
+            let norm: f64 = v.iter().map(|e| e * e).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                continue;
+            }
+            v.iter_mut().for_each(|e| *e /= norm);
+
+            // H = (I − 2vvᵀ) H (I − 2vvᵀ) on rows and columns k+1..n.
+            for c in 0..n {
+                let dot: f64 = v.iter().enumerate().map(|(i, e)| e * h.get(k + 1 + i, c)).sum();
+                for (i, e) in v.iter().enumerate() {
+                    h.set(k + 1 + i, c, h.get(k + 1 + i, c) - 2.0 * e * dot);
+                }
+            }
+            for r in 0..n {
+                let dot: f64 = v.iter().enumerate().map(|(i, e)| e * h.get(r, k + 1 + i)).sum();
+                for (i, e) in v.iter().enumerate() {
+                    h.set(r, k + 1 + i, h.get(r, k + 1 + i) - 2.0 * e * dot);
+                }
+            }
+        }
+        h
+    }
+
+    /// One shifted QR step on the leading `size` × `size` block, with Givens rotations.
+    fn qr_step(&mut self, size: usize, shift: f64) {
+        for i in 0..size {
+            self.set(i, i, self.get(i, i) - shift);
+        }
+        let mut rotations = Vec::with_capacity(size - 1);
+        for k in 0..size - 1 {
+            let (a, b) = (self.get(k, k), self.get(k + 1, k));
+            let r = a.hypot(b);
+            let (c, s) = if r == 0.0 { (1.0, 0.0) } else { (a / r, b / r) };
+            for col in 0..size {
+                let (x, y) = (self.get(k, col), self.get(k + 1, col));
+                self.set(k, col, c * x + s * y);
+                self.set(k + 1, col, -s * x + c * y);
+            }
+            rotations.push((c, s));
+        }
+        for (k, (c, s)) in rotations.into_iter().enumerate() {
+            for row in 0..size {
+                let (x, y) = (self.get(row, k), self.get(row, k + 1));
+                self.set(row, k, c * x + s * y);
+                self.set(row, k + 1, -s * x + c * y);
+            }
+        }
+        for i in 0..size {
+            self.set(i, i, self.get(i, i) + shift);
+        }
+    }
+}
+
+/// A value seen as a number, a vector or a matrix.
+enum Operand {
+    Scalar(f64),
+    Vector(Vec<f64>),
+    Matrix(Matrix),
+}
+
+impl Operand {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Num(n) => Ok(Operand::Scalar(*n)),
+            Value::List(items) if items.iter().all(|item| matches!(item, Value::Num(_))) => {
+                Ok(Operand::Vector(value.as_numbers()?))
+            }
+            Value::List(items) => {
+                let rows = items
+                    .iter()
+                    .map(|row| row.as_numbers().map_err(|_| "Niepoprawna macierz".to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Operand::Matrix(Matrix::from_rows(&rows)?))
+            }
+        }
+    }
+}
+
+fn vector(values: Vec<f64>) -> Value {
+    Value::List(values.into_iter().map(Value::Num).collect())
+}
+
+fn column(values: &[f64]) -> Matrix {
+    Matrix { rows: values.len(), cols: 1, data: values.to_vec() }
+}
+
+/// A value that must be a matrix; a vector is taken as a single row.
+pub fn matrix_of(value: &Value) -> Result<Matrix, String> {
+    match Operand::from_value(value)? {
+        Operand::Matrix(m) => Ok(m),
+        Operand::Vector(v) => Matrix::from_rows(&[v]),
+        Operand::Scalar(_) => Err("Oczekiwano macierzy [[..], [..]]".to_string()),
+    }
+}
+
+pub fn negate(value: &Value) -> Result<Value, String> {
+    match value {
+        Value::Num(n) => Ok(Value::Num(-n)),
+        Value::List(items) => Ok(Value::List(items.iter().map(negate).collect::<Result<_, _>>()?)),
+    }
+}
+
+/// An operator with at least one vector or matrix operand.
+pub fn binary(op: Op, a: &Value, b: &Value) -> Result<Value, String> {
+    use Operand::{Matrix as M, Scalar as S, Vector as V};
+
+    let elementwise = |f: fn(f64, f64) -> f64| -> Result<Value, String> {
+        match (Operand::from_value(a)?, Operand::from_value(b)?) {
+            (V(x), V(y)) if x.len() == y.len() => Ok(vector(x.iter().zip(&y).map(|(p, q)| f(*p, *q)).collect())),
+            (V(x), V(y)) => Err(format!("Niezgodne długości wektorów: {} i {}", x.len(), y.len())),
+            (M(x), M(y)) => Ok(x.zip(&y, f)?.to_value()),
+            _ => Err("Dodawać i odejmować można tylko wektory lub macierze tych samych wymiarów".to_string()),
+        }
+    };
+
+    match op {
+        Op::Add => elementwise(|p, q| p + q),
+        Op::Sub => elementwise(|p, q| p - q),
+        Op::Mul => match (Operand::from_value(a)?, Operand::from_value(b)?) {
+            (S(s), V(v)) | (V(v), S(s)) => Ok(vector(v.iter().map(|x| x * s).collect())),
+            (S(s), M(m)) | (M(m), S(s)) => Ok(m.map(|x| x * s).to_value()),
+            (M(x), M(y)) => Ok(x.multiply(&y)?.to_value()),
+            (M(m), V(v)) => Ok(vector(m.multiply(&column(&v))?.data)),
+            (V(v), M(m)) => Ok(vector(column(&v).transpose().multiply(&m)?.data)),
+            (V(_), V(_)) => Err("Iloczyn wektorów: użyj dot(u, v) lub cross(u, v)".to_string()),
+            (S(_), S(_)) => unreachable!("numbers are multiplied by the evaluator"),
+        },
+        Op::Div => match (Operand::from_value(a)?, Operand::from_value(b)?) {
+            (V(v), S(s)) => Ok(vector(v.iter().map(|x| x / s).collect())),
+            (M(m), S(s)) => Ok(m.map(|x| x / s).to_value()),
+            _ => Err("Wektor lub macierz można dzielić tylko przez liczbę".to_string()),
+        },
+        Op::Pow => match (Operand::from_value(a)?, Operand::from_value(b)?) {
+            (M(m), S(exponent)) => Ok(m.power(exponent)?.to_value()),
+            _ => Err("Potęgować można tylko macierz kwadratową do potęgi całkowitej".to_string()),
+        },
+    }
+}
+
+fn vectors(args: &[Value], name: &str) -> Result<(Vec<f64>, Vec<f64>), String> {
+    match args {
+        [u, v] => {
+            let (u, v) = (u.as_numbers()?, v.as_numbers()?);
+            if u.len() != v.len() {
+                return Err(format!("{}: wektory mają różne długości", name));
+            }
+            Ok((u, v))
+        }
+        _ => Err(format!("Użycie: {}(u, v)", name)),
+    }
+}
+
+pub fn register(ctx: &mut Context) {
+    fn matrix_fn(ctx: &mut Context, name: &'static str, f: fn(&Matrix) -> Result<Value, String>) {
+        ctx.func(name, move |args| match args {
+            [value] => f(&matrix_of(value)?),
+            _ => Err(format!("Funkcja {} przyjmuje jedną macierz", name)),
+        });
+    }
+
+    matrix_fn(ctx, "transpose", |m| Ok(m.transpose().to_value()));
+    matrix_fn(ctx, "det", |m| m.determinant().map(Value::Num));
+    matrix_fn(ctx, "inv", |m| m.inverse().map(|i| i.to_value()));
+    matrix_fn(ctx, "rank", |m| Ok(Value::Num(m.rank() as f64)));
+    matrix_fn(ctx, "trace", |m| m.trace().map(Value::Num));
+    matrix_fn(ctx, "eigenvalues", |m| m.eigenvalues().map(vector));
+    matrix_fn(ctx, "eig", |m| m.eigenvalues().map(vector));
+
+    ctx.func("identity", |args| match args {
+        [Value::Num(n)] if n.fract() == 0.0 && (1.0..=100.0).contains(n) => Ok(Matrix::identity(*n as usize).to_value()),
+        _ => Err("Użycie: identity(n), n od 1 do 100".to_string()),
+    });
+    ctx.func("dot", |args| {
+        let (u, v) = vectors(args, "dot")?;
+        Ok(Value::Num(u.iter().zip(&v).map(|(a, b)| a * b).sum()))
+    });
+    ctx.func("cross", |args| {
+        let (u, v) = vectors(args, "cross")?;
+        if u.len() != 3 {
+            return Err("cross: wektory muszą mieć 3 współrzędne".to_string());
+        }
+        Ok(vector(vec![
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ]))
+    });
+    // Euclidean length of a vector, Frobenius norm of a matrix.
+    ctx.func("norm", |args| match args {
+        [value] => Ok(Value::Num(matrix_of(value)?.data.iter().map(|x| x * x).sum::<f64>().sqrt())),
+        _ => Err("Użycie: norm(v)".to_string()),
+    });
+}
+
+/// Rows of a value for display as a grid: a number is one cell, a vector one row.
+pub fn grid(value: &Value) -> Vec<Vec<f64>> {
+    match Operand::from_value(value) {
+        Ok(Operand::Scalar(n)) => vec![vec![n]],
+        Ok(Operand::Vector(v)) => vec![v],
+        Ok(Operand::Matrix(m)) => (0..m.rows).map(|r| (0..m.cols).map(|c| m.get(r, c)).collect()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// A grid entry with up to 10 decimals, so rounding noise such as
+/// 0.9999999999999996 is shown as 1.
+pub fn format_entry(value: f64) -> String {
+    let text = format!("{:.10}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        String::from("0")
+    } else {
+        text.to_string()
+    }
+}
+
+/// A result for the display with every entry in `format`. Entries are first
+/// snapped to a nearby simple fraction, so `inv([[1, 2], [3, 4]])` shows -2
+/// and 1.5 rather than -1.9999999999999996 and 1.4999999999999998.
+pub fn format_value(value: &Value, format: NumberFormat, max_denominator: i128) -> String {
+    match value {
+        Value::Num(n) => {
+            let snapped = Rational::recover(*n, max_denominator);
+            format.format(snapped.map_or(*n, Rational::to_f64), snapped, max_denominator)
+        }
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| format_value(item, format, max_denominator)).collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixName {
+    A,
+    B,
+}
+
+impl MatrixName {
+    pub const ALL: [MatrixName; 2] = [MatrixName::A, MatrixName::B];
+}
+
+impl fmt::Display for MatrixName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if *self == MatrixName::A { "A" } else { "B" })
+    }
+}
+
+/// A matrix being edited cell by cell.
+pub struct MatrixInput {
+    pub cells: Vec<Vec<String>>,
+}
+
+impl MatrixInput {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self { cells: vec![vec![String::new(); cols]; rows] }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+
+    /// Changes the size, keeping the entries that still fit.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        self.cells.resize(rows, Vec::new());
+        for row in &mut self.cells {
+            row.resize(cols, String::new());
+        }
+    }
+
+    /// Fills the editor from a result; false if it is not a vector or matrix.
+    pub fn load(&mut self, value: &Value) -> bool {
+        let rows = grid(value);
+        if rows.is_empty() || matches!(value, Value::Num(_)) {
+            return false;
+        }
+        self.cells = rows.iter().map(|row| row.iter().map(|v| format_entry(*v)).collect()).collect();
+        true
+    }
+}
+
+pub const SIZES: [usize; 6] = [1, 2, 3, 4, 5, 6];
+
+pub struct MatrixForm {
+    pub a: MatrixInput,
+    pub b: MatrixInput,
+    pub expression: String,
+    pub result: Option<Result<Value, String>>,
+}
+
+impl Default for MatrixForm {
+    fn default() -> Self {
+        Self {
+            a: MatrixInput::new(2, 2),
+            b: MatrixInput::new(2, 2),
+            expression: String::from("A*B"),
+            result: None,
+        }
+    }
+}
+
+impl MatrixForm {
+    pub fn input(&self, name: MatrixName) -> &MatrixInput {
+        match name {
+            MatrixName::A => &self.a,
+            MatrixName::B => &self.b,
+        }
+    }
+
+    pub fn input_mut(&mut self, name: MatrixName) -> &mut MatrixInput {
+        match name {
+            MatrixName::A => &mut self.a,
+            MatrixName::B => &mut self.b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<Value, String> {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        ctx.eval_str(input)
+    }
+
+    fn show(input: &str) -> String {
+        format_value(&eval(input).unwrap(), NumberFormat::Decimal, 1000)
+    }
+
+    #[test]
+    fn determinant_rank_and_trace() {
+        assert_eq!(show("det([[1, 2], [3, 4]])"), "-2");
+        assert_eq!(show("det([[2, 0, 0], [0, 3, 0], [0, 0, 4]])"), "24");
+        assert_eq!(show("rank([[1, 2], [2, 4]])"), "1");
+        assert_eq!(show("trace([[1, 2], [3, 4]])"), "5");
+        assert!(eval("det([[1, 2, 3], [4, 5, 6]])").is_err());
+    }
+
+    #[test]
+    fn inverse_is_shown_without_rounding_noise() {
+        assert_eq!(show("inv([[1, 2], [3, 4]])"), "[[-2, 1], [1.5, -0.5]]");
+        assert_eq!(show("inv([[4, 7], [2, 6]])"), "[[0.6, -0.7], [-0.2, 0.4]]");
+        assert_eq!(
+            format_value(&eval("inv([[4, 7], [2, 6]])").unwrap(), NumberFormat::Fraction, 1000),
+            "[[3/5, -7/10], [-1/5, 2/5]]"
+        );
+        assert!(eval("inv([[1, 2], [2, 4]])").is_err());
+    }
+
+    #[test]
+    fn eigenvalues_under_both_names() {
+        assert_eq!(show("eigenvalues([[2, 0], [0, 3]])"), show("eig([[3, 0], [0, 2]])"));
+        let values = eval("eigenvalues([[2, 1], [1, 2]])").unwrap().as_numbers().unwrap();
+        let mut values: Vec<String> = values.into_iter().map(format_entry).collect();
+        values.sort();
+        assert_eq!(values, ["1", "3"]);
+    }
+
+    #[test]
+    fn vector_functions() {
+        assert_eq!(show("dot([1, 2, 3], [4, 5, 6])"), "32");
+        assert_eq!(show("cross([1, 0, 0], [0, 1, 0])"), "[0, 0, 1]");
+        assert_eq!(show("norm([3, 4])"), "5");
+        assert_eq!(show("[[1, 2], [3, 4]] * [[0, 1], [1, 0]]"), "[[2, 1], [4, 3]]");
+        assert!(eval("cross([1, 2], [3, 4])").is_err());
+    }
+
+    #[test]
+    fn grid_entries() {
+        assert_eq!(grid(&eval("[1, 2]").unwrap()), [[1.0, 2.0]]);
+        assert_eq!(format_entry(0.9999999999999996), "1");
+        assert_eq!(format_entry(-0.00000000001), "0");
+    }
+}
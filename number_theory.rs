@@ -0,0 +1,479 @@
+// Integer number theory: gcd and lcm, primes, modular arithmetic, Euler's
+// totient and factorials. Arguments are whole numbers up to 2^53, the largest
+// a display value holds exactly. Typed on the display, `factor(n)` shows the
+// factorization as `2³·3·5` and `factorial(n)` shows every digit.
+
+use crate::expr::{self, Context, Expr, Value};
+
+const MAX_EXACT: f64 = 9_007_199_254_740_992.0;
+const MAX_FACTORIAL: u64 = 5000;
+/// 170! is the largest factorial a floating-point number can hold.
+const MAX_FLOAT_FACTORIAL: u64 = 170;
+/// Consecutive primes below 2^64 are never further apart than this.
+const MAX_PRIME_GAP: u64 = 1550;
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// A whole number that a display value holds exactly.
+fn integer(value: &Value) -> Result<i64, String> {
+    let x = value.as_number()?;
+    if !x.is_finite() || x.fract() != 0.0 {
+        return Err("Oczekiwano liczby całkowitej".to_string());
+    }
+    if x.abs() > MAX_EXACT {
+        return Err("Liczba za duża (najwyżej 2^53)".to_string());
+    }
+    Ok(x as i64)
+}
+
+fn natural(value: &Value) -> Result<u64, String> {
+    u64::try_from(integer(value)?).map_err(|_| "Oczekiwano liczby nieujemnej".to_string())
+}
+
+/// Integers from numbers and lists alike: `gcd(12, 18)`, `gcd([12, 18, 30])`.
+fn integers(args: &[Value]) -> Result<Vec<i64>, String> {
+    let mut out = Vec::new();
+    for arg in args {
+        match arg {
+            Value::Num(_) => out.push(integer(arg)?),
+            Value::List(items) => {
+                for item in items {
+                    out.push(integer(item)?);
+                }
+            }
+        }
+    }
+    if out.is_empty() {
+        return Err("Brak argumentów".to_string());
+    }
+    Ok(out)
+}
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// None when the result does not fit.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+pub fn pow_mod(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    let mut base = base % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Miller–Rabin with the first twelve primes as witnesses, which is exact
+/// for every 64-bit number.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let (mut d, mut s) = (n - 1, 0);
+    while d % 2 == 0 {
+        d /= 2;
+        s += 1;
+    }
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// The smallest prime above `n`; None beyond 2^53.
+pub fn next_prime(n: u64) -> Option<u64> {
+    (n.checked_add(1)?..=n.saturating_add(MAX_PRIME_GAP))
+        .find(|&k| is_prime(k))
+        .filter(|&p| p as f64 <= MAX_EXACT)
+}
+
+/// The largest prime below `n`, searching no further than 2^53.
+pub fn previous_prime(n: u64) -> Option<u64> {
+    let n = n.min(MAX_EXACT as u64);
+    (n.saturating_sub(MAX_PRIME_GAP).max(2)..n).rev().find(|&k| is_prime(k))
+}
+
+/// A non-trivial divisor of an odd composite `n` (Pollard's rho).
+fn pollard_rho(n: u64) -> u64 {
+    let mut c = 1;
+    loop {
+        let step = |x: u64| ((mul_mod(x, x, n) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = step(x);
+            y = step(step(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+        c += 1;
+    }
+}
+
+/// Prime factors with their exponents, smallest first; empty for 0 and 1.
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    fn split(n: u64, primes: &mut Vec<u64>) {
+        if n == 1 {
+            return;
+        }
+        if is_prime(n) {
+            primes.push(n);
+            return;
+        }
+        let d = pollard_rho(n);
+        split(d, primes);
+        split(n / d, primes);
+    }
+
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut primes = Vec::new();
+    let mut p = 2;
+    while p < 1000 && p * p <= n {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    split(n, &mut primes);
+    primes.sort_unstable();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, k)) if *q == p => *k += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// Euler's φ: how many of 1..=n are coprime to n.
+pub fn totient(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |phi, (p, _)| phi / p * (p - 1))
+}
+
+/// The x in 0..m with a·x ≡ 1 (mod m), if a and m are coprime.
+pub fn mod_inverse(a: i64, m: u64) -> Option<u64> {
+    let m = m as i128;
+    let (mut r0, mut r1) = ((a as i128).rem_euclid(m), m);
+    let (mut s0, mut s1) = (1i128, 0i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    (r0 == 1).then(|| s0.rem_euclid(m) as u64)
+}
+
+/// n! with every digit, multiplied out in base 10^9.
+pub fn factorial(n: u64) -> String {
+    const BASE: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = vec![1];
+    for k in 2..=n {
+        let mut carry = 0;
+        for limb in limbs.iter_mut() {
+            let value = *limb * k + carry;
+            *limb = value % BASE;
+            carry = value / BASE;
+        }
+        while carry > 0 {
+            limbs.push(carry % BASE);
+            carry /= BASE;
+        }
+    }
+
+    let mut digits = limbs.last().map_or(String::new(), u64::to_string);
+    for limb in limbs.iter().rev().skip(1) {
+        digits.push_str(&format!("{:09}", limb));
+    }
+    digits
+}
+
+fn superscript(n: u32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .chars()
+        .filter_map(|c| c.to_digit(10).map(|d| DIGITS[d as usize]))
+        .collect()
+}
+
+/// `360` as `2³·3²·5`, which the display can evaluate again.
+pub fn format_factorization(n: u64) -> String {
+    let factors = factorize(n);
+    if factors.is_empty() {
+        return n.to_string();
+    }
+    factors
+        .iter()
+        .map(|(p, k)| if *k == 1 { p.to_string() } else { format!("{}{}", p, superscript(*k)) })
+        .collect::<Vec<_>>()
+        .join("·")
+}
+
+/// Results typed on the display that are shown exactly rather than as a
+/// number: `factor(360)` and `factorial(30)`. None for anything else, e.g.
+/// `factor(x^2 - 1)`, which is left to symbolic algebra.
+pub fn exact_result(ctx: &Context, input: &str) -> Option<Result<String, String>> {
+    let Ok(Expr::Call(name, args)) = expr::parse(input) else {
+        return None;
+    };
+    let [arg] = args.as_slice() else {
+        return None;
+    };
+    let value = ctx.eval(arg);
+    match name.as_str() {
+        "factor" => {
+            // A plain literal may go beyond 2^53, up to the 64-bit limit.
+            let literal = input
+                .trim()
+                .strip_prefix("factor(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|n| n.trim().parse::<u64>().ok());
+            let (n, negative) = match literal {
+                Some(n) => (n, false),
+                None => {
+                    let value = value.ok()?;
+                    if value.as_number().ok()?.fract() != 0.0 {
+                        return None;
+                    }
+                    match integer(&value) {
+                        Ok(n) => (n.unsigned_abs(), n < 0),
+                        Err(error) => return Some(Err(error)),
+                    }
+                }
+            };
+            if n == 0 {
+                return Some(Err("0 nie ma rozkładu na czynniki".to_string()));
+            }
+            let sign = if negative { "-" } else { "" };
+            Some(Ok(format!("{}{}", sign, format_factorization(n))))
+        }
+        "factorial" => Some(value.and_then(|v| natural(&v)).and_then(|n| {
+            if n > MAX_FACTORIAL {
+                return Err(format!("Najwyżej {}!", MAX_FACTORIAL));
+            }
+            Ok(factorial(n))
+        })),
+        _ => None,
+    }
+}
+
+pub fn register(ctx: &mut Context) {
+    ctx.func("gcd", |args| {
+        let numbers = integers(args)?;
+        Ok(Value::Num(numbers.iter().fold(0, |g, n| gcd(g, n.unsigned_abs())) as f64))
+    });
+    ctx.func("lcm", |args| {
+        let numbers = integers(args)?;
+        numbers
+            .iter()
+            .try_fold(1, |l, n| lcm(l, n.unsigned_abs()))
+            .map(|l| Value::Num(l as f64))
+            .ok_or_else(|| "Wynik za duży".to_string())
+    });
+
+    ctx.func("isprime", |args| {
+        let [n] = args else {
+            return Err("Użycie: isprime(n)".to_string());
+        };
+        let n = integer(n)?;
+        Ok(Value::Num(if n >= 2 && is_prime(n as u64) { 1.0 } else { 0.0 }))
+    });
+    ctx.func("nextprime", |args| {
+        let [n] = args else {
+            return Err("Użycie: nextprime(n)".to_string());
+        };
+        let n = integer(n)?.max(1) as u64;
+        next_prime(n)
+            .map(|p| Value::Num(p as f64))
+            .ok_or_else(|| "Wynik za duży".to_string())
+    });
+    ctx.func("prevprime", |args| {
+        let [n] = args else {
+            return Err("Użycie: prevprime(n)".to_string());
+        };
+        let n = integer(n)?.max(0) as u64;
+        previous_prime(n)
+            .map(|p| Value::Num(p as f64))
+            .ok_or_else(|| "Brak mniejszej liczby pierwszej".to_string())
+    });
+
+    ctx.func("modpow", |args| {
+        let [base, exponent, modulus] = args else {
+            return Err("Użycie: modpow(a, b, m)".to_string());
+        };
+        let m = natural(modulus)?;
+        if m == 0 {
+            return Err("Moduł musi być dodatni".to_string());
+        }
+        let a = integer(base)?;
+        let b = integer(exponent)?;
+        // A negative exponent raises the inverse.
+        let a = if b < 0 {
+            mod_inverse(a, m).ok_or_else(|| format!("{} nie ma odwrotności modulo {}", a, m))?
+        } else {
+            (a as i128).rem_euclid(m as i128) as u64
+        };
+        Ok(Value::Num(pow_mod(a, b.unsigned_abs(), m) as f64))
+    });
+    ctx.func("modinv", |args| {
+        let [a, modulus] = args else {
+            return Err("Użycie: modinv(a, m)".to_string());
+        };
+        let (a, m) = (integer(a)?, natural(modulus)?);
+        if m == 0 {
+            return Err("Moduł musi być dodatni".to_string());
+        }
+        mod_inverse(a, m)
+            .map(|x| Value::Num(x as f64))
+            .ok_or_else(|| format!("{} nie ma odwrotności modulo {}", a, m))
+    });
+
+    ctx.func("totient", |args| {
+        let [n] = args else {
+            return Err("Użycie: totient(n)".to_string());
+        };
+        match natural(n)? {
+            0 => Err("totient: n musi być dodatnie".to_string()),
+            n => Ok(Value::Num(totient(n) as f64)),
+        }
+    });
+
+    // The prime factors with repetition, e.g. factor(12) = [2, 2, 3].
+    ctx.func("factor", |args| {
+        let [n] = args else {
+            return Err("Użycie: factor(n)".to_string());
+        };
+        let n = integer(n)?;
+        if n == 0 {
+            return Err("0 nie ma rozkładu na czynniki".to_string());
+        }
+        let mut primes = if n < 0 { vec![Value::Num(-1.0)] } else { Vec::new() };
+        for (p, k) in factorize(n.unsigned_abs()) {
+            primes.extend((0..k).map(|_| Value::Num(p as f64)));
+        }
+        Ok(Value::List(primes))
+    });
+
+    // In floating point; `factorial(n)` alone on the display is exact.
+    ctx.func("factorial", |args| {
+        let [n] = args else {
+            return Err("Użycie: factorial(n)".to_string());
+        };
+        let n = natural(n)?;
+        if n > MAX_FLOAT_FACTORIAL {
+            return Err(format!("Wynik za duży (najwyżej {}!)", MAX_FLOAT_FACTORIAL));
+        }
+        Ok(Value::Num((2..=n).map(|k| k as f64).product()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<f64, String> {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        ctx.eval_str(input)?.as_number()
+    }
+
+    fn exact(input: &str) -> Option<Result<String, String>> {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        exact_result(&ctx, input)
+    }
+
+    #[test]
+    fn gcd_lcm_and_modular_arithmetic() {
+        assert_eq!(eval("gcd(12, 18)"), Ok(6.0));
+        assert_eq!(eval("gcd([12, 18, 30])"), Ok(6.0));
+        assert_eq!(eval("lcm(4, 6)"), Ok(12.0));
+        assert_eq!(eval("modpow(3, 200, 13)"), Ok(9.0));
+        assert_eq!(eval("modinv(3, 11)"), Ok(4.0));
+        assert_eq!(eval("modpow(3, -1, 11)"), Ok(4.0));
+        assert!(eval("modinv(2, 4)").is_err());
+        assert_eq!(eval("totient(36)"), Ok(12.0));
+        assert!(eval("gcd(1.5, 3)").is_err());
+    }
+
+    #[test]
+    fn primes() {
+        assert!(is_prime(2) && is_prime(97) && is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(1) && !is_prime(561) && !is_prime(3_215_031_751));
+        assert_eq!(next_prime(13), Some(17));
+        assert_eq!(previous_prime(13), Some(11));
+        assert_eq!(previous_prime(2), None);
+        assert_eq!(previous_prime(u64::MAX), Some((1 << 53) - 111));
+        assert_eq!(next_prime((1 << 53) - 111), None);
+        assert_eq!(eval("nextprime(100)"), Ok(101.0));
+        assert_eq!(eval("prevprime(100)"), Ok(97.0));
+        assert!(eval("prevprime(2)").is_err());
+    }
+
+    #[test]
+    fn factorization() {
+        assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(600_851_475_143), [(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+        assert_eq!(format_factorization(360), "2³·3²·5");
+        assert_eq!(exact("factor(360)"), Some(Ok("2³·3²·5".to_string())));
+        assert_eq!(exact("factor(-12)"), Some(Ok("-2²·3".to_string())));
+        assert_eq!(exact("factor(18446744073709551615)"), Some(Ok("3·5·17·257·641·65537·6700417".to_string())));
+        assert!(exact("factor(0)").is_some_and(|result| result.is_err()));
+        assert_eq!(exact("factor(x^2 - 1)"), None);
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        let primes = ctx.eval_str("factor(-12)").and_then(|value| value.as_numbers());
+        assert_eq!(primes, Ok(vec![-1.0, 2.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn factorials() {
+        assert_eq!(factorial(0), "1");
+        assert_eq!(factorial(25), "15511210043330985984000000");
+        assert_eq!(exact("factorial(20)"), Some(Ok("2432902008176640000".to_string())));
+        assert!(exact("factorial(5001)").is_some_and(|result| result.is_err()));
+        assert_eq!(eval("factorial(5)"), Ok(120.0));
+        assert!(eval("factorial(170)").is_ok_and(f64::is_finite));
+        assert!(eval("factorial(171)").is_err());
+    }
+}
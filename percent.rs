@@ -0,0 +1,83 @@
+// Percentage helpers for the expression language; arguments are in percent,
+// as typed on a business calculator.
+
+use crate::expr::Context;
+
+/// Change from `old` to `new`, in percent.
+pub fn change(old: f64, new: f64) -> f64 {
+    (new - old) / old * 100.0
+}
+
+/// `p` percent of `x`.
+pub fn of(p: f64, x: f64) -> f64 {
+    x * p / 100.0
+}
+
+/// The amount that `p` percent was added to in order to get `value`.
+pub fn reverse(value: f64, p: f64) -> f64 {
+    value / (1.0 + p / 100.0)
+}
+
+/// Simple interest on `principal` at `rate` percent a year over `years`.
+pub fn interest(principal: f64, rate: f64, years: f64) -> f64 {
+    principal * rate / 100.0 * years
+}
+
+/// Return on investment in percent: the gain over the cost.
+pub fn roi(revenue: f64, cost: f64) -> f64 {
+    (revenue - cost) / cost * 100.0
+}
+
+/// Registers `pctchange(old, new)`, `pctof(p, x)`, `pctrev(value, p)`,
+/// `interest(principal, rate, years)` and `roi(revenue, cost)`.
+pub fn register(ctx: &mut Context) {
+    ctx.funcn("pctchange", |a| change(a[0], a[1]), 2..=2);
+    ctx.funcn("pctof", |a| of(a[0], a[1]), 2..=2);
+    ctx.funcn("pctrev", |a| reverse(a[0], a[1]), 2..=2);
+    ctx.funcn("interest", |a| interest(a[0], a[1], a[2]), 3..=3);
+    ctx.funcn("roi", |a| roi(a[0], a[1]), 2..=2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        ctx.eval_str(input).unwrap().as_number().unwrap()
+    }
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-9
+    }
+
+    #[test]
+    fn percent_operator() {
+        assert!(close(eval("200+10%"), 220.0));
+        assert!(close(eval("200-10%"), 180.0));
+        assert!(close(eval("200*10%"), 20.0));
+        assert!(close(eval("200/10%"), 2000.0));
+        assert!(close(eval("50%"), 0.5));
+        assert!(close(eval("(100+100)+10%+10%"), 242.0));
+    }
+
+    #[test]
+    fn scaled_percentages_are_shares_too() {
+        assert!(close(eval("200 + 10% * 2"), 240.0));
+        assert!(close(eval("200 - 2 * 10%"), 160.0));
+        assert!(close(eval("200 + 10% / 2"), 210.0));
+        assert!(close(eval("200 + -5%"), 190.0));
+        // Inside a function argument a percentage is just a number.
+        assert!(close(eval("200 + max(10%, 0)"), 200.1));
+    }
+
+    #[test]
+    fn percentage_functions() {
+        assert!(close(eval("pctchange(80, 100)"), 25.0));
+        assert!(close(eval("pctof(15, 200)"), 30.0));
+        assert!(close(eval("pctrev(123, 23)"), 100.0));
+        assert!(close(eval("interest(1000, 5, 2)"), 100.0));
+        assert!(close(eval("roi(150, 100)"), 50.0));
+    }
+}
@@ -0,0 +1,516 @@
+// Physics formula catalog.
+//
+// Every formula lists its variables and knows how to compute any one of them
+// from the rest, so a single form can solve for whichever field is left empty.
+
+use std::f64::consts::PI;
+
+pub struct Variable {
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub default: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Mechanics,
+    Kinematics,
+    Thermodynamics,
+    Electricity,
+    Optics,
+    Waves,
+    Fields,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::Mechanics,
+        Category::Kinematics,
+        Category::Thermodynamics,
+        Category::Electricity,
+        Category::Optics,
+        Category::Waves,
+        Category::Fields,
+    ];
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Category::Mechanics => "Mechanika",
+                Category::Kinematics => "Kinematyka",
+                Category::Thermodynamics => "Termodynamika",
+                Category::Electricity => "Elektryczność",
+                Category::Optics => "Optyka",
+                Category::Waves => "Fale",
+                Category::Fields => "Grawitacja i elektrostatyka",
+            }
+        )
+    }
+}
+
+pub struct Formula {
+    pub category: Category,
+    pub name: &'static str,
+    pub equation: &'static str,
+    pub variables: &'static [Variable],
+    // Computes variable `index` from the others; the unknown slot holds NaN.
+    solve: fn(usize, &[f64]) -> f64,
+}
+
+const C: f64 = 299_792_458.0;
+const R_GAS: f64 = 8.314_462_618;
+const K_COULOMB: f64 = 8.987_551_792_3e9;
+const G_GRAV: f64 = 6.674_30e-11;
+
+const fn var(symbol: &'static str, name: &'static str, unit: &'static str) -> Variable {
+    Variable { symbol, name, unit, default: None }
+}
+
+const fn with_default(symbol: &'static str, name: &'static str, unit: &'static str, default: f64) -> Variable {
+    Variable { symbol, name, unit, default: Some(default) }
+}
+
+pub static FORMULAS: &[Formula] = &[
+    Formula {
+        category: Category::Mechanics,
+        name: "Energia spoczynkowa",
+        equation: "E = mc²",
+        variables: &[var("E", "energia", "J"), var("m", "masa", "kg")],
+        solve: |i, v| match i {
+            0 => v[1] * C * C,
+            _ => v[0] / (C * C),
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Prędkość",
+        equation: "v = s/t",
+        variables: &[var("v", "prędkość", "m/s"), var("s", "droga", "m"), var("t", "czas", "s")],
+        solve: |i, v| match i {
+            0 => v[1] / v[2],
+            1 => v[0] * v[2],
+            _ => v[1] / v[0],
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Przyspieszenie",
+        equation: "a = Δv/t",
+        variables: &[
+            var("a", "przyspieszenie", "m/s²"),
+            var("Δv", "zmiana prędkości", "m/s"),
+            var("t", "czas", "s"),
+        ],
+        solve: |i, v| match i {
+            0 => v[1] / v[2],
+            1 => v[0] * v[2],
+            _ => v[1] / v[0],
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "II zasada dynamiki",
+        equation: "F = ma",
+        variables: &[var("F", "siła", "N"), var("m", "masa", "kg"), var("a", "przyspieszenie", "m/s²")],
+        solve: |i, v| match i {
+            0 => v[1] * v[2],
+            1 => v[0] / v[2],
+            _ => v[0] / v[1],
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Energia kinetyczna",
+        equation: "Ek = ½mv²",
+        variables: &[var("Ek", "energia kinetyczna", "J"), var("m", "masa", "kg"), var("v", "prędkość", "m/s")],
+        solve: |i, v| match i {
+            0 => 0.5 * v[1] * v[2] * v[2],
+            1 => 2.0 * v[0] / (v[2] * v[2]),
+            _ => (2.0 * v[0] / v[1]).sqrt(),
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Energia potencjalna",
+        equation: "Ep = mgh",
+        variables: &[
+            var("Ep", "energia potencjalna", "J"),
+            var("m", "masa", "kg"),
+            with_default("g", "przyspieszenie ziemskie", "m/s²", 9.81),
+            var("h", "wysokość", "m"),
+        ],
+        solve: |i, v| match i {
+            0 => v[1] * v[2] * v[3],
+            1 => v[0] / (v[2] * v[3]),
+            2 => v[0] / (v[1] * v[3]),
+            _ => v[0] / (v[1] * v[2]),
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Moc",
+        equation: "P = W/t",
+        variables: &[var("P", "moc", "W"), var("W", "praca", "J"), var("t", "czas", "s")],
+        solve: |i, v| match i {
+            0 => v[1] / v[2],
+            1 => v[0] * v[2],
+            _ => v[1] / v[0],
+        },
+    },
+    Formula {
+        category: Category::Mechanics,
+        name: "Pęd",
+        equation: "p = mv",
+        variables: &[var("p", "pęd", "kg·m/s"), var("m", "masa", "kg"), var("v", "prędkość", "m/s")],
+        solve: |i, v| match i {
+            0 => v[1] * v[2],
+            1 => v[0] / v[2],
+            _ => v[0] / v[1],
+        },
+    },
+    Formula {
+        category: Category::Kinematics,
+        name: "Zasięg rzutu ukośnego",
+        equation: "R = v²·sin(2θ)/g",
+        variables: &[
+            var("R", "zasięg", "m"),
+            var("v", "prędkość początkowa", "m/s"),
+            var("θ", "kąt wyrzutu", "°"),
+            with_default("g", "przyspieszenie ziemskie", "m/s²", 9.81),
+        ],
+        solve: |i, v| match i {
+            0 => v[1] * v[1] * (2.0 * v[2].to_radians()).sin() / v[3],
+            1 => (v[0] * v[3] / (2.0 * v[2].to_radians()).sin()).sqrt(),
+            2 => (v[0] * v[3] / (v[1] * v[1])).asin().to_degrees() / 2.0,
+            _ => v[1] * v[1] * (2.0 * v[2].to_radians()).sin() / v[0],
+        },
+    },
+    Formula {
+        category: Category::Kinematics,
+        name: "Czas lotu w rzucie ukośnym",
+        equation: "T = 2v·sin(θ)/g",
+        variables: &[
+            var("T", "czas lotu", "s"),
+            var("v", "prędkość początkowa", "m/s"),
+            var("θ", "kąt wyrzutu", "°"),
+            with_default("g", "przyspieszenie ziemskie", "m/s²", 9.81),
+        ],
+        solve: |i, v| match i {
+            0 => 2.0 * v[1] * v[2].to_radians().sin() / v[3],
+            1 => v[0] * v[3] / (2.0 * v[2].to_radians().sin()),
+            2 => (v[0] * v[3] / (2.0 * v[1])).asin().to_degrees(),
+            _ => 2.0 * v[1] * v[2].to_radians().sin() / v[0],
+        },
+    },
+    Formula {
+        category: Category::Thermodynamics,
+        name: "Równanie Clapeyrona",
+        equation: "pV = nRT",
+        variables: &[
+            var("p", "ciśnienie", "Pa"),
+            var("V", "objętość", "m³"),
+            var("n", "liczba moli", "mol"),
+            var("T", "temperatura", "K"),
+        ],
+        solve: |i, v| match i {
+            0 => v[2] * R_GAS * v[3] / v[1],
+            1 => v[2] * R_GAS * v[3] / v[0],
+            2 => v[0] * v[1] / (R_GAS * v[3]),
+            _ => v[0] * v[1] / (v[2] * R_GAS),
+        },
+    },
+    Formula {
+        category: Category::Thermodynamics,
+        name: "Ciepło",
+        equation: "Q = mcΔT",
+        variables: &[
+            var("Q", "ciepło", "J"),
+            var("m", "masa", "kg"),
+            var("c", "ciepło właściwe", "J/(kg·K)"),
+            var("ΔT", "zmiana temperatury", "K"),
+        ],
+        solve: |i, v| match i {
+            0 => v[1] * v[2] * v[3],
+            1 => v[0] / (v[2] * v[3]),
+            2 => v[0] / (v[1] * v[3]),
+            _ => v[0] / (v[1] * v[2]),
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Prawo Ohma",
+        equation: "U = IR",
+        variables: &[var("U", "napięcie", "V"), var("I", "natężenie", "A"), var("R", "opór", "Ω")],
+        solve: |i, v| match i {
+            0 => v[1] * v[2],
+            1 => v[0] / v[2],
+            _ => v[0] / v[1],
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Moc elektryczna",
+        equation: "P = UI",
+        variables: &[var("P", "moc", "W"), var("U", "napięcie", "V"), var("I", "natężenie", "A")],
+        solve: |i, v| match i {
+            0 => v[1] * v[2],
+            1 => v[0] / v[2],
+            _ => v[0] / v[1],
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Oporniki szeregowo",
+        equation: "R = R₁ + R₂",
+        variables: &[var("R", "opór zastępczy", "Ω"), var("R₁", "opór 1", "Ω"), var("R₂", "opór 2", "Ω")],
+        solve: |i, v| match i {
+            0 => v[1] + v[2],
+            1 => v[0] - v[2],
+            _ => v[0] - v[1],
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Oporniki równolegle",
+        equation: "1/R = 1/R₁ + 1/R₂",
+        variables: &[var("R", "opór zastępczy", "Ω"), var("R₁", "opór 1", "Ω"), var("R₂", "opór 2", "Ω")],
+        solve: |i, v| match i {
+            0 => 1.0 / (1.0 / v[1] + 1.0 / v[2]),
+            1 => 1.0 / (1.0 / v[0] - 1.0 / v[2]),
+            _ => 1.0 / (1.0 / v[0] - 1.0 / v[1]),
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Reaktancja kondensatora",
+        equation: "Xc = 1/(2πfC)",
+        variables: &[var("Xc", "reaktancja", "Ω"), var("f", "częstotliwość", "Hz"), var("C", "pojemność", "F")],
+        solve: |i, v| match i {
+            0 => 1.0 / (2.0 * PI * v[1] * v[2]),
+            1 => 1.0 / (2.0 * PI * v[0] * v[2]),
+            _ => 1.0 / (2.0 * PI * v[0] * v[1]),
+        },
+    },
+    Formula {
+        category: Category::Electricity,
+        name: "Reaktancja cewki",
+        equation: "XL = 2πfL",
+        variables: &[var("XL", "reaktancja", "Ω"), var("f", "częstotliwość", "Hz"), var("L", "indukcyjność", "H")],
+        solve: |i, v| match i {
+            0 => 2.0 * PI * v[1] * v[2],
+            1 => v[0] / (2.0 * PI * v[2]),
+            _ => v[0] / (2.0 * PI * v[1]),
+        },
+    },
+    Formula {
+        category: Category::Optics,
+        name: "Prawo Snelliusa",
+        equation: "n₁·sin(θ₁) = n₂·sin(θ₂)",
+        variables: &[
+            var("n₁", "współczynnik załamania 1", ""),
+            var("θ₁", "kąt padania", "°"),
+            var("n₂", "współczynnik załamania 2", ""),
+            var("θ₂", "kąt załamania", "°"),
+        ],
+        solve: |i, v| match i {
+            0 => v[2] * v[3].to_radians().sin() / v[1].to_radians().sin(),
+            1 => (v[2] * v[3].to_radians().sin() / v[0]).asin().to_degrees(),
+            2 => v[0] * v[1].to_radians().sin() / v[3].to_radians().sin(),
+            _ => (v[0] * v[1].to_radians().sin() / v[2]).asin().to_degrees(),
+        },
+    },
+    Formula {
+        category: Category::Optics,
+        name: "Równanie soczewki",
+        equation: "1/f = 1/x + 1/y",
+        variables: &[
+            var("f", "ogniskowa", "m"),
+            var("x", "odległość przedmiotu", "m"),
+            var("y", "odległość obrazu", "m"),
+        ],
+        solve: |i, v| match i {
+            0 => 1.0 / (1.0 / v[1] + 1.0 / v[2]),
+            1 => 1.0 / (1.0 / v[0] - 1.0 / v[2]),
+            _ => 1.0 / (1.0 / v[0] - 1.0 / v[1]),
+        },
+    },
+    Formula {
+        category: Category::Waves,
+        name: "Prędkość fali",
+        equation: "v = fλ",
+        variables: &[var("v", "prędkość", "m/s"), var("f", "częstotliwość", "Hz"), var("λ", "długość fali", "m")],
+        solve: |i, v| match i {
+            0 => v[1] * v[2],
+            1 => v[0] / v[2],
+            _ => v[0] / v[1],
+        },
+    },
+    Formula {
+        category: Category::Waves,
+        name: "Efekt Dopplera",
+        equation: "f' = f·(v + vo)/(v − vs)",
+        variables: &[
+            var("f'", "częstotliwość odbierana", "Hz"),
+            var("f", "częstotliwość źródła", "Hz"),
+            with_default("v", "prędkość fali", "m/s", 343.0),
+            var("vo", "prędkość obserwatora (ku źródłu)", "m/s"),
+            var("vs", "prędkość źródła (ku obserwatorowi)", "m/s"),
+        ],
+        solve: |i, v| match i {
+            0 => v[1] * (v[2] + v[3]) / (v[2] - v[4]),
+            1 => v[0] * (v[2] - v[4]) / (v[2] + v[3]),
+            2 => (v[1] * v[3] + v[0] * v[4]) / (v[0] - v[1]),
+            3 => v[0] * (v[2] - v[4]) / v[1] - v[2],
+            _ => v[2] - v[1] * (v[2] + v[3]) / v[0],
+        },
+    },
+    Formula {
+        category: Category::Fields,
+        name: "Prawo Coulomba",
+        equation: "F = k·q₁q₂/r²",
+        variables: &[
+            var("F", "siła", "N"),
+            var("q₁", "ładunek 1", "C"),
+            var("q₂", "ładunek 2", "C"),
+            var("r", "odległość", "m"),
+        ],
+        solve: |i, v| match i {
+            0 => K_COULOMB * v[1] * v[2] / (v[3] * v[3]),
+            1 => v[0] * v[3] * v[3] / (K_COULOMB * v[2]),
+            2 => v[0] * v[3] * v[3] / (K_COULOMB * v[1]),
+            _ => (K_COULOMB * v[1] * v[2] / v[0]).sqrt(),
+        },
+    },
+    Formula {
+        category: Category::Fields,
+        name: "Prawo powszechnego ciążenia",
+        equation: "F = G·m₁m₂/r²",
+        variables: &[
+            var("F", "siła", "N"),
+            var("m₁", "masa 1", "kg"),
+            var("m₂", "masa 2", "kg"),
+            var("r", "odległość", "m"),
+        ],
+        solve: |i, v| match i {
+            0 => G_GRAV * v[1] * v[2] / (v[3] * v[3]),
+            1 => v[0] * v[3] * v[3] / (G_GRAV * v[2]),
+            2 => v[0] * v[3] * v[3] / (G_GRAV * v[1]),
+            _ => (G_GRAV * v[1] * v[2] / v[0]).sqrt(),
+        },
+    },
+];
+
+impl Formula {
+    /// Case-insensitive match against the name, equation and category.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.name.to_lowercase().contains(&query)
+            || self.equation.to_lowercase().contains(&query)
+            || self.category.to_string().to_lowercase().contains(&query)
+    }
+
+    /// Solves for the single missing variable and returns its index and value.
+    pub fn solve(&self, values: &[Option<f64>]) -> Result<(usize, f64), String> {
+        let missing: Vec<usize> = values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_none())
+            .map(|(i, _)| i)
+            .collect();
+
+        let index = match missing.as_slice() {
+            [index] => *index,
+            [] => return Err("Zostaw jedno pole puste".to_string()),
+            _ => return Err("Uzupełnij wszystkie pola poza jednym".to_string()),
+        };
+
+        let known: Vec<f64> = values.iter().map(|v| v.unwrap_or(f64::NAN)).collect();
+        let result = (self.solve)(index, &known);
+
+        if result.is_finite() {
+            Ok((index, result))
+        } else {
+            Err("Brak rozwiązania dla podanych wartości".to_string())
+        }
+    }
+}
+
+/// State of the open formula form.
+pub struct FormulaForm {
+    pub formula: usize,
+    pub inputs: Vec<String>,
+    pub result: Option<Result<(usize, f64), String>>,
+}
+
+impl FormulaForm {
+    pub fn new(formula: usize) -> Self {
+        let inputs = FORMULAS[formula]
+            .variables
+            .iter()
+            .map(|v| v.default.map(|d| d.to_string()).unwrap_or_default())
+            .collect();
+
+        Self { formula, inputs, result: None }
+    }
+
+    pub fn formula(&self) -> &'static Formula {
+        &FORMULAS[self.formula]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formula(name: &str) -> &'static Formula {
+        FORMULAS.iter().find(|f| f.name == name).unwrap()
+    }
+
+    #[test]
+    fn solves_for_the_empty_field() {
+        let ohm = formula("Prawo Ohma");
+        assert_eq!(ohm.solve(&[Some(12.0), None, Some(4.0)]), Ok((1, 3.0)));
+        assert_eq!(ohm.solve(&[None, Some(2.0), Some(5.0)]), Ok((0, 10.0)));
+    }
+
+    #[test]
+    fn needs_exactly_one_empty_field() {
+        let ohm = formula("Prawo Ohma");
+        assert!(ohm.solve(&[Some(12.0), Some(3.0), Some(4.0)]).is_err());
+        assert!(ohm.solve(&[None, None, Some(4.0)]).is_err());
+        assert!(ohm.solve(&[Some(12.0), Some(0.0), None]).is_err());
+    }
+
+    #[test]
+    fn every_rearrangement_is_consistent() {
+        for f in FORMULAS {
+            let mut values = vec![0.3; f.variables.len()];
+            values[0] = (f.solve)(0, &values);
+            if !values[0].is_finite() {
+                continue;
+            }
+            for i in 1..values.len() {
+                let mut known = values.clone();
+                known[i] = (f.solve)(i, &values);
+                let again = (f.solve)(0, &known);
+                assert!((again - values[0]).abs() <= 1e-9 * values[0].abs().max(1.0), "{} ({})", f.name, i);
+            }
+        }
+    }
+
+    #[test]
+    fn search_covers_name_equation_and_category() {
+        let ohm = formula("Prawo Ohma");
+        assert!(ohm.matches("  OHMA "));
+        assert!(ohm.matches("u = ir"));
+        assert!(ohm.matches("elektryczn"));
+        assert!(ohm.matches(""));
+        assert!(!ohm.matches("soczewka"));
+    }
+}
@@ -0,0 +1,387 @@
+// Function plotting: the visible window in graph coordinates, grid spacing
+// and sampling of curves into polylines broken at discontinuities. Besides
+// y = f(x), curves can be parametric (x(t), y(t)) or polar (r(θ)).
+
+use std::fmt;
+
+use crate::analysis::AnalysisForm;
+use crate::export::ExportForm;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self { x_min: -10.0, x_max: 10.0, y_min: -7.0, y_max: 7.0 }
+    }
+}
+
+impl Viewport {
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+
+    /// Maps a graph point onto a `width` × `height` pixel area (y grows downwards).
+    pub fn screen_point(&self, x: f64, y: f64, width: f32, height: f32) -> (f32, f32) {
+        // Far-off points are pulled in so the renderer never sees huge values.
+        let y = y.clamp(self.y_min - 10.0 * self.height(), self.y_max + 10.0 * self.height());
+        (
+            ((x - self.x_min) / self.width() * width as f64) as f32,
+            ((self.y_max - y) / self.height() * height as f64) as f32,
+        )
+    }
+
+    pub fn graph_point(&self, px: f32, py: f32, width: f32, height: f32) -> (f64, f64) {
+        (
+            self.x_min + px as f64 / width as f64 * self.width(),
+            self.y_max - py as f64 / height as f64 * self.height(),
+        )
+    }
+
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.x_min += dx;
+        self.x_max += dx;
+        self.y_min += dy;
+        self.y_max += dy;
+    }
+
+    /// Scales the window around (`cx`, `cy`); factors below 1 zoom in.
+    pub fn zoom(&mut self, factor: f64, cx: f64, cy: f64) {
+        let width = self.width() * factor;
+        let height = self.height() * factor;
+        if !(1e-9..=1e9).contains(&width) || !(1e-9..=1e9).contains(&height) {
+            return;
+        }
+        self.x_min = cx - (cx - self.x_min) * factor;
+        self.x_max = self.x_min + width;
+        self.y_min = cy - (cy - self.y_min) * factor;
+        self.y_max = self.y_min + height;
+    }
+}
+
+/// A grid spacing of 1, 2 or 5 × 10ⁿ giving roughly `lines` lines over `range`.
+pub fn grid_step(range: f64, lines: f64) -> f64 {
+    let raw = range / lines;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .find(|m| m * magnitude >= raw)
+        .unwrap_or(10.0);
+    step * magnitude
+}
+
+/// Multiples of `step` within [min, max].
+pub fn grid_lines(min: f64, max: f64, step: f64) -> Vec<f64> {
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// A connected piece of a curve.
+pub type Segment = Vec<(f64, f64)>;
+
+/// Samples `f` across the viewport. The curve is split where it is undefined
+/// and where it jumps across the window without passing through the values in
+/// between (poles of tan or 1/x), so no vertical spikes are drawn.
+pub fn sample(mut f: impl FnMut(f64) -> f64, viewport: &Viewport, samples: usize) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current: Segment = Vec::new();
+    let step = viewport.width() / samples as f64;
+
+    for i in 0..=samples {
+        let x = viewport.x_min + i as f64 * step;
+        let y = f(x);
+        if !y.is_finite() {
+            if current.len() > 1 {
+                segments.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            continue;
+        }
+
+        if let Some(&(px, py)) = current.last() {
+            if (y - py).abs() > viewport.height() {
+                let mid = f((px + x) / 2.0);
+                let between = mid.is_finite() && mid >= py.min(y) && mid <= py.max(y);
+                if !between {
+                    if current.len() > 1 {
+                        segments.push(std::mem::take(&mut current));
+                    }
+                    current.clear();
+                }
+            }
+        }
+        current.push((x, y));
+    }
+
+    if current.len() > 1 {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Samples a parametric curve for t from `from` to `to`. As with `sample`,
+/// undefined points and jumps that skip the points in between split the curve.
+pub fn sample_parametric(
+    mut f: impl FnMut(f64) -> (f64, f64),
+    from: f64,
+    to: f64,
+    step: f64,
+    viewport: &Viewport,
+) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current: Segment = Vec::new();
+    let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+    let jump = viewport.width().hypot(viewport.height());
+    let count = ((to - from) / step).floor() as usize;
+
+    for i in 0..=count {
+        let t = from + i as f64 * step;
+        let p = f(t);
+        if !p.0.is_finite() || !p.1.is_finite() {
+            if current.len() > 1 {
+                segments.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            continue;
+        }
+
+        if let Some(&last) = current.last() {
+            let gap = distance(last, p);
+            if gap > jump {
+                let mid = f(t - step / 2.0);
+                if !(distance(last, mid) < gap && distance(mid, p) < gap) {
+                    if current.len() > 1 {
+                        segments.push(std::mem::take(&mut current));
+                    }
+                    current.clear();
+                }
+            }
+        }
+        current.push(p);
+    }
+
+    if current.len() > 1 {
+        segments.push(current);
+    }
+    segments
+}
+
+/// The sampled point closest to `x`, for tracing.
+pub fn nearest(segments: &[Segment], x: f64) -> Option<(f64, f64)> {
+    segments
+        .iter()
+        .flatten()
+        .copied()
+        .min_by(|a, b| (a.0 - x).abs().total_cmp(&(b.0 - x).abs()))
+}
+
+/// The sampled point closest to (`x`, `y`), measured relative to the window size.
+pub fn nearest_point(segments: &[Segment], x: f64, y: f64, viewport: &Viewport) -> Option<(f64, f64)> {
+    let distance = |p: &(f64, f64)| ((p.0 - x) / viewport.width()).hypot((p.1 - y) / viewport.height());
+    segments
+        .iter()
+        .flatten()
+        .copied()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveKind {
+    Function,
+    Parametric,
+    Polar,
+}
+
+impl CurveKind {
+    pub const ALL: [CurveKind; 3] = [CurveKind::Function, CurveKind::Parametric, CurveKind::Polar];
+
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            CurveKind::Function => "y = f(x)",
+            CurveKind::Parametric => "x(t), y(t)",
+            CurveKind::Polar => "r = f(θ)",
+        }
+    }
+}
+
+impl fmt::Display for CurveKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CurveKind::Function => "y(x)",
+                CurveKind::Parametric => "Parametryczna",
+                CurveKind::Polar => "Biegunowa",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CurveField {
+    Input,
+    From,
+    To,
+    Step,
+}
+
+pub struct Curve {
+    pub kind: CurveKind,
+    pub input: String,
+    /// Parameter range of parametric and polar curves; empty means the default.
+    pub from: String,
+    pub to: String,
+    pub step: String,
+    pub segments: Vec<Segment>,
+    pub error: Option<String>,
+}
+
+impl Curve {
+    pub fn new(input: &str) -> Self {
+        Self {
+            kind: CurveKind::Function,
+            input: input.to_string(),
+            from: String::new(),
+            to: String::new(),
+            step: String::new(),
+            segments: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: CurveField) -> &mut String {
+        match field {
+            CurveField::Input => &mut self.input,
+            CurveField::From => &mut self.from,
+            CurveField::To => &mut self.to,
+            CurveField::Step => &mut self.step,
+        }
+    }
+
+    /// The expression without a `y =`, `r =` or `r(θ) =` style prefix.
+    pub fn expression(&self) -> &str {
+        let input = self.input.trim();
+        match input.split_once('=') {
+            Some((lhs, rhs)) if !lhs.trim().is_empty() && lhs.trim().chars().all(|c| c.is_alphanumeric() || c == '(' || c == ')') => {
+                rhs.trim()
+            }
+            _ => input,
+        }
+    }
+
+    /// The `x(t)` and `y(t)` parts of a parametric curve, split at the
+    /// comma outside any brackets.
+    pub fn parametric_parts(&self) -> Option<(&str, &str)> {
+        let input = self.expression();
+        let mut depth = 0;
+        for (i, c) in input.char_indices() {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                ',' if depth == 0 => return Some((input[..i].trim(), input[i + 1..].trim())),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+pub struct PlotForm {
+    pub curves: Vec<Curve>,
+    pub viewport: Viewport,
+    pub analysis: AnalysisForm,
+    pub export: ExportForm,
+}
+
+impl Default for PlotForm {
+    fn default() -> Self {
+        Self {
+            curves: vec![Curve::new("y = sin(x)")],
+            viewport: Viewport::default(),
+            analysis: AnalysisForm::default(),
+            export: ExportForm::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_and_graph_points_are_inverse() {
+        let viewport = Viewport::default();
+        assert_eq!(viewport.screen_point(0.0, 0.0, 400.0, 280.0), (200.0, 140.0));
+        assert_eq!(viewport.screen_point(-10.0, 7.0, 400.0, 280.0), (0.0, 0.0));
+        let (x, y) = viewport.graph_point(100.0, 70.0, 400.0, 280.0);
+        assert!((x - -5.0).abs() < 1e-9 && (y - 3.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pan_and_zoom() {
+        let mut viewport = Viewport::default();
+        viewport.pan(5.0, -2.0);
+        assert_eq!((viewport.x_min, viewport.y_max), (-5.0, 5.0));
+
+        viewport.zoom(0.5, 0.0, 0.0);
+        assert_eq!((viewport.x_min, viewport.x_max), (-2.5, 7.5));
+        assert_eq!(viewport.height(), 7.0);
+
+        let before = viewport;
+        viewport.zoom(1e-12, 0.0, 0.0);
+        assert_eq!(viewport, before);
+    }
+
+    #[test]
+    fn grid() {
+        assert_eq!(grid_step(20.0, 10.0), 2.0);
+        assert_eq!(grid_step(7.0, 10.0), 1.0);
+        assert_eq!(grid_step(0.3, 10.0), 0.05);
+        assert_eq!(grid_lines(-3.5, 4.2, 2.0), [-2.0, 0.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn sampling_breaks_at_poles_and_gaps() {
+        let viewport = Viewport::default();
+        assert_eq!(sample(|x| x * x, &viewport, 100).len(), 1);
+        assert_eq!(sample(|x| 1.0 / x, &viewport, 101).len(), 2);
+        assert_eq!(sample(f64::sqrt, &viewport, 100).len(), 1);
+        assert_eq!(sample(f64::tan, &viewport, 400).len(), 7);
+
+        let segments = sample(|x| x, &viewport, 100);
+        let (x, _) = nearest(&segments, 0.33).unwrap();
+        assert!((x - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn curve_input_forms() {
+        assert_eq!(Curve::new("y = x^2").expression(), "x^2");
+        assert_eq!(Curve::new("r(θ) = 1 + cos(θ)").expression(), "1 + cos(θ)");
+        assert_eq!(Curve::new("x^2 = 4").expression(), "x^2 = 4");
+
+        assert_eq!(Curve::new("cos(t), max(t, 1)").parametric_parts(), Some(("cos(t)", "max(t, 1)")));
+        assert_eq!(Curve::new("sin(x)").parametric_parts(), None);
+    }
+
+    #[test]
+    fn parametric_sampling() {
+        let viewport = Viewport::default();
+        let circle = sample_parametric(|t| (t.cos(), t.sin()), 0.0, 2.0 * std::f64::consts::PI, 0.01, &viewport);
+        assert_eq!(circle.len(), 1);
+        assert!(circle[0].iter().all(|(x, y)| (x.hypot(*y) - 1.0).abs() < 1e-12));
+
+        let hyperbola = sample_parametric(|t| (t, 1.0 / t), -1.0, 1.0, 0.01, &viewport);
+        assert_eq!(hyperbola.len(), 2);
+    }
+}
@@ -0,0 +1,213 @@
+// Pricing worksheet linking cost, price, margin and markup, plus break-even.
+//
+// The four pricing values have two degrees of freedom, so the field being
+// edited is combined with the most recently edited other field to recompute
+// the remaining two.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PricingField {
+    Cost,
+    Price,
+    Margin,
+    Markup,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Prices {
+    pub cost: f64,
+    pub price: f64,
+    /// Percent of the price.
+    pub margin: f64,
+    /// Percent of the cost.
+    pub markup: f64,
+}
+
+/// Computes all four values from two known ones (percentages in %).
+pub fn solve(a: (PricingField, f64), b: (PricingField, f64)) -> Result<Prices, String> {
+    use PricingField::*;
+
+    let value = |field| {
+        if a.0 == field {
+            Some(a.1)
+        } else if b.0 == field {
+            Some(b.1)
+        } else {
+            None
+        }
+    };
+
+    let (cost, price) = match (value(Cost), value(Price), value(Margin), value(Markup)) {
+        (Some(cost), Some(price), _, _) => (cost, price),
+        (Some(cost), _, Some(margin), _) => (cost, cost / (1.0 - margin / 100.0)),
+        (Some(cost), _, _, Some(markup)) => (cost, cost * (1.0 + markup / 100.0)),
+        (_, Some(price), Some(margin), _) => (price * (1.0 - margin / 100.0), price),
+        (_, Some(price), _, Some(markup)) => (price / (1.0 + markup / 100.0), price),
+        _ => return Err("Podaj koszt lub cenę".to_string()),
+    };
+
+    let prices = Prices {
+        cost,
+        price,
+        margin: (price - cost) / price * 100.0,
+        markup: (price - cost) / cost * 100.0,
+    };
+
+    if [prices.cost, prices.price, prices.margin, prices.markup].iter().all(|v| v.is_finite()) {
+        Ok(prices)
+    } else {
+        Err("Nie da się wyznaczyć cen dla tych wartości".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BreakEven {
+    pub contribution: f64,
+    pub units: f64,
+    pub revenue: f64,
+}
+
+pub fn break_even(fixed_costs: f64, unit_price: f64, unit_variable_cost: f64) -> Result<BreakEven, String> {
+    let contribution = unit_price - unit_variable_cost;
+    if contribution <= 0.0 {
+        return Err("Cena musi przewyższać koszt zmienny jednostki".to_string());
+    }
+
+    let units = fixed_costs / contribution;
+    Ok(BreakEven { contribution, units, revenue: units * unit_price })
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BreakEvenField {
+    FixedCosts,
+    UnitPrice,
+    UnitVariableCost,
+}
+
+#[derive(Default)]
+pub struct PricingForm {
+    pub cost: String,
+    pub price: String,
+    pub margin: String,
+    pub markup: String,
+    // Most recent edit first.
+    pub history: Vec<PricingField>,
+    pub error: Option<String>,
+    pub fixed_costs: String,
+    pub unit_price: String,
+    pub unit_variable_cost: String,
+}
+
+impl PricingForm {
+    pub fn field(&self, field: PricingField) -> &String {
+        match field {
+            PricingField::Cost => &self.cost,
+            PricingField::Price => &self.price,
+            PricingField::Margin => &self.margin,
+            PricingField::Markup => &self.markup,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: PricingField) -> &mut String {
+        match field {
+            PricingField::Cost => &mut self.cost,
+            PricingField::Price => &mut self.price,
+            PricingField::Margin => &mut self.margin,
+            PricingField::Markup => &mut self.markup,
+        }
+    }
+
+    pub fn break_even_field_mut(&mut self, field: BreakEvenField) -> &mut String {
+        match field {
+            BreakEvenField::FixedCosts => &mut self.fixed_costs,
+            BreakEvenField::UnitPrice => &mut self.unit_price,
+            BreakEvenField::UnitVariableCost => &mut self.unit_variable_cost,
+        }
+    }
+
+    /// Records an edit and returns the field to pair it with, if any.
+    pub fn edited(&mut self, field: PricingField) -> Option<PricingField> {
+        self.history.retain(|f| *f != field);
+        self.history.insert(0, field);
+
+        let partner = self.history[1..]
+            .iter()
+            .copied()
+            .find(|f| !self.field(*f).trim().is_empty());
+
+        // Margin and markup fix only the ratio; anchor them to a cost or price.
+        match (field, partner) {
+            (PricingField::Margin, Some(PricingField::Markup)) | (PricingField::Markup, Some(PricingField::Margin)) | (_, None) => {
+                [PricingField::Cost, PricingField::Price]
+                    .into_iter()
+                    .find(|f| *f != field && !self.field(*f).trim().is_empty())
+            }
+            (_, partner) => partner,
+        }
+    }
+
+    /// Writes the derived values into every field except `edited`.
+    pub fn fill(&mut self, prices: &Prices, edited: PricingField) {
+        let values = [
+            (PricingField::Cost, prices.cost),
+            (PricingField::Price, prices.price),
+            (PricingField::Margin, prices.margin),
+            (PricingField::Markup, prices.markup),
+        ];
+        for (field, value) in values {
+            if field != edited {
+                *self.field_mut(field) = format!("{:.2}", value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PricingField::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-9
+    }
+
+    #[test]
+    fn any_two_values_give_the_rest() {
+        let prices = solve((Cost, 80.0), (Price, 100.0)).unwrap();
+        assert!(close(prices.margin, 20.0) && close(prices.markup, 25.0));
+
+        let prices = solve((Margin, 20.0), (Cost, 80.0)).unwrap();
+        assert!(close(prices.price, 100.0));
+
+        let prices = solve((Price, 100.0), (Markup, 25.0)).unwrap();
+        assert!(close(prices.cost, 80.0) && close(prices.margin, 20.0));
+    }
+
+    #[test]
+    fn needs_a_cost_or_price() {
+        assert!(solve((Margin, 20.0), (Markup, 25.0)).is_err());
+        assert!(solve((Cost, 80.0), (Margin, 100.0)).is_err());
+    }
+
+    #[test]
+    fn break_even_point() {
+        let point = break_even(10_000.0, 50.0, 30.0).unwrap();
+        assert!(close(point.units, 500.0) && close(point.revenue, 25_000.0));
+        assert!(break_even(10_000.0, 30.0, 30.0).is_err());
+    }
+
+    #[test]
+    fn edits_pair_with_the_latest_filled_field() {
+        let mut form = PricingForm { cost: "80".to_string(), ..Default::default() };
+        assert_eq!(form.edited(Cost), None);
+
+        form.margin = "20".to_string();
+        assert_eq!(form.edited(Margin), Some(Cost));
+
+        form.markup = "25".to_string();
+        assert_eq!(form.edited(Markup), Some(Cost));
+
+        form.fill(&solve((Markup, 25.0), (Cost, 80.0)).unwrap(), Markup);
+        assert_eq!(form.price, "100.00");
+        assert_eq!(form.markup, "25");
+    }
+}
@@ -0,0 +1,299 @@
+// Exact fractions with i128 parts, kept in lowest terms with a positive
+// denominator. Arithmetic is checked: an overflow gives None, so callers can
+// fall back to floating point.
+
+use std::fmt;
+
+/// Choices for the largest denominator shown in fraction mode.
+pub const MAX_DENOMINATORS: [i128; 6] = [10, 100, 1000, 10_000, 100_000, 1_000_000];
+pub const DEFAULT_MAX_DENOMINATOR: i128 = 1000;
+
+/// How far a decimal may be from a fraction and still be taken for it; about
+/// the rounding error of a few floating-point operations.
+const RECOVERY_TOLERANCE: f64 = 1e-14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+pub fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn integer(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+
+    /// `num / den` in lowest terms; None for a zero denominator.
+    pub fn new(num: i128, den: i128) -> Option<Self> {
+        if den == 0 {
+            return None;
+        }
+        let divisor = gcd(num, den);
+        let sign = if den < 0 { -1 } else { 1 };
+        Some(Self {
+            num: (num / divisor).checked_mul(sign)?,
+            den: sign.checked_mul(den / divisor)?,
+        })
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.num < 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    pub fn abs(&self) -> Self {
+        Self { num: self.num.abs(), den: self.den }
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self { num: self.num.checked_neg()?, den: self.den })
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let divisor = gcd(self.den, other.den);
+        let left = self.num.checked_mul(other.den / divisor)?;
+        let right = other.num.checked_mul(self.den / divisor)?;
+        Self::new(left.checked_add(right)?, self.den.checked_mul(other.den / divisor)?)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        // Cancelling crosswise first keeps the intermediate values small.
+        let a = gcd(self.num, other.den).max(1);
+        let b = gcd(other.num, self.den).max(1);
+        Self::new(
+            (self.num / a).checked_mul(other.num / b)?,
+            (self.den / b).checked_mul(other.den / a)?,
+        )
+    }
+
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.checked_mul(&Self::new(other.den, other.num)?)
+    }
+
+    pub fn checked_pow(&self, exponent: i32) -> Option<Self> {
+        let power = Self {
+            num: self.num.checked_pow(exponent.unsigned_abs())?,
+            den: self.den.checked_pow(exponent.unsigned_abs())?,
+        };
+        if exponent < 0 {
+            Self::new(power.den, power.num)
+        } else {
+            Some(power)
+        }
+    }
+
+    /// The simplest fraction with a denominator up to `max_denominator` that
+    /// `x` equals up to rounding error, from the convergents of its continued
+    /// fraction: 0.30000000000000004 gives 3/10, π gives None.
+    pub fn recover(x: f64, max_denominator: i128) -> Option<Self> {
+        if !x.is_finite() || x.abs() > 1e15 {
+            return None;
+        }
+        let (mut h0, mut h1) = (0i128, 1i128);
+        let (mut k0, mut k1) = (1i128, 0i128);
+        let mut rest = x;
+        loop {
+            let a = rest.floor();
+            let h = (a as i128).checked_mul(h1)?.checked_add(h0)?;
+            let k = (a as i128).checked_mul(k1)?.checked_add(k0)?;
+            if k > max_denominator {
+                return None;
+            }
+            if (x - h as f64 / k as f64).abs() <= RECOVERY_TOLERANCE * x.abs() {
+                return Self::new(h, k);
+            }
+            if rest == a {
+                return None;
+            }
+            (h0, h1, k0, k1) = (h1, h, k1, k);
+            rest = 1.0 / (rest - a);
+        }
+    }
+
+    /// `7/2` as the mixed number `3 1/2`; proper fractions stay as they are.
+    pub fn to_mixed(self) -> String {
+        let whole = self.num / self.den;
+        let rest = (self.num % self.den).abs();
+        if whole == 0 || rest == 0 {
+            self.to_string()
+        } else {
+            format!("{} {}/{}", whole, rest, self.den)
+        }
+    }
+
+    /// Parses `3`, `-0.25`, `1/3` or `2.5/4`; anything else gives None.
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.split_once('/') {
+            Some((num, den)) => Self::parse_decimal(num)?.checked_div(&Self::parse_decimal(den)?),
+            None => Self::parse_decimal(input),
+        }
+    }
+
+    fn parse_decimal(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut num: i128 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            num = num.checked_mul(10)?.checked_add(c.to_digit(10)? as i128)?;
+        }
+        let den = 10i128.checked_pow(fraction.len() as u32)?;
+        Self::new(if negative { -num } else { num }, den)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// How numeric results are shown; the S⇔D key cycles through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberFormat {
+    #[default]
+    Decimal,
+    Fraction,
+    Mixed,
+}
+
+impl NumberFormat {
+    pub fn next(self) -> Self {
+        match self {
+            NumberFormat::Decimal => NumberFormat::Fraction,
+            NumberFormat::Fraction => NumberFormat::Mixed,
+            NumberFormat::Mixed => NumberFormat::Decimal,
+        }
+    }
+
+    /// `value` in this format. `exact` is the value as a fraction when it is
+    /// known; otherwise a fraction is recovered from the decimal, and the
+    /// decimal is shown when none fits within `max_denominator`.
+    pub fn format(self, value: f64, exact: Option<Rational>, max_denominator: i128) -> String {
+        if self == NumberFormat::Decimal {
+            return value.to_string();
+        }
+        let fraction = exact
+            .filter(|r| r.den <= max_denominator)
+            .or_else(|| Rational::recover(value, max_denominator));
+        match (self, fraction) {
+            (NumberFormat::Fraction, Some(r)) => r.to_string(),
+            (NumberFormat::Mixed, Some(r)) => r.to_mixed(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn lowest_terms_and_sign() {
+        assert_eq!(r(6, -8), r(-3, 4));
+        assert_eq!((r(6, -8).numer(), r(6, -8).denom()), (-3, 4));
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(r(-3, 4).to_string(), "-3/4");
+        assert_eq!(r(8, 4).to_string(), "2");
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        assert_eq!(r(1, 3).checked_add(&r(1, 6)), Some(r(1, 2)));
+        assert_eq!(r(1, 2).checked_sub(&r(3, 4)), Some(r(-1, 4)));
+        assert_eq!(r(2, 3).checked_mul(&r(9, 4)), Some(r(3, 2)));
+        assert_eq!(r(1, 2).checked_div(&Rational::ZERO), None);
+        assert_eq!(r(2, 3).checked_pow(-2), Some(r(9, 4)));
+        assert_eq!(Rational::integer(i128::MAX).checked_add(&Rational::ONE), None);
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(Rational::parse("-0.25"), Some(r(-1, 4)));
+        assert_eq!(Rational::parse(" 2.5/4 "), Some(r(5, 8)));
+        assert_eq!(Rational::parse("+3"), Some(Rational::integer(3)));
+        assert_eq!(Rational::parse("1/0"), None);
+        assert_eq!(Rational::parse("."), None);
+        assert_eq!(Rational::parse("1e3"), None);
+    }
+
+    #[test]
+    fn recovery_from_floating_point() {
+        assert_eq!(Rational::recover(0.30000000000000004, 1000), Some(r(3, 10)));
+        assert_eq!(Rational::recover(1.0 / 3.0, 1000), Some(r(1, 3)));
+        assert_eq!(Rational::recover(-2.5, 10), Some(r(-5, 2)));
+        assert_eq!(Rational::recover(std::f64::consts::PI, 1000), None);
+        assert_eq!(Rational::recover(1.0 / 1024.0, 1000), None);
+        assert_eq!(Rational::recover(f64::NAN, 1000), None);
+    }
+
+    #[test]
+    fn mixed_numbers() {
+        assert_eq!(r(7, 2).to_mixed(), "3 1/2");
+        assert_eq!(r(-7, 3).to_mixed(), "-2 1/3");
+        assert_eq!(r(2, 3).to_mixed(), "2/3");
+        assert_eq!(r(4, 2).to_mixed(), "2");
+    }
+
+    #[test]
+    fn number_formats() {
+        assert_eq!(NumberFormat::Decimal.next().next().next(), NumberFormat::Decimal);
+        assert_eq!(NumberFormat::Decimal.format(0.5, None, 1000), "0.5");
+        assert_eq!(NumberFormat::Fraction.format(0.1 + 0.2, None, 1000), "3/10");
+        assert_eq!(NumberFormat::Mixed.format(3.5, None, 1000), "3 1/2");
+        assert_eq!(NumberFormat::Fraction.format(1.0 / 3.0, Some(r(1, 3)), 1000), "1/3");
+        // An exact value beyond the limit falls back to the decimal.
+        assert_eq!(NumberFormat::Fraction.format(0.0001, Some(r(1, 10_000)), 1000), "0.0001");
+    }
+}
@@ -0,0 +1,297 @@
+// Least-squares regression for paired (x, y) data. The exponential,
+// logarithmic and power models are fitted as straight lines after taking
+// logarithms; R² is always reported against the original y values.
+
+use std::fmt;
+
+use crate::stats::{mean, parse_data};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Linear,
+    Polynomial,
+    Exponential,
+    Logarithmic,
+    Power,
+}
+
+impl Model {
+    pub const ALL: [Model; 5] = [
+        Model::Linear,
+        Model::Polynomial,
+        Model::Exponential,
+        Model::Logarithmic,
+        Model::Power,
+    ];
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Model::Linear => "Liniowa: y = a + bx",
+                Model::Polynomial => "Wielomianowa",
+                Model::Exponential => "Wykładnicza: y = a·e^(bx)",
+                Model::Logarithmic => "Logarytmiczna: y = a + b·ln x",
+                Model::Power => "Potęgowa: y = a·x^b",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Fit {
+    pub model: Model,
+    /// `[a, b]` for two-parameter models, `[c0, c1, ..]` for polynomials.
+    pub coefficients: Vec<f64>,
+    pub r_squared: f64,
+}
+
+impl Fit {
+    pub fn predict(&self, x: f64) -> f64 {
+        let c = &self.coefficients;
+        match self.model {
+            Model::Linear | Model::Polynomial => c.iter().rev().fold(0.0, |acc, coefficient| acc * x + coefficient),
+            Model::Exponential => c[0] * (c[1] * x).exp(),
+            Model::Logarithmic => c[0] + c[1] * x.ln(),
+            Model::Power => c[0] * x.powf(c[1]),
+        }
+    }
+
+    pub fn equation(&self) -> String {
+        let c = &self.coefficients;
+        match self.model {
+            Model::Linear | Model::Polynomial => {
+                let mut terms = Vec::new();
+                for (power, coefficient) in c.iter().enumerate() {
+                    terms.push(match power {
+                        0 => format!("{:.6}", coefficient),
+                        1 => format!("{:.6}x", coefficient),
+                        _ => format!("{:.6}x^{}", coefficient, power),
+                    });
+                }
+                format!("y = {}", terms.join(" + ").replace("+ -", "- "))
+            }
+            Model::Exponential => format!("y = {:.6}·e^({:.6}x)", c[0], c[1]),
+            Model::Logarithmic => format!("y = {:.6} + {:.6}·ln x", c[0], c[1]).replace("+ -", "- "),
+            Model::Power => format!("y = {:.6}·x^{:.6}", c[0], c[1]),
+        }
+    }
+}
+
+/// Pearson correlation coefficient.
+pub fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let (mx, my) = (mean(x), mean(y));
+    let mut sxy = 0.0;
+    let mut sxx = 0.0;
+    let mut syy = 0.0;
+    for (xi, yi) in x.iter().zip(y) {
+        sxy += (xi - mx) * (yi - my);
+        sxx += (xi - mx).powi(2);
+        syy += (yi - my).powi(2);
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+/// Least-squares polynomial coefficients, lowest power first.
+fn polynomial(x: &[f64], y: &[f64], degree: usize) -> Result<Vec<f64>, String> {
+    let n = degree + 1;
+    if x.len() < n {
+        return Err(format!("Potrzeba co najmniej {} punktów", n));
+    }
+
+    // Normal equations (XᵀX)c = Xᵀy as an augmented matrix.
+    let mut m = vec![vec![0.0; n + 1]; n];
+    for (xi, yi) in x.iter().zip(y) {
+        let powers: Vec<f64> = (0..=2 * degree).map(|p| xi.powi(p as i32)).collect();
+        for (row, cells) in m.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().take(n).enumerate() {
+                *cell += powers[row + col];
+            }
+            cells[n] += powers[row] * yi;
+        }
+    }
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))
+            .unwrap_or(col);
+        if m[pivot][col].abs() < 1e-12 {
+            return Err("Wartości x nie pozwalają dopasować modelu".to_string());
+        }
+        m.swap(col, pivot);
+
+        let pivot_row = m[col].clone();
+        for (row, cells) in m.iter_mut().enumerate() {
+            if row != col {
+                let factor = cells[col] / pivot_row[col];
+                for (cell, pivot) in cells.iter_mut().zip(&pivot_row).skip(col) {
+                    *cell -= factor * pivot;
+                }
+            }
+        }
+    }
+
+    Ok((0..n).map(|i| m[i][n] / m[i][i]).collect())
+}
+
+pub fn fit(model: Model, x: &[f64], y: &[f64], degree: usize) -> Result<Fit, String> {
+    if x.len() != y.len() {
+        return Err(format!("Różna liczba wartości x ({}) i y ({})", x.len(), y.len()));
+    }
+    if x.len() < 2 {
+        return Err("Potrzeba co najmniej dwóch punktów".to_string());
+    }
+    if matches!(model, Model::Logarithmic | Model::Power) && x.iter().any(|v| *v <= 0.0) {
+        return Err("Model wymaga dodatnich wartości x".to_string());
+    }
+    if matches!(model, Model::Exponential | Model::Power) && y.iter().any(|v| *v <= 0.0) {
+        return Err("Model wymaga dodatnich wartości y".to_string());
+    }
+
+    let ln = |values: &[f64]| values.iter().map(|v| v.ln()).collect::<Vec<_>>();
+    let coefficients = match model {
+        Model::Linear => polynomial(x, y, 1)?,
+        Model::Polynomial => {
+            if degree == 0 {
+                return Err("Stopień wielomianu musi wynosić co najmniej 1".to_string());
+            }
+            polynomial(x, y, degree)?
+        }
+        Model::Exponential => {
+            let line = polynomial(x, &ln(y), 1)?;
+            vec![line[0].exp(), line[1]]
+        }
+        Model::Logarithmic => polynomial(&ln(x), y, 1)?,
+        Model::Power => {
+            let line = polynomial(&ln(x), &ln(y), 1)?;
+            vec![line[0].exp(), line[1]]
+        }
+    };
+
+    let mut fit = Fit { model, coefficients, r_squared: 0.0 };
+    let my = mean(y);
+    let total: f64 = y.iter().map(|yi| (yi - my).powi(2)).sum();
+    let residual: f64 = x.iter().zip(y).map(|(xi, yi)| (yi - fit.predict(*xi)).powi(2)).sum();
+    fit.r_squared = 1.0 - residual / total;
+    Ok(fit)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum RegressionField {
+    X,
+    Y,
+    Degree,
+    Predict,
+}
+
+pub struct RegressionForm {
+    pub x: String,
+    pub y: String,
+    pub model: Model,
+    pub degree: String,
+    pub predict: String,
+    pub fit: Option<Result<Fit, String>>,
+    pub correlation: Option<f64>,
+    pub prediction: Option<f64>,
+}
+
+impl Default for RegressionForm {
+    fn default() -> Self {
+        Self {
+            x: String::new(),
+            y: String::new(),
+            model: Model::Linear,
+            degree: String::from("2"),
+            predict: String::new(),
+            fit: None,
+            correlation: None,
+            prediction: None,
+        }
+    }
+}
+
+impl RegressionForm {
+    pub fn field_mut(&mut self, field: RegressionField) -> &mut String {
+        match field {
+            RegressionField::X => &mut self.x,
+            RegressionField::Y => &mut self.y,
+            RegressionField::Degree => &mut self.degree,
+            RegressionField::Predict => &mut self.predict,
+        }
+    }
+
+    /// Refits the model to the current data; the prediction is left to the caller.
+    pub fn refit(&mut self) {
+        self.correlation = None;
+        self.prediction = None;
+        if self.x.trim().is_empty() || self.y.trim().is_empty() {
+            self.fit = None;
+            return;
+        }
+
+        let result = self.compute();
+        self.correlation = result.as_ref().ok().map(|(_, r)| *r);
+        self.fit = Some(result.map(|(fit, _)| fit));
+    }
+
+    fn compute(&self) -> Result<(Fit, f64), String> {
+        let x = parse_data(&self.x)?;
+        let y = parse_data(&self.y)?;
+        let degree = self
+            .degree
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| "Niepoprawny stopień wielomianu".to_string())?;
+
+        let fit = fit(self.model, &x, &y, degree)?;
+        Ok((fit, correlation(&x, &y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+    fn close(actual: &[f64], expected: &[f64]) -> bool {
+        actual.len() == expected.len() && actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn linear_fit_and_correlation() {
+        let y = [2.0, 4.0, 5.0, 4.0, 5.0];
+        let line = fit(Model::Linear, &X, &y, 1).unwrap();
+        assert!(close(&line.coefficients, &[2.2, 0.6]));
+        assert!((line.r_squared - 0.6).abs() < 1e-9);
+        assert!((correlation(&X, &y) - 0.6f64.sqrt()).abs() < 1e-9);
+        assert_eq!(line.equation(), "y = 2.200000 + 0.600000x");
+    }
+
+    #[test]
+    fn exact_models_are_recovered() {
+        let square: Vec<f64> = X.iter().map(|x| 1.0 - 2.0 * x + 3.0 * x * x).collect();
+        let parabola = fit(Model::Polynomial, &X, &square, 2).unwrap();
+        assert!(close(&parabola.coefficients, &[1.0, -2.0, 3.0]));
+        assert!((parabola.r_squared - 1.0).abs() < 1e-12);
+
+        let growth: Vec<f64> = X.iter().map(|x| 2.0 * (0.5 * x).exp()).collect();
+        assert!(close(&fit(Model::Exponential, &X, &growth, 1).unwrap().coefficients, &[2.0, 0.5]));
+
+        let power: Vec<f64> = X.iter().map(|x| 3.0 * x.powf(1.5)).collect();
+        let curve = fit(Model::Power, &X, &power, 1).unwrap();
+        assert!(close(&curve.coefficients, &[3.0, 1.5]));
+        assert!((curve.predict(4.0) - 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_unsuitable_data() {
+        assert!(fit(Model::Linear, &X, &[1.0, 2.0], 1).is_err());
+        assert!(fit(Model::Linear, &[1.0, 1.0], &[1.0, 2.0], 1).is_err());
+        assert!(fit(Model::Logarithmic, &[0.0, 1.0], &[1.0, 2.0], 1).is_err());
+        assert!(fit(Model::Polynomial, &X[..2], &[1.0, 2.0], 2).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+// Compound interest with regular contributions, capital-gains tax ("podatek
+// Belki") charged on the profit at the end and inflation-adjusted results.
+
+use crate::expr::Context;
+use crate::loan::Frequency;
+
+/// Longest plan simulated, so the yearly table stays small.
+const MAX_YEARS: u32 = 100;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Savings {
+    pub principal: f64,
+    pub annual_rate: f64,
+    pub compounding: Frequency,
+    pub contribution: f64,
+    pub contribution_frequency: Frequency,
+    pub years: u32,
+    pub tax_rate: f64,
+    pub inflation: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SavingsYear {
+    pub year: u32,
+    pub contributions: f64,
+    pub interest: f64,
+    pub balance: f64,
+    pub real_balance: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SavingsResult {
+    pub total_contributed: f64,
+    pub gross_balance: f64,
+    pub tax: f64,
+    pub final_nominal: f64,
+    pub final_real: f64,
+    pub rows: Vec<SavingsYear>,
+}
+
+impl Savings {
+    /// Simulates contribution periods using the interest rate equivalent to
+    /// the chosen compounding; contributions are paid at the end of a period.
+    pub fn simulate(&self) -> Result<SavingsResult, String> {
+        if self.years == 0 {
+            return Err("Podaj liczbę lat".to_string());
+        }
+        if self.years > MAX_YEARS {
+            return Err(format!("Okres może wynosić najwyżej {} lat", MAX_YEARS));
+        }
+        let inputs = [self.principal, self.annual_rate, self.contribution, self.tax_rate, self.inflation];
+        if !inputs.iter().all(|v| v.is_finite()) {
+            return Err("Wszystkie pola muszą być skończonymi liczbami".to_string());
+        }
+        if self.principal < 0.0 || self.contribution < 0.0 {
+            return Err("Kwoty nie mogą być ujemne".to_string());
+        }
+
+        let m = self.compounding.per_year() as f64;
+        let p = self.contribution_frequency.per_year();
+        let rate = (1.0 + self.annual_rate / 100.0 / m).powf(m / p as f64) - 1.0;
+        let inflation = self.inflation / 100.0;
+
+        let mut balance = self.principal;
+        let mut total_contributed = self.principal;
+        let mut rows = Vec::with_capacity(self.years as usize);
+
+        for year in 1..=self.years {
+            let mut interest = 0.0;
+            for _ in 0..p {
+                let earned = balance * rate;
+                interest += earned;
+                balance += earned + self.contribution;
+            }
+            let contributions = self.contribution * p as f64;
+            total_contributed += contributions;
+
+            rows.push(SavingsYear {
+                year,
+                contributions,
+                interest,
+                balance,
+                real_balance: balance / (1.0 + inflation).powi(year as i32),
+            });
+        }
+
+        let tax = (balance - total_contributed).max(0.0) * self.tax_rate / 100.0;
+        let final_nominal = balance - tax;
+
+        Ok(SavingsResult {
+            total_contributed,
+            gross_balance: balance,
+            tax,
+            final_nominal,
+            final_real: final_nominal / (1.0 + inflation).powi(self.years as i32),
+            rows,
+        })
+    }
+}
+
+/// Registers `compound`, `savings` and `realvalue`; rates are decimals.
+pub fn register(ctx: &mut Context) {
+    // compound(principal, rate, years, [compounds per year = 1])
+    ctx.funcn(
+        "compound",
+        |a| {
+            let n = a.get(3).copied().unwrap_or(1.0);
+            a[0] * (1.0 + a[1] / n).powf(n * a[2])
+        },
+        3..=4,
+    );
+    // savings(principal, rate, years, contribution, [periods per year = 12])
+    ctx.funcn(
+        "savings",
+        |a| {
+            let n = a.get(4).copied().unwrap_or(12.0);
+            let i = a[1] / n;
+            let periods = n * a[2];
+            let growth = (1.0 + i).powf(periods);
+            let annuity = if i == 0.0 { periods } else { (growth - 1.0) / i };
+            a[0] * growth + a[3] * annuity
+        },
+        4..=5,
+    );
+    // realvalue(amount, inflation, years)
+    ctx.funcn("realvalue", |a| a[0] / (1.0 + a[1]).powf(a[2]), 3..=3);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SavingsField {
+    Principal,
+    Rate,
+    Years,
+    Contribution,
+    Tax,
+    Inflation,
+}
+
+pub struct SavingsForm {
+    pub principal: String,
+    pub rate: String,
+    pub years: String,
+    pub contribution: String,
+    pub tax: String,
+    pub inflation: String,
+    pub compounding: Frequency,
+    pub contribution_frequency: Frequency,
+    pub result: Option<Result<SavingsResult, String>>,
+}
+
+impl Default for SavingsForm {
+    fn default() -> Self {
+        Self {
+            principal: String::new(),
+            rate: String::new(),
+            years: String::new(),
+            contribution: String::new(),
+            tax: String::from("19"),
+            inflation: String::new(),
+            compounding: Frequency::Monthly,
+            contribution_frequency: Frequency::Monthly,
+            result: None,
+        }
+    }
+}
+
+impl SavingsForm {
+    pub fn field_mut(&mut self, field: SavingsField) -> &mut String {
+        match field {
+            SavingsField::Principal => &mut self.principal,
+            SavingsField::Rate => &mut self.rate,
+            SavingsField::Years => &mut self.years,
+            SavingsField::Contribution => &mut self.contribution,
+            SavingsField::Tax => &mut self.tax,
+            SavingsField::Inflation => &mut self.inflation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn savings(principal: f64, annual_rate: f64, contribution: f64, frequency: Frequency, years: u32) -> Savings {
+        Savings {
+            principal,
+            annual_rate,
+            compounding: frequency,
+            contribution,
+            contribution_frequency: frequency,
+            years,
+            tax_rate: 0.0,
+            inflation: 0.0,
+        }
+    }
+
+    #[test]
+    fn compound_interest_and_tax() {
+        let mut plan = savings(1000.0, 5.0, 0.0, Frequency::Annual, 10);
+        let result = plan.simulate().unwrap();
+        assert!((result.gross_balance - 1628.89).abs() < 0.005);
+        assert_eq!(result.rows.len(), 10);
+
+        plan.tax_rate = 19.0;
+        plan.inflation = 2.0;
+        let result = plan.simulate().unwrap();
+        assert!((result.tax - 628.89 * 0.19).abs() < 0.005);
+        assert!((result.final_real - result.final_nominal / 1.02f64.powi(10)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn monthly_contributions() {
+        let result = savings(0.0, 6.0, 100.0, Frequency::Monthly, 10).simulate().unwrap();
+        assert!((result.gross_balance - 16387.93).abs() < 0.005);
+        assert_eq!(result.total_contributed, 12_000.0);
+    }
+
+    #[test]
+    fn rejects_invalid_plans() {
+        assert!(savings(1000.0, 5.0, 0.0, Frequency::Annual, 0).simulate().is_err());
+        assert!(savings(-1.0, 5.0, 0.0, Frequency::Annual, 1).simulate().is_err());
+        assert!(savings(1000.0, 5.0, 0.0, Frequency::Annual, 100).simulate().is_ok());
+        assert!(savings(1000.0, 5.0, 0.0, Frequency::Annual, u32::MAX).simulate().is_err());
+        assert!(savings(1000.0, f64::NAN, 0.0, Frequency::Annual, 1).simulate().is_err());
+
+        let mut plan = savings(1000.0, 5.0, 0.0, Frequency::Annual, 1);
+        plan.inflation = f64::INFINITY;
+        assert!(plan.simulate().is_err());
+    }
+
+    #[test]
+    fn expression_functions() {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        let eval = |input: &str| ctx.eval_str(input).unwrap().as_number().unwrap();
+        assert!((eval("compound(1000, 0.05, 10)") - 1628.89).abs() < 0.005);
+        assert!((eval("compound(1000, 0.12, 1, 12)") - 1126.83).abs() < 0.005);
+        assert!((eval("savings(0, 0.06, 10, 100)") - 16387.93).abs() < 0.005);
+        assert!((eval("realvalue(110, 0.1, 1)") - 100.0).abs() < 1e-9);
+    }
+}
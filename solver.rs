@@ -0,0 +1,396 @@
+// Numeric solution of equations in one unknown. Polynomials are solved
+// completely: between consecutive roots of the derivative there is at most
+// one root, so recursing on the derivative brackets all of them. Other
+// equations use Newton's method from a starting point or a scan of an interval.
+
+use std::fmt;
+
+use crate::analysis::{self, Settings};
+use crate::expr::{self, Context, Expr, Op, Scope, Value};
+
+/// Interval scanned for non-polynomial equations when nothing else is given.
+pub const DEFAULT_RANGE: (f64, f64) = (-100.0, 100.0);
+const TOLERANCE: f64 = 1e-13;
+const SUBDIVISIONS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Search {
+    /// All real roots of a polynomial, otherwise the roots in `DEFAULT_RANGE`.
+    Auto,
+    /// The root reached from a starting point.
+    Guess(f64),
+    /// All roots in [a, b].
+    Interval(f64, f64),
+}
+
+/// `lhs - rhs` of an equation; a plain expression is taken as `= 0`.
+pub fn residual(equation: &Expr) -> Expr {
+    match equation {
+        Expr::Equation(lhs, rhs) => Expr::Binary(Op::Sub, lhs.clone(), rhs.clone()),
+        _ => equation.clone(),
+    }
+}
+
+/// Whether the input is an equation `lhs = rhs` rather than an expression.
+pub fn is_equation(input: &str) -> bool {
+    matches!(expr::parse(input), Ok(Expr::Equation(..)))
+}
+
+/// The one variable of the equation that has no value.
+pub fn unknown<'e>(equation: &'e Expr, scope: &Scope) -> Result<&'e str, String> {
+    let free: Vec<&str> = equation.variables().into_iter().filter(|v| !scope.is_defined(v)).collect();
+    match free.as_slice() {
+        [name] => Ok(name),
+        [] => Err("Równanie nie zawiera niewiadomej".to_string()),
+        _ => Err(format!("Za dużo niewiadomych: {}", free.join(", "))),
+    }
+}
+
+fn horner(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+fn add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0.0) + b.get(i).unwrap_or(&0.0))
+        .collect()
+}
+
+fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// Coefficients, lowest power first, if `expr` is a polynomial in `var`.
+pub fn polynomial(expr: &Expr, var: &str, scope: &Scope) -> Option<Vec<f64>> {
+    if !expr.variables().contains(&var) {
+        return scope.eval(expr).ok()?.as_number().ok().map(|c| vec![c]);
+    }
+
+    let mut coefficients = match expr {
+        Expr::Var(_) => vec![0.0, 1.0],
+        Expr::Neg(inner) => polynomial(inner, var, scope)?.into_iter().map(|c| -c).collect(),
+        Expr::Binary(op, lhs, rhs) => {
+            let a = polynomial(lhs, var, scope)?;
+            match op {
+                Op::Add => add(&a, &polynomial(rhs, var, scope)?),
+                Op::Sub => add(&a, &polynomial(rhs, var, scope)?.into_iter().map(|c| -c).collect::<Vec<_>>()),
+                Op::Mul => multiply(&a, &polynomial(rhs, var, scope)?),
+                Op::Div => match polynomial(rhs, var, scope)?.as_slice() {
+                    [divisor] if *divisor != 0.0 => a.into_iter().map(|c| c / divisor).collect(),
+                    _ => return None,
+                },
+                Op::Pow => match polynomial(rhs, var, scope)?.as_slice() {
+                    [n] if n.fract() == 0.0 && (0.0..=64.0).contains(n) => {
+                        (0..*n as usize).fold(vec![1.0], |power, _| multiply(&power, &a))
+                    }
+                    _ => return None,
+                },
+            }
+        }
+        _ => return None,
+    };
+
+    while coefficients.len() > 1 && coefficients.last() == Some(&0.0) {
+        coefficients.pop();
+    }
+    Some(coefficients)
+}
+
+/// All real roots of a polynomial with a non-zero leading coefficient.
+pub fn polynomial_roots(coefficients: &[f64]) -> Vec<f64> {
+    let degree = coefficients.len().saturating_sub(1);
+    if degree == 0 {
+        return Vec::new();
+    }
+    if degree == 1 {
+        return vec![-coefficients[0] / coefficients[1]];
+    }
+
+    // Cauchy's bound: every root satisfies |x| < 1 + max |cᵢ / cₙ|.
+    let leading = coefficients[degree];
+    let bound = 1.0 + coefficients[..degree].iter().map(|c| (c / leading).abs()).fold(0.0, f64::max);
+
+    let derivative: Vec<f64> = coefficients.iter().enumerate().skip(1).map(|(i, c)| i as f64 * c).collect();
+    let critical = polynomial_roots(&derivative);
+    let mut points = vec![-bound];
+    points.extend(critical.iter().copied().filter(|c| c.abs() < bound));
+    points.push(bound);
+
+    let p = |x: f64| horner(coefficients, x);
+    // What counts as zero at x, given the size of the terms being summed.
+    let negligible = |x: f64| {
+        let scale = coefficients.iter().rev().fold(0.0, |acc, c| acc * x.abs() + c.abs());
+        1e-12 * scale
+    };
+
+    let mut roots = Vec::new();
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if p(a) * p(b) < 0.0 {
+            if let Some(root) = analysis::find_root(&mut |x| p(x), a, b, TOLERANCE * (1.0 + a.abs().max(b.abs()))) {
+                roots.push(root);
+            }
+        }
+    }
+    // A root of even multiplicity touches the axis at a critical point.
+    for &c in &critical {
+        if p(c).abs() <= negligible(c) {
+            roots.push(c);
+        }
+    }
+
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|next, kept| (*next - *kept).abs() <= 1e-9 * (1.0 + kept.abs()));
+    roots
+}
+
+/// Newton's method with a central-difference derivative.
+fn newton(f: &mut dyn FnMut(f64) -> f64, mut x: f64) -> Option<f64> {
+    for _ in 0..100 {
+        let h = 1e-7 * (1.0 + x.abs());
+        let slope = (f(x + h) - f(x - h)) / (2.0 * h);
+        let step = f(x) / slope;
+        if !step.is_finite() {
+            return None;
+        }
+        x -= step;
+        if step.abs() <= TOLERANCE * (1.0 + x.abs()) {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Widens a window around `x` until it contains a sign change, then brackets it.
+fn nearest_bracketed_root(f: &mut dyn FnMut(f64) -> f64, x: f64) -> Option<f64> {
+    let mut width = 0.1 * (1.0 + x.abs());
+    for _ in 0..40 {
+        let settings = Settings { tolerance: TOLERANCE, subdivisions: 100 };
+        let roots = analysis::roots(f, x - width, x + width, &settings);
+        if let Some(root) = roots.into_iter().min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs())) {
+            return Some(root);
+        }
+        width *= 2.0;
+    }
+    None
+}
+
+/// Rounds to 12 significant digits when that does not make the residual worse,
+/// so that `x^2 = 4` gives 2 rather than 1.9999999999999998.
+fn tidy(f: &mut dyn FnMut(f64) -> f64, x: f64) -> f64 {
+    let rounded: f64 = format!("{:.11e}", x).parse().unwrap_or(x);
+    if f(rounded).abs() <= f(x).abs() {
+        rounded
+    } else {
+        x
+    }
+}
+
+/// Roots of an equation in `var`, in increasing order.
+pub fn solve(scope: &Scope, equation: &Expr, var: &str, search: Search) -> Result<Vec<f64>, String> {
+    let residual = residual(equation);
+
+    if let Some(coefficients) = polynomial(&residual, var, scope) {
+        if coefficients.len() == 1 {
+            return Err(if coefficients[0] == 0.0 {
+                format!("Równanie jest spełnione dla każdego {}", var)
+            } else {
+                "Równanie jest sprzeczne".to_string()
+            });
+        }
+        let roots = polynomial_roots(&coefficients);
+        let mut p = |x: f64| horner(&coefficients, x);
+        let roots = roots.into_iter().map(|x| tidy(&mut p, x));
+        return Ok(match search {
+            Search::Auto => roots.collect(),
+            Search::Guess(x0) => roots.min_by(|a, b| (a - x0).abs().total_cmp(&(b - x0).abs())).into_iter().collect(),
+            Search::Interval(a, b) => roots.filter(|x| (a..=b).contains(x)).collect(),
+        });
+    }
+
+    let mut function = scope.function(&residual, var);
+    let mut error = None;
+    let mut f = |x: f64| {
+        function(x).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            f64::NAN
+        })
+    };
+
+    let settings = Settings { tolerance: TOLERANCE, subdivisions: SUBDIVISIONS };
+    let roots = match search {
+        Search::Auto => analysis::roots(&mut f, DEFAULT_RANGE.0, DEFAULT_RANGE.1, &settings),
+        Search::Interval(a, b) => {
+            if a >= b {
+                return Err("Początek przedziału musi być mniejszy od końca".to_string());
+            }
+            analysis::roots(&mut f, a, b, &settings)
+        }
+        Search::Guess(x0) => newton(&mut f, x0)
+            .filter(|x| f(*x).abs() <= 1e-9)
+            .or_else(|| nearest_bracketed_root(&mut f, x0))
+            .into_iter()
+            .collect(),
+    };
+    let roots: Vec<f64> = roots.into_iter().map(|x| tidy(&mut f, x)).collect();
+
+    match error {
+        Some(error) if roots.is_empty() => Err(error),
+        _ => Ok(roots),
+    }
+}
+
+/// Solutions of an equation typed by the user.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub variable: String,
+    /// Each root with the difference between the sides at that root.
+    pub roots: Vec<(f64, f64)>,
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.roots.as_slice() {
+            [(x, _)] => write!(f, "{} = {}", self.variable, x),
+            roots => {
+                let values: Vec<String> = roots.iter().map(|(x, _)| x.to_string()).collect();
+                write!(f, "{} = [{}]", self.variable, values.join(", "))
+            }
+        }
+    }
+}
+
+/// Parses and solves `input`; `variable` may be left empty to pick the unknown.
+pub fn solve_equation(ctx: &Context, input: &str, variable: &str, search: Search) -> Result<Solution, String> {
+    let equation = expr::parse(input)?;
+    let scope = Scope::new(ctx);
+    let variable = match variable.trim() {
+        "" => unknown(&equation, &scope)?.to_string(),
+        name => name.to_string(),
+    };
+
+    let roots = solve(&scope, &equation, &variable, search)?;
+    if roots.is_empty() {
+        return Err("Brak rozwiązań rzeczywistych".to_string());
+    }
+
+    let residual = residual(&equation);
+    let mut f = scope.function(&residual, &variable);
+    let roots = roots.into_iter().map(|x| (x, f(x).unwrap_or(f64::NAN))).collect();
+    Ok(Solution { variable, roots })
+}
+
+/// Adds `solve(equation, x)`, `solve(.., x, start)` and `solve(.., x, a, b)`.
+/// One root is returned as a number, several as a list.
+pub fn register(ctx: &mut Context) {
+    ctx.form("solve", |scope, args| {
+        let (equation, rest) = args.split_first().ok_or("Użycie: solve(równanie, x)")?;
+        let variable = match rest.first() {
+            Some(var) => var.as_variable()?,
+            None => unknown(equation, scope)?,
+        };
+        let number = |arg: &Expr| scope.eval(arg)?.as_number();
+        let search = match rest {
+            [] | [_] => Search::Auto,
+            [_, start] => Search::Guess(number(start)?),
+            [_, a, b] => Search::Interval(number(a)?, number(b)?),
+            _ => return Err("Użycie: solve(równanie, x, start) lub solve(równanie, x, a, b)".to_string()),
+        };
+
+        let roots = solve(scope, equation, variable, search)?;
+        match roots.as_slice() {
+            [] => Err("Brak rozwiązań rzeczywistych".to_string()),
+            [root] => Ok(Value::Num(*root)),
+            _ => Ok(Value::List(roots.into_iter().map(Value::Num).collect())),
+        }
+    });
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SolverField {
+    Equation,
+    Variable,
+    From,
+    To,
+    Start,
+}
+
+#[derive(Default)]
+pub struct SolverForm {
+    pub equation: String,
+    pub variable: String,
+    pub from: String,
+    pub to: String,
+    pub start: String,
+    pub result: Option<Result<Solution, String>>,
+}
+
+impl SolverForm {
+    pub fn field_mut(&mut self, field: SolverField) -> &mut String {
+        match field {
+            SolverField::Equation => &mut self.equation,
+            SolverField::Variable => &mut self.variable,
+            SolverField::From => &mut self.from,
+            SolverField::To => &mut self.to,
+            SolverField::Start => &mut self.start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<Value, String> {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        ctx.eval_str(input)
+    }
+
+    fn close(actual: &[f64], expected: &[f64]) -> bool {
+        actual.len() == expected.len() && actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn polynomial_roots_are_complete() {
+        assert!(close(&polynomial_roots(&[-6.0, 11.0, -6.0, 1.0]), &[1.0, 2.0, 3.0]));
+        assert!(close(&polynomial_roots(&[1.0, -2.0, 1.0]), &[1.0]));
+        assert!(close(&polynomial_roots(&[0.0, 0.0, 0.0, 1.0]), &[0.0]));
+        assert!(polynomial_roots(&[1.0, 0.0, 1.0]).is_empty());
+    }
+
+    #[test]
+    fn solve_function() {
+        let list = |values: &[f64]| Value::List(values.iter().map(|v| Value::Num(*v)).collect());
+        assert_eq!(eval("solve(x^2 = 4, x)"), Ok(list(&[-2.0, 2.0])));
+        assert_eq!(eval("solve(2y + 1 = 0)"), Ok(Value::Num(-0.5)));
+        assert_eq!(eval("solve(x^2 = 4, x, 1)"), Ok(Value::Num(2.0)));
+
+        let dottie = eval("solve(cos(x) = x, x, 0)").unwrap().as_number().unwrap();
+        assert!((dottie - 0.739_085_133_215_160_6).abs() < 1e-12);
+        let pi = eval("solve(sin(x), x, 3, 4)").unwrap().as_number().unwrap();
+        assert!((pi - std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn unsolvable_equations() {
+        assert!(eval("solve(x = x + 1, x)").is_err());
+        assert!(eval("solve(x = x, x)").is_err());
+        assert!(eval("solve(x^2 = -1, x)").is_err());
+        assert!(eval("solve(x + y = 1)").is_err());
+    }
+
+    #[test]
+    fn solution_display() {
+        let solution = solve_equation(&Context::new(), "x^2 - 5x + 6 = 0", "", Search::Auto).unwrap();
+        assert_eq!(solution.to_string(), "x = [2, 3]");
+        assert!(solution.roots.iter().all(|(_, residual)| residual.abs() < 1e-12));
+        assert!(is_equation("a = 1") && !is_equation("a + 1"));
+    }
+}
@@ -0,0 +1,280 @@
+// Descriptive statistics over lists of values.
+
+use crate::expr::{Context, Value};
+
+pub fn sum(data: &[f64]) -> f64 {
+    data.iter().sum()
+}
+
+pub fn mean(data: &[f64]) -> f64 {
+    sum(data) / data.len() as f64
+}
+
+fn sorted(data: &[f64]) -> Vec<f64> {
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Quantile `p` in [0, 1] with linear interpolation between order statistics
+/// (the "inclusive" method used by spreadsheets' QUARTILE.INC).
+pub fn quantile(data: &[f64], p: f64) -> f64 {
+    let sorted = sorted(data);
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let position = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+pub fn median(data: &[f64]) -> f64 {
+    quantile(data, 0.5)
+}
+
+/// All values sharing the highest frequency; empty when every value is unique.
+pub fn modes(data: &[f64]) -> Vec<f64> {
+    let sorted = sorted(data);
+    let mut runs: Vec<(f64, usize)> = Vec::new();
+    for value in sorted {
+        match runs.last_mut() {
+            Some((last, count)) if *last == value => *count += 1,
+            _ => runs.push((value, 1)),
+        }
+    }
+
+    let best = runs.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    if best < 2 {
+        return Vec::new();
+    }
+    runs.into_iter().filter(|(_, count)| *count == best).map(|(value, _)| value).collect()
+}
+
+/// Variance; `sample` divides by n − 1 instead of n.
+pub fn variance(data: &[f64], sample: bool) -> f64 {
+    let m = mean(data);
+    let squares: f64 = data.iter().map(|x| (x - m).powi(2)).sum();
+    let n = data.len() as f64;
+    squares / if sample { n - 1.0 } else { n }
+}
+
+pub fn stdev(data: &[f64], sample: bool) -> f64 {
+    variance(data, sample).sqrt()
+}
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub count: usize,
+    pub sum: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub modes: Vec<f64>,
+    pub min: f64,
+    pub max: f64,
+    pub range: f64,
+    /// None for a single value, which has no sample variance.
+    pub sample_variance: Option<f64>,
+    pub population_variance: f64,
+    pub sample_stdev: Option<f64>,
+    pub population_stdev: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub iqr: f64,
+}
+
+pub fn summarize(data: &[f64]) -> Result<Summary, String> {
+    if data.is_empty() {
+        return Err("Brak danych".to_string());
+    }
+
+    let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let q1 = quantile(data, 0.25);
+    let q3 = quantile(data, 0.75);
+    let sample = data.len() >= 2;
+
+    Ok(Summary {
+        count: data.len(),
+        sum: sum(data),
+        mean: mean(data),
+        median: median(data),
+        modes: modes(data),
+        min,
+        max,
+        range: max - min,
+        sample_variance: sample.then(|| variance(data, true)),
+        population_variance: variance(data, false),
+        sample_stdev: sample.then(|| stdev(data, true)),
+        population_stdev: stdev(data, false),
+        q1,
+        q3,
+        iqr: q3 - q1,
+    })
+}
+
+/// Parses pasted data. Values may be separated by whitespace, `;` or `,`;
+/// once a `;`, tab or newline is present, commas are read as decimal commas.
+pub fn parse_data(input: &str) -> Result<Vec<f64>, String> {
+    let decimal_comma = input.contains([';', '\t', '\n']);
+
+    let separators: &[char] = if decimal_comma {
+        &[';', '\t', '\n', '\r', ' ']
+    } else {
+        &[',', ';', '\t', '\n', '\r', ' ']
+    };
+
+    input
+        .split(separators)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let normalized = if decimal_comma { item.replace(',', ".") } else { item.to_string() };
+            normalized
+                .parse::<f64>()
+                .map_err(|_| format!("Niepoprawna wartość: {}", item))
+        })
+        .collect()
+}
+
+/// Registers the statistics functions; each takes one list: `mean([1, 2, 3])`.
+pub fn register(ctx: &mut Context) {
+    // `min_len` is 2 for the sample statistics, which divide by n − 1.
+    fn list_fn(ctx: &mut Context, name: &'static str, min_len: usize, f: fn(&[f64]) -> f64) {
+        ctx.func(name, move |args| match args {
+            [list] => {
+                let data = list.as_numbers()?;
+                if data.is_empty() {
+                    return Err(format!("{}: pusta lista", name));
+                }
+                if data.len() < min_len {
+                    return Err(format!("{}: potrzeba co najmniej {} wartości", name, min_len));
+                }
+                Ok(Value::Num(f(&data)))
+            }
+            _ => Err(format!("Funkcja {} przyjmuje jedną listę", name)),
+        });
+    }
+
+    list_fn(ctx, "count", 1, |d| d.len() as f64);
+    list_fn(ctx, "sum", 1, sum);
+    list_fn(ctx, "mean", 1, mean);
+    list_fn(ctx, "median", 1, median);
+    list_fn(ctx, "range", 1, |d| {
+        let sorted = sorted(d);
+        sorted[sorted.len() - 1] - sorted[0]
+    });
+    list_fn(ctx, "var", 2, |d| variance(d, true));
+    list_fn(ctx, "varp", 1, |d| variance(d, false));
+    list_fn(ctx, "stdev", 2, |d| stdev(d, true));
+    list_fn(ctx, "stdevp", 1, |d| stdev(d, false));
+    list_fn(ctx, "q1", 1, |d| quantile(d, 0.25));
+    list_fn(ctx, "q3", 1, |d| quantile(d, 0.75));
+    list_fn(ctx, "iqr", 1, |d| quantile(d, 0.75) - quantile(d, 0.25));
+
+    // A single mode is a number, several modes a list.
+    ctx.func("mode", |args| match args {
+        [list] => {
+            let modes = modes(&list.as_numbers()?);
+            match modes.as_slice() {
+                [] => Err("Brak dominanty – wszystkie wartości są różne".to_string()),
+                [mode] => Ok(Value::Num(*mode)),
+                _ => Ok(Value::List(modes.into_iter().map(Value::Num).collect())),
+            }
+        }
+        _ => Err("Funkcja mode przyjmuje jedną listę".to_string()),
+    });
+    ctx.func("quartile", |args| match args {
+        [list, k] => {
+            let data = list.as_numbers()?;
+            Ok(Value::Num(quantile(&data, k.as_number()? / 4.0)))
+        }
+        _ => Err("Użycie: quartile([..], k)".to_string()),
+    });
+}
+
+#[derive(Default)]
+pub struct StatsForm {
+    pub input: String,
+    pub data: Vec<f64>,
+    pub summary: Option<Result<Summary, String>>,
+}
+
+impl StatsForm {
+    /// Stores the raw input and recomputes the summary.
+    pub fn set_input(&mut self, input: String) {
+        self.data.clear();
+        self.summary = if input.trim().is_empty() {
+            None
+        } else {
+            Some(parse_data(&input).and_then(|data| {
+                let summary = summarize(&data)?;
+                self.data = data;
+                Ok(summary)
+            }))
+        };
+        self.input = input;
+    }
+
+    /// The data as a list literal for the evaluator, e.g. `[1, 2.5, 3]`.
+    pub fn list_literal(&self) -> String {
+        let items: Vec<String> = self.data.iter().map(|x| x.to_string()).collect();
+        format!("[{}]", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: [f64; 8] = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+    #[test]
+    fn summary_of_a_textbook_sample() {
+        let summary = summarize(&DATA).unwrap();
+        assert_eq!(summary.mean, 5.0);
+        assert_eq!(summary.median, 4.5);
+        assert_eq!(summary.modes, [4.0]);
+        assert_eq!(summary.population_stdev, 2.0);
+        assert!((summary.sample_variance.unwrap() - 32.0 / 7.0).abs() < 1e-12);
+        assert_eq!((summary.q1, summary.q3, summary.range), (4.0, 5.5, 7.0));
+        assert!(summarize(&[]).is_err());
+    }
+
+    #[test]
+    fn single_value_has_no_sample_variance() {
+        let summary = summarize(&[3.0]).unwrap();
+        assert_eq!((summary.sample_variance, summary.sample_stdev), (None, None));
+        assert_eq!((summary.population_variance, summary.mean), (0.0, 3.0));
+    }
+
+    #[test]
+    fn modes_and_quantiles() {
+        assert_eq!(modes(&[1.0, 2.0, 3.0]), Vec::<f64>::new());
+        assert_eq!(modes(&[3.0, 1.0, 3.0, 1.0]), [1.0, 3.0]);
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+        assert!(quantile(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn pasted_data() {
+        assert_eq!(parse_data("1, 2.5 3"), Ok(vec![1.0, 2.5, 3.0]));
+        assert_eq!(parse_data("1,5; 2\n3"), Ok(vec![1.5, 2.0, 3.0]));
+        assert!(parse_data("1, x").is_err());
+    }
+
+    #[test]
+    fn list_functions() {
+        let mut ctx = Context::new();
+        register(&mut ctx);
+        let eval = |input: &str| ctx.eval_str(input);
+        assert_eq!(eval("stdevp([2, 4, 4, 4, 5, 5, 7, 9])"), Ok(Value::Num(2.0)));
+        assert_eq!(eval("mode([1, 2, 2])"), Ok(Value::Num(2.0)));
+        assert_eq!(eval("quartile([1, 2, 3, 4, 5], 3)"), Ok(Value::Num(4.0)));
+        assert_eq!(eval("varp([5])"), Ok(Value::Num(0.0)));
+        assert!(eval("var([5])").is_err());
+        assert!(eval("stdev([5])").is_err());
+        assert!(eval("mean([])").is_err());
+    }
+}
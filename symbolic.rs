@@ -0,0 +1,1079 @@
+// Symbolic algebra over the expression AST: simplification, expansion,
+// factoring and derivatives, with results shown as expressions that the
+// display can evaluate again. Coefficients are exact fractions and every name
+// is a symbol, so `pi` stays `pi` and `a x^2 + b` is a polynomial in x with
+// parameters a and b. Trigonometric functions are taken in radians.
+//
+// Expressions are kept in a normal form as they are built: sums and products
+// are flattened, like terms and equal bases are combined and numbers are
+// folded into a single coefficient.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::expr::{self, Expr, Op};
+use crate::rational::{gcd, Rational};
+
+/// Expansion stops beyond this many terms, e.g. `(a + b + c)^100`.
+const MAX_TERMS: usize = 5000;
+const MAX_ORDER: usize = 20;
+/// Rational roots are only searched for when the divisors are cheap to list.
+const MAX_DIVISOR_SEARCH: i128 = 1_000_000_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sym {
+    Num(Rational),
+    Var(String),
+    /// At least two terms in display order; the number, if any, comes last.
+    Add(Vec<Sym>),
+    /// At least two factors; the coefficient, if any, comes first and is not 1.
+    Mul(Vec<Sym>),
+    Pow(Box<Sym>, Box<Sym>),
+    Call(String, Vec<Sym>),
+}
+
+fn overflow() -> String {
+    "Za duże liczby w obliczeniach symbolicznych".to_string()
+}
+
+fn exact(value: Option<Rational>) -> Result<Rational, String> {
+    value.ok_or_else(overflow)
+}
+
+fn number(n: i128) -> Sym {
+    Sym::Num(Rational::integer(n))
+}
+
+impl Sym {
+    pub fn depends_on(&self, var: &str) -> bool {
+        match self {
+            Sym::Num(_) => false,
+            Sym::Var(name) => name == var,
+            Sym::Add(items) | Sym::Mul(items) | Sym::Call(_, items) => items.iter().any(|item| item.depends_on(var)),
+            Sym::Pow(base, exponent) => base.depends_on(var) || exponent.depends_on(var),
+        }
+    }
+
+    /// The first name that is not a constant, as the default unknown.
+    fn first_variable(&self) -> Option<&str> {
+        match self {
+            Sym::Num(_) => None,
+            Sym::Var(name) => (!matches!(name.as_str(), "pi" | "π" | "e")).then_some(name.as_str()),
+            Sym::Add(items) | Sym::Mul(items) | Sym::Call(_, items) => items.iter().find_map(Sym::first_variable),
+            Sym::Pow(base, exponent) => base.first_variable().or_else(|| exponent.first_variable()),
+        }
+    }
+}
+
+pub fn from_expr(expr: &Expr) -> Result<Sym, String> {
+    match expr {
+        Expr::Num(n) => Rational::parse(&n.to_string())
+            .map(Sym::Num)
+            .ok_or_else(|| format!("Liczba {} jest za duża do obliczeń symbolicznych", n)),
+        Expr::Var(name) => Ok(Sym::Var(name.clone())),
+        Expr::List(_) => Err("Obliczenia symboliczne nie obejmują list".to_string()),
+        Expr::Neg(inner) => mul(vec![number(-1), from_expr(inner)?]),
+        Expr::Percent(inner) => mul(vec![from_expr(inner)?, Sym::Num(exact(Rational::new(1, 100))?)]),
+        // The same business percentages as the evaluator: a + 10% = a (1 + 10/100).
+        Expr::Binary(op @ (Op::Add | Op::Sub), lhs, rhs) if matches!(**rhs, Expr::Percent(_)) => {
+            let share = from_expr(rhs)?;
+            let share = if *op == Op::Add { share } else { mul(vec![number(-1), share])? };
+            mul(vec![from_expr(lhs)?, add(vec![number(1), share])?])
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let (a, b) = (from_expr(lhs)?, from_expr(rhs)?);
+            match op {
+                Op::Add => add(vec![a, b]),
+                Op::Sub => add(vec![a, mul(vec![number(-1), b])?]),
+                Op::Mul => mul(vec![a, b]),
+                Op::Div => mul(vec![a, pow(b, number(-1))?]),
+                Op::Pow => pow(a, b),
+            }
+        }
+        Expr::Call(name, args) if name == "sqrt" && args.len() == 1 => {
+            pow(from_expr(&args[0])?, Sym::Num(exact(Rational::new(1, 2))?))
+        }
+        Expr::Call(name, args) => call(name, args.iter().map(from_expr).collect::<Result<_, _>>()?),
+        Expr::Equation(..) => Err("Równanie można tylko rozwiązać: solve(.., x)".to_string()),
+    }
+}
+
+/// The exact value of an expression made of fractions alone, such as
+/// `1/3 + 1/6`; None when it involves anything else.
+pub fn exact_value(input: &str) -> Option<Rational> {
+    match from_expr(&expr::parse(input).ok()?).ok()? {
+        Sym::Num(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// A total order used to recognise equal expressions; sums and products
+/// compare as multisets, whatever their display order.
+fn compare(a: &Sym, b: &Sym) -> Ordering {
+    fn rank(sym: &Sym) -> u8 {
+        match sym {
+            Sym::Num(_) => 0,
+            Sym::Var(_) => 1,
+            Sym::Call(..) => 2,
+            Sym::Pow(..) => 3,
+            Sym::Mul(_) => 4,
+            Sym::Add(_) => 5,
+        }
+    }
+
+    fn sorted(items: &[Sym]) -> Vec<&Sym> {
+        let mut items: Vec<&Sym> = items.iter().collect();
+        items.sort_by(|a, b| compare(a, b));
+        items
+    }
+
+    fn lists(a: &[&Sym], b: &[&Sym]) -> Ordering {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| compare(x, y))
+            .find(|order| order.is_ne())
+            .unwrap_or(a.len().cmp(&b.len()))
+    }
+
+    match (a, b) {
+        (Sym::Num(x), Sym::Num(y)) => x
+            .to_f64()
+            .total_cmp(&y.to_f64())
+            .then((x.numer(), x.denom()).cmp(&(y.numer(), y.denom()))),
+        (Sym::Var(x), Sym::Var(y)) => x.cmp(y),
+        (Sym::Call(f, xs), Sym::Call(g, ys)) => f
+            .cmp(g)
+            .then_with(|| lists(&xs.iter().collect::<Vec<_>>(), &ys.iter().collect::<Vec<_>>())),
+        (Sym::Pow(b1, e1), Sym::Pow(b2, e2)) => compare(b1, b2).then_with(|| compare(e1, e2)),
+        (Sym::Mul(xs), Sym::Mul(ys)) | (Sym::Add(xs), Sym::Add(ys)) => lists(&sorted(xs), &sorted(ys)),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn same(a: &Sym, b: &Sym) -> bool {
+    compare(a, b).is_eq()
+}
+
+/// The numeric coefficient of a term and the rest of it.
+fn split_coefficient(term: Sym) -> (Rational, Sym) {
+    match term {
+        Sym::Num(n) => (n, number(1)),
+        Sym::Mul(mut factors) => match factors.first() {
+            Some(Sym::Num(c)) => {
+                let c = *c;
+                factors.remove(0);
+                let rest = if factors.len() == 1 { factors.remove(0) } else { Sym::Mul(factors) };
+                (c, rest)
+            }
+            _ => (Rational::ONE, Sym::Mul(factors)),
+        },
+        other => (Rational::ONE, other),
+    }
+}
+
+fn with_coefficient(coefficient: Rational, rest: Sym) -> Sym {
+    if coefficient == Rational::ONE {
+        return rest;
+    }
+    match rest {
+        Sym::Num(n) if n == Rational::ONE => Sym::Num(coefficient),
+        Sym::Mul(mut factors) => {
+            factors.insert(0, Sym::Num(coefficient));
+            Sym::Mul(factors)
+        }
+        other => Sym::Mul(vec![Sym::Num(coefficient), other]),
+    }
+}
+
+/// Exponents of the variables in a term, as in `3 x^2 y` → x: 2, y: 1.
+fn exponents(term: &Sym) -> Vec<(&str, f64)> {
+    let factors = match term {
+        Sym::Mul(factors) => factors.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    factors
+        .iter()
+        .filter_map(|factor| match factor {
+            Sym::Var(name) => Some((name.as_str(), 1.0)),
+            Sym::Pow(base, exponent) => match (&**base, &**exponent) {
+                (Sym::Var(name), Sym::Num(e)) => Some((name.as_str(), e.to_f64())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Display order of terms: highest total degree first, then by variable.
+fn term_order(a: &Sym, b: &Sym) -> Ordering {
+    let (ea, eb) = (exponents(a), exponents(b));
+    let degree = |e: &[(&str, f64)]| e.iter().map(|(_, d)| d).sum::<f64>();
+    let exponent = |e: &[(&str, f64)], name: &str| e.iter().find(|(n, _)| *n == name).map_or(0.0, |(_, d)| *d);
+
+    let mut names: Vec<&str> = ea.iter().chain(&eb).map(|(name, _)| *name).collect();
+    names.sort();
+    names.dedup();
+
+    degree(&eb)
+        .total_cmp(&degree(&ea))
+        .then_with(|| {
+            names
+                .iter()
+                .map(|name| exponent(&eb, name).total_cmp(&exponent(&ea, name)))
+                .find(|order| order.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+        .then_with(|| compare(a, b))
+}
+
+pub fn add(terms: Vec<Sym>) -> Result<Sym, String> {
+    fn collect(term: Sym, constant: &mut Rational, like: &mut Vec<(Rational, Sym)>) -> Result<(), String> {
+        match term {
+            Sym::Add(inner) => {
+                for term in inner {
+                    collect(term, constant, like)?;
+                }
+            }
+            Sym::Num(n) => *constant = exact(constant.checked_add(&n))?,
+            other => {
+                let (c, rest) = split_coefficient(other);
+                match like.iter_mut().find(|(_, existing)| same(existing, &rest)) {
+                    Some(entry) => entry.0 = exact(entry.0.checked_add(&c))?,
+                    None => like.push((c, rest)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut constant = Rational::ZERO;
+    let mut like = Vec::new();
+    for term in terms {
+        collect(term, &mut constant, &mut like)?;
+    }
+
+    let mut terms: Vec<Sym> = like
+        .into_iter()
+        .filter(|(c, _)| !c.is_zero())
+        .map(|(c, rest)| with_coefficient(c, rest))
+        .collect();
+    terms.sort_by(term_order);
+    if !constant.is_zero() || terms.is_empty() {
+        terms.push(Sym::Num(constant));
+    }
+    Ok(if terms.len() == 1 { terms.remove(0) } else { Sym::Add(terms) })
+}
+
+pub fn mul(factors: Vec<Sym>) -> Result<Sym, String> {
+    fn collect(factor: Sym, coefficient: &mut Rational, powers: &mut Vec<(Sym, Sym)>) -> Result<(), String> {
+        match factor {
+            Sym::Mul(inner) => {
+                for factor in inner {
+                    collect(factor, coefficient, powers)?;
+                }
+            }
+            Sym::Num(n) => *coefficient = exact(coefficient.checked_mul(&n))?,
+            Sym::Pow(base, exponent) => match powers.iter_mut().find(|(existing, _)| same(existing, &base)) {
+                Some(entry) => entry.1 = add(vec![entry.1.clone(), *exponent])?,
+                None => powers.push((*base, *exponent)),
+            },
+            other => match powers.iter_mut().find(|(existing, _)| same(existing, &other)) {
+                Some(entry) => entry.1 = add(vec![entry.1.clone(), number(1)])?,
+                None => powers.push((other, number(1))),
+            },
+        }
+        Ok(())
+    }
+
+    let mut coefficient = Rational::ONE;
+    let mut powers = Vec::new();
+    for factor in factors {
+        collect(factor, &mut coefficient, &mut powers)?;
+    }
+    if coefficient.is_zero() {
+        return Ok(number(0));
+    }
+
+    let mut factors = Vec::new();
+    for (base, exponent) in powers {
+        match pow(base, exponent)? {
+            Sym::Num(n) => coefficient = exact(coefficient.checked_mul(&n))?,
+            Sym::Mul(inner) => {
+                for factor in inner {
+                    match factor {
+                        Sym::Num(n) => coefficient = exact(coefficient.checked_mul(&n))?,
+                        other => factors.push(other),
+                    }
+                }
+            }
+            other => factors.push(other),
+        }
+    }
+    if coefficient.is_zero() {
+        return Ok(number(0));
+    }
+
+    // Powers of variables come first, in alphabetical order; functions and
+    // sums keep the order in which they were written.
+    let variable = |factor: &Sym| match factor {
+        Sym::Var(name) => Some(name.clone()),
+        Sym::Pow(base, _) => match &**base {
+            Sym::Var(name) => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    factors.sort_by(|a, b| match (variable(a), variable(b)) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    Ok(match factors.len() {
+        0 => Sym::Num(coefficient),
+        1 if coefficient == Rational::ONE => factors.remove(0),
+        _ => with_coefficient(coefficient, Sym::Mul(factors)),
+    })
+}
+
+/// The integer `q`-th root of `n`, if there is one.
+fn exact_root(n: i128, q: u32) -> Option<i128> {
+    if q == 1 {
+        return Some(n);
+    }
+    if n < 0 {
+        return if q % 2 == 1 { exact_root(-n, q).map(|r| -r) } else { None };
+    }
+    let guess = (n as f64).powf(1.0 / q as f64).round() as i128;
+    (guess.saturating_sub(1)..=guess.saturating_add(1)).find(|r| *r >= 0 && r.checked_pow(q) == Some(n))
+}
+
+/// `base^exponent` for fractions when the result is a fraction again.
+fn numeric_power(base: Rational, exponent: Rational) -> Result<Option<Rational>, String> {
+    if base.is_zero() && exponent.is_negative() {
+        return Err("Dzielenie przez zero".to_string());
+    }
+    let (Ok(p), Ok(q)) = (i32::try_from(exponent.numer()), u32::try_from(exponent.denom())) else {
+        return Ok(None);
+    };
+    if p.abs() > 1000 {
+        return Ok(None);
+    }
+    let root = match (exact_root(base.numer(), q), exact_root(base.denom(), q)) {
+        (Some(num), Some(den)) => Rational::new(num, den),
+        _ => None,
+    };
+    Ok(root.and_then(|root| root.checked_pow(p)))
+}
+
+pub fn pow(base: Sym, exponent: Sym) -> Result<Sym, String> {
+    match (base, exponent) {
+        (_, Sym::Num(e)) if e.is_zero() => Ok(number(1)),
+        (base, Sym::Num(e)) if e == Rational::ONE => Ok(base),
+        (Sym::Num(b), Sym::Num(e)) => {
+            Ok(numeric_power(b, e)?.map_or_else(|| Sym::Pow(Box::new(Sym::Num(b)), Box::new(Sym::Num(e))), Sym::Num))
+        }
+        (Sym::Num(b), _) if b == Rational::ONE => Ok(number(1)),
+        (Sym::Pow(base, inner), Sym::Num(e)) if e.is_integer() => pow(*base, mul(vec![*inner, Sym::Num(e)])?),
+        (Sym::Mul(factors), Sym::Num(e)) if e.is_integer() => {
+            mul(factors.into_iter().map(|f| pow(f, Sym::Num(e))).collect::<Result<_, _>>()?)
+        }
+        (base, exponent) => Ok(Sym::Pow(Box::new(base), Box::new(exponent))),
+    }
+}
+
+/// A function call, with the values that are known exactly worked out.
+pub fn call(name: &str, args: Vec<Sym>) -> Result<Sym, String> {
+    let is = |sym: &Sym, n: i128| matches!(sym, Sym::Num(x) if *x == Rational::integer(n));
+    Ok(match (name, args.as_slice()) {
+        ("sin" | "tan" | "sinh" | "tanh" | "asin" | "atan" | "asinh" | "atanh", [x]) if is(x, 0) => number(0),
+        ("cos" | "cosh" | "exp", [x]) if is(x, 0) => number(1),
+        ("ln" | "log", [x]) if is(x, 1) => number(0),
+        ("log", [x]) if is(x, 10) => number(1),
+        ("ln", [Sym::Var(name)]) if name == "e" => number(1),
+        ("abs", [Sym::Num(x)]) => Sym::Num(x.abs()),
+        ("exp", [Sym::Call(inner, u)]) if inner == "ln" && u.len() == 1 => u[0].clone(),
+        ("ln", [Sym::Call(inner, u)]) if inner == "exp" && u.len() == 1 => u[0].clone(),
+        _ => Sym::Call(name.to_string(), args),
+    })
+}
+
+fn negate(sym: Sym) -> Result<Sym, String> {
+    mul(vec![number(-1), sym])
+}
+
+pub fn derivative(sym: &Sym, var: &str) -> Result<Sym, String> {
+    if !sym.depends_on(var) {
+        return Ok(number(0));
+    }
+    match sym {
+        Sym::Num(_) => Ok(number(0)),
+        Sym::Var(_) => Ok(number(1)),
+        Sym::Add(terms) => add(terms.iter().map(|t| derivative(t, var)).collect::<Result<_, _>>()?),
+        Sym::Mul(factors) => {
+            let mut terms = Vec::new();
+            for (i, factor) in factors.iter().enumerate() {
+                if !factor.depends_on(var) {
+                    continue;
+                }
+                let mut product = factors.clone();
+                product[i] = derivative(factor, var)?;
+                terms.push(mul(product)?);
+            }
+            add(terms)
+        }
+        Sym::Pow(base, exponent) => {
+            let (base, exponent) = (&**base, &**exponent);
+            if !exponent.depends_on(var) {
+                // n u^(n-1) u'
+                let lowered = pow(base.clone(), add(vec![exponent.clone(), number(-1)])?)?;
+                mul(vec![exponent.clone(), lowered, derivative(base, var)?])
+            } else if !base.depends_on(var) {
+                // a^u ln(a) u'
+                mul(vec![sym.clone(), call("ln", vec![base.clone()])?, derivative(exponent, var)?])
+            } else {
+                // u^v (v' ln(u) + v u'/u)
+                let log = mul(vec![derivative(exponent, var)?, call("ln", vec![base.clone()])?])?;
+                let ratio = mul(vec![exponent.clone(), derivative(base, var)?, pow(base.clone(), number(-1))?])?;
+                mul(vec![sym.clone(), add(vec![log, ratio])?])
+            }
+        }
+        Sym::Call(name, args) => {
+            let [u] = args.as_slice() else {
+                return Err(format!("Nie umiem różniczkować funkcji {} wielu zmiennych", name));
+            };
+            let half = Sym::Num(exact(Rational::new(1, 2))?);
+            let square = pow(u.clone(), number(2))?;
+            let outer = match name.as_str() {
+                "sin" => call("cos", vec![u.clone()])?,
+                "cos" => negate(call("sin", vec![u.clone()])?)?,
+                "tan" => pow(call("cos", vec![u.clone()])?, number(-2))?,
+                "exp" => sym.clone(),
+                "ln" => pow(u.clone(), number(-1))?,
+                "log" => pow(mul(vec![u.clone(), call("ln", vec![number(10)])?])?, number(-1))?,
+                "abs" => mul(vec![u.clone(), pow(sym.clone(), number(-1))?])?,
+                "sinh" => call("cosh", vec![u.clone()])?,
+                "cosh" => call("sinh", vec![u.clone()])?,
+                "tanh" => pow(call("cosh", vec![u.clone()])?, number(-2))?,
+                "asin" => pow(add(vec![number(1), negate(square)?])?, negate(half)?)?,
+                "acos" => negate(pow(add(vec![number(1), negate(square)?])?, negate(half)?)?)?,
+                "atan" => pow(add(vec![square, number(1)])?, number(-1))?,
+                "asinh" => pow(add(vec![square, number(1)])?, negate(half)?)?,
+                "acosh" => pow(add(vec![square, number(-1)])?, negate(half)?)?,
+                "atanh" => pow(add(vec![number(1), negate(square)?])?, number(-1))?,
+                _ => return Err(format!("Nie umiem różniczkować funkcji {}", name)),
+            };
+            mul(vec![outer, derivative(u, var)?])
+        }
+    }
+}
+
+/// Multiplies out products and positive integer powers of sums.
+pub fn expand(sym: &Sym) -> Result<Sym, String> {
+    fn terms(sym: &Sym) -> &[Sym] {
+        match sym {
+            Sym::Add(terms) => terms,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    fn product(a: &Sym, b: &Sym) -> Result<Sym, String> {
+        let (a, b) = (terms(a), terms(b));
+        if a.len() * b.len() > MAX_TERMS {
+            return Err("Rozwinięcie ma za dużo wyrazów".to_string());
+        }
+        let mut out = Vec::with_capacity(a.len() * b.len());
+        for x in a {
+            for y in b {
+                out.push(mul(vec![x.clone(), y.clone()])?);
+            }
+        }
+        add(out)
+    }
+
+    match sym {
+        Sym::Add(terms) => add(terms.iter().map(expand).collect::<Result<_, _>>()?),
+        Sym::Mul(factors) => {
+            let mut result = number(1);
+            for factor in factors {
+                result = product(&result, &expand(factor)?)?;
+            }
+            Ok(result)
+        }
+        Sym::Pow(base, exponent) => {
+            let base = expand(base)?;
+            match **exponent {
+                Sym::Num(e) if e.is_integer() && e.numer() > 1 && matches!(base, Sym::Add(_)) => {
+                    // A power of k terms has C(n + k - 1, k - 1) terms; check
+                    // up front rather than after most of the work is done.
+                    let (n, k) = (e.numer() as u128, terms(&base).len() as u128);
+                    let mut count = 1u128;
+                    for i in 1..k {
+                        count = count.saturating_mul(n + i) / i;
+                        if count > MAX_TERMS as u128 {
+                            return Err("Rozwinięcie ma za dużo wyrazów".to_string());
+                        }
+                    }
+                    let mut result = base.clone();
+                    for _ in 1..e.numer() {
+                        result = product(&result, &base)?;
+                    }
+                    Ok(result)
+                }
+                _ => pow(base, expand(exponent)?),
+            }
+        }
+        Sym::Call(name, args) => call(name, args.iter().map(expand).collect::<Result<_, _>>()?),
+        other => Ok(other.clone()),
+    }
+}
+
+/// The shorter of the normal form and its expansion.
+pub fn simplify(sym: &Sym) -> Result<Sym, String> {
+    match expand(sym) {
+        Ok(expanded) if expanded.to_string().len() < sym.to_string().len() => Ok(expanded),
+        _ => Ok(sym.clone()),
+    }
+}
+
+fn lcm(a: i128, b: i128) -> Option<i128> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The positive fraction that leaves coprime integers when dividing `values`.
+fn content(values: &[Rational]) -> Result<Rational, String> {
+    let mut numerator = 0;
+    let mut denominator = 1;
+    for value in values {
+        numerator = gcd(numerator, value.numer());
+        denominator = lcm(denominator, value.denom()).ok_or_else(overflow)?;
+    }
+    exact(Rational::new(numerator.max(1), denominator))
+}
+
+fn divisors(n: i128) -> Vec<i128> {
+    let n = n.abs();
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut d = 1;
+    while d * d <= n {
+        if n % d == 0 {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+fn evaluate(poly: &[Rational], x: Rational) -> Option<Rational> {
+    poly.iter()
+        .rev()
+        .try_fold(Rational::ZERO, |acc, c| acc.checked_mul(&x)?.checked_add(c))
+}
+
+/// Divides by `x - root`, which must be a root.
+fn deflate(poly: &[Rational], root: Rational) -> Option<Vec<Rational>> {
+    let mut quotient = vec![Rational::ZERO; poly.len() - 1];
+    let mut carry = Rational::ZERO;
+    for i in (1..poly.len()).rev() {
+        carry = carry.checked_mul(&root)?.checked_add(&poly[i])?;
+        quotient[i - 1] = carry;
+    }
+    Some(quotient)
+}
+
+/// Coefficients from the constant term up, if `sym` is a polynomial in `var`
+/// with numeric coefficients.
+fn coefficients(sym: &Sym, var: &str) -> Option<Vec<Rational>> {
+    let terms = match sym {
+        Sym::Add(terms) => terms.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    let mut poly = Vec::new();
+    for term in terms {
+        let (c, rest) = split_coefficient(term.clone());
+        let degree = match rest {
+            Sym::Num(_) => 0,
+            Sym::Var(name) if name == var => 1,
+            Sym::Pow(base, exponent) => match (*base, *exponent) {
+                (Sym::Var(name), Sym::Num(e)) if name == var && e.is_integer() && e.numer() > 0 && e.numer() <= 1000 => {
+                    e.numer() as usize
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if poly.len() <= degree {
+            poly.resize(degree + 1, Rational::ZERO);
+        }
+        poly[degree] = c;
+    }
+    Some(poly)
+}
+
+fn polynomial(poly: &[Rational], var: &str) -> Result<Sym, String> {
+    let terms = poly
+        .iter()
+        .enumerate()
+        .map(|(k, c)| mul(vec![Sym::Num(*c), pow(Sym::Var(var.to_string()), number(k as i128))?]))
+        .collect::<Result<_, _>>()?;
+    add(terms)
+}
+
+/// Factors a polynomial over the rationals: a constant, linear factors
+/// `q x - p` for its rational roots and what remains.
+fn factor_polynomial(mut poly: Vec<Rational>, var: &str) -> Result<Vec<Sym>, String> {
+    let leading = poly.last().copied().unwrap_or(Rational::ZERO);
+    if poly.len() < 3 || poly[0].is_zero() || leading.is_zero() {
+        return Ok(vec![polynomial(&poly, var)?]);
+    }
+
+    let scale = content(&poly)?;
+    let mut constant = if leading.is_negative() { exact(scale.checked_neg())? } else { scale };
+    for c in poly.iter_mut() {
+        *c = exact(c.checked_div(&constant))?;
+    }
+
+    let (first, last) = (poly[0].numer(), poly[poly.len() - 1].numer());
+    let mut roots = Vec::new();
+    if first.abs() <= MAX_DIVISOR_SEARCH && last.abs() <= MAX_DIVISOR_SEARCH {
+        for p in divisors(first) {
+            for q in divisors(last) {
+                for p in [p, -p] {
+                    let Some(root) = Rational::new(p, q) else { continue };
+                    while poly.len() > 1 && evaluate(&poly, root).is_some_and(|v| v.is_zero()) {
+                        poly = deflate(&poly, root).ok_or_else(overflow)?;
+                        roots.push(root);
+                    }
+                }
+            }
+        }
+    }
+
+    // x - p/q becomes q x - p, moving 1/q into the constant.
+    let mut factors = Vec::new();
+    roots.sort_by(|a, b| b.to_f64().total_cmp(&a.to_f64()));
+    for root in roots {
+        let q = Rational::integer(root.denom());
+        constant = exact(constant.checked_div(&q))?;
+        factors.push(add(vec![
+            mul(vec![Sym::Num(q), Sym::Var(var.to_string())])?,
+            Sym::Num(exact(Rational::integer(root.numer()).checked_neg())?),
+        ])?);
+    }
+    let rest = content(&poly)?;
+    let rest = if poly.last().is_some_and(Rational::is_negative) { exact(rest.checked_neg())? } else { rest };
+    constant = exact(constant.checked_mul(&rest))?;
+    for c in poly.iter_mut() {
+        *c = exact(c.checked_div(&rest))?;
+    }
+
+    factors.insert(0, Sym::Num(constant));
+    factors.push(polynomial(&poly, var)?);
+    Ok(factors)
+}
+
+/// Takes out the common numeric and symbolic factors of a sum, then splits a
+/// polynomial in one variable into linear factors over the rationals.
+pub fn factor(sym: &Sym, var: Option<&str>) -> Result<Sym, String> {
+    let expanded = expand(sym)?;
+    let Sym::Add(terms) = &expanded else {
+        return Ok(expanded);
+    };
+
+    let split: Vec<(Rational, Sym)> = terms.iter().map(|t| split_coefficient(t.clone())).collect();
+    let numbers: Vec<Rational> = split.iter().map(|(c, _)| *c).collect();
+    let mut common = content(&numbers)?;
+    if numbers[0].is_negative() {
+        common = exact(common.checked_neg())?;
+    }
+
+    // Bases present in every term, with their lowest exponent.
+    let powers = |rest: &Sym| -> Vec<(Sym, Rational)> {
+        let factors = match rest {
+            Sym::Mul(factors) => factors.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        factors
+            .iter()
+            .filter(|f| !matches!(f, Sym::Num(_)))
+            .map(|f| match f {
+                Sym::Pow(base, exponent) => match **exponent {
+                    Sym::Num(e) => ((**base).clone(), e),
+                    _ => (f.clone(), Rational::ONE),
+                },
+                other => (other.clone(), Rational::ONE),
+            })
+            .collect()
+    };
+    let mut shared: Vec<(Sym, Rational)> = Vec::new();
+    for (base, exponent) in powers(&split[0].1) {
+        let mut lowest = exponent;
+        let everywhere = split[1..].iter().all(|(_, rest)| {
+            powers(rest).iter().find(|(b, _)| same(b, &base)).is_some_and(|(_, e)| {
+                if e.to_f64() < lowest.to_f64() {
+                    lowest = *e;
+                }
+                true
+            })
+        });
+        if everywhere {
+            shared.push((base, lowest));
+        }
+    }
+
+    let mut divisor = vec![Sym::Num(exact(Rational::ONE.checked_div(&common))?)];
+    for (base, exponent) in &shared {
+        divisor.push(pow(base.clone(), Sym::Num(exact(exponent.checked_neg())?))?);
+    }
+    let rest = add(terms
+        .iter()
+        .map(|term| {
+            let mut factors = divisor.clone();
+            factors.push(term.clone());
+            mul(factors)
+        })
+        .collect::<Result<_, _>>()?)?;
+
+    let mut factors = vec![Sym::Num(common)];
+    for (base, exponent) in shared {
+        factors.push(pow(base, Sym::Num(exponent))?);
+    }
+    let var = var.or_else(|| rest.first_variable()).unwrap_or("x");
+    match coefficients(&rest, var) {
+        Some(poly) => factors.extend(factor_polynomial(poly, var)?),
+        None => factors.push(rest),
+    }
+    mul(factors)
+}
+
+// ---- Display -------------------------------------------------------------
+
+fn is_negative(term: &Sym) -> bool {
+    match term {
+        Sym::Num(n) => n.is_negative(),
+        Sym::Mul(factors) => matches!(factors.first(), Some(Sym::Num(n)) if n.is_negative()),
+        _ => false,
+    }
+}
+
+fn negated(term: &Sym) -> Sym {
+    match term {
+        Sym::Num(n) => Sym::Num(n.abs()),
+        Sym::Mul(factors) => match factors.split_first() {
+            Some((Sym::Num(n), rest)) => with_coefficient(n.abs(), match rest {
+                [single] => single.clone(),
+                _ => Sym::Mul(rest.to_vec()),
+            }),
+            _ => term.clone(),
+        },
+        _ => term.clone(),
+    }
+}
+
+/// `x^-n` as `x^n`, for putting it under a fraction bar.
+fn reciprocal(factor: &Sym) -> Option<Sym> {
+    let Sym::Pow(base, exponent) = factor else { return None };
+    match **exponent {
+        Sym::Num(e) if e.is_negative() => {
+            let e = e.abs();
+            Some(if e == Rational::ONE { (**base).clone() } else { Sym::Pow(base.clone(), Box::new(Sym::Num(e))) })
+        }
+        _ => None,
+    }
+}
+
+/// Factors side by side; `*` only where a number would follow, as the parser
+/// does not read `x 2` as a product.
+fn juxtapose(items: &[String]) -> String {
+    let mut out = String::new();
+    for item in items {
+        if !out.is_empty() {
+            out.push(if item.starts_with(|c: char| c.is_ascii_digit()) { '*' } else { ' ' });
+        }
+        out.push_str(item);
+    }
+    out
+}
+
+fn factor_text(factor: &Sym) -> String {
+    match factor {
+        Sym::Add(_) | Sym::Mul(_) => format!("({})", factor),
+        other => other.to_string(),
+    }
+}
+
+fn product_text(coefficient: Rational, factors: &[Sym]) -> String {
+    let mut top = Vec::new();
+    let mut bottom = Vec::new();
+    if coefficient.abs().numer() != 1 {
+        top.push(coefficient.abs().numer().to_string());
+    }
+    if coefficient.denom() != 1 {
+        bottom.push(coefficient.denom().to_string());
+    }
+    for factor in factors {
+        match reciprocal(factor) {
+            Some(factor) => bottom.push(factor_text(&factor)),
+            None => top.push(factor_text(factor)),
+        }
+    }
+
+    let sign = if coefficient.is_negative() { "-" } else { "" };
+    let top = if top.is_empty() { String::from("1") } else { juxtapose(&top) };
+    match bottom.len() {
+        0 => format!("{}{}", sign, top),
+        1 => format!("{}{}/{}", sign, top, bottom[0]),
+        _ => format!("{}{}/({})", sign, top, juxtapose(&bottom)),
+    }
+}
+
+fn power_text(base: &Sym, exponent: &Sym) -> String {
+    if matches!(exponent, Sym::Num(e) if e.numer() == 1 && e.denom() == 2) {
+        return format!("sqrt({})", base);
+    }
+    let base = match base {
+        Sym::Num(n) if n.is_negative() || !n.is_integer() => format!("({})", base),
+        Sym::Add(_) | Sym::Mul(_) | Sym::Pow(..) => format!("({})", base),
+        other => other.to_string(),
+    };
+    let exponent = match exponent {
+        Sym::Num(e) if e.is_integer() && !e.is_negative() => e.to_string(),
+        Sym::Var(_) | Sym::Call(..) => exponent.to_string(),
+        other => format!("({})", other),
+    };
+    format!("{}^{}", base, exponent)
+}
+
+impl fmt::Display for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sym::Num(n) => write!(f, "{}", n),
+            Sym::Var(name) => write!(f, "{}", name),
+            Sym::Add(terms) => {
+                // Start with a positive term where possible: 1 - x^2, not -x^2 + 1.
+                let mut order: Vec<&Sym> = terms.iter().collect();
+                if let Some(i) = order.iter().position(|t| !is_negative(t)) {
+                    let first = order.remove(i);
+                    order.insert(0, first);
+                }
+                for (i, term) in order.into_iter().enumerate() {
+                    match (i, is_negative(term)) {
+                        (0, _) => write!(f, "{}", term)?,
+                        (_, true) => write!(f, " - {}", negated(term))?,
+                        (_, false) => write!(f, " + {}", term)?,
+                    }
+                }
+                Ok(())
+            }
+            Sym::Mul(factors) => match factors.split_first() {
+                Some((Sym::Num(c), rest)) => write!(f, "{}", product_text(*c, rest)),
+                _ => write!(f, "{}", product_text(Rational::ONE, factors)),
+            },
+            Sym::Pow(..) if reciprocal(self).is_some() => {
+                write!(f, "{}", product_text(Rational::ONE, std::slice::from_ref(self)))
+            }
+            Sym::Pow(base, exponent) => write!(f, "{}", power_text(base, exponent)),
+            Sym::Call(name, args) => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+// ---- Commands ------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Simplify,
+    Expand,
+    Factor,
+    Differentiate,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 4] = [Operation::Simplify, Operation::Expand, Operation::Factor, Operation::Differentiate];
+
+    /// The name of the command typed on the display.
+    pub fn command(self) -> &'static str {
+        match self {
+            Operation::Simplify => "simplify",
+            Operation::Expand => "expand",
+            Operation::Factor => "factor",
+            Operation::Differentiate => "diff",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Operation::Simplify => "Uprość",
+            Operation::Expand => "Rozwiń",
+            Operation::Factor => "Rozłóż na czynniki",
+            Operation::Differentiate => "Pochodna",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Applies `operation` to `input`; `variable` may be empty to use the first
+/// variable of the expression. Derivatives of order `order` are simplified.
+pub fn apply(operation: Operation, input: &Expr, variable: &str, order: usize) -> Result<Sym, String> {
+    let sym = from_expr(input)?;
+    let variable = match variable.trim() {
+        "" => None,
+        name => Some(expr::parse(name)?.as_variable()?.to_string()),
+    };
+    match operation {
+        Operation::Simplify => simplify(&sym),
+        Operation::Expand => expand(&sym),
+        Operation::Factor => factor(&sym, variable.as_deref()),
+        Operation::Differentiate => {
+            let variable = variable.as_deref().or_else(|| sym.first_variable()).unwrap_or("x").to_string();
+            let mut result = sym;
+            for _ in 0..order {
+                result = derivative(&result, &variable)?;
+            }
+            simplify(&result)
+        }
+    }
+}
+
+/// Whether the display holds a symbolic command such as `expand((x+1)^2)` or
+/// `d/dx sin(x)^2`, whose result is an expression rather than a number.
+pub fn is_command(input: &str) -> bool {
+    let input = input.trim();
+    input.starts_with("d/d")
+        || Operation::ALL.iter().any(|operation| {
+            input
+                .strip_prefix(operation.command())
+                .is_some_and(|rest| rest.trim_start().starts_with('('))
+        })
+}
+
+/// Runs a symbolic command typed on the display.
+pub fn run(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if let Some(rest) = input.strip_prefix("d/d") {
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        let (variable, body) = rest.split_at(end);
+        if variable.is_empty() || body.trim().is_empty() {
+            return Err("Użycie: d/dx wyrażenie".to_string());
+        }
+        return Ok(apply(Operation::Differentiate, &expr::parse(body)?, variable, 1)?.to_string());
+    }
+
+    let Expr::Call(name, args) = expr::parse(input)? else {
+        return Err("Polecenie musi obejmować całe wyrażenie, np. expand((x+1)^2)".to_string());
+    };
+    let operation = Operation::ALL
+        .into_iter()
+        .find(|operation| operation.command() == name)
+        .ok_or_else(|| format!("Nieznane polecenie: {}", name))?;
+    let result = match (operation, args.as_slice()) {
+        (Operation::Differentiate, [body]) => apply(operation, body, "", 1)?,
+        (Operation::Differentiate, [body, var]) => apply(operation, body, var.as_variable()?, 1)?,
+        (Operation::Differentiate, [body, var, Expr::Num(n)]) if n.fract() == 0.0 && *n >= 0.0 => {
+            if *n as usize > MAX_ORDER {
+                return Err(format!("Najwyżej pochodna rzędu {}", MAX_ORDER));
+            }
+            apply(operation, body, var.as_variable()?, *n as usize)?
+        }
+        (Operation::Differentiate, _) => return Err("Użycie: diff(f, x) lub diff(f, x, rząd)".to_string()),
+        (Operation::Factor, [body, var]) => apply(operation, body, var.as_variable()?, 0)?,
+        (_, [body]) => apply(operation, body, "", 0)?,
+        _ => return Err(format!("Użycie: {}(wyrażenie)", name)),
+    };
+    Ok(result.to_string())
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SymbolicField {
+    Expression,
+    Variable,
+}
+
+#[derive(Default)]
+pub struct SymbolicForm {
+    pub expression: String,
+    pub variable: String,
+    pub result: Option<Result<String, String>>,
+}
+
+impl SymbolicForm {
+    pub fn field_mut(&mut self, field: SymbolicField) -> &mut String {
+        match field {
+            SymbolicField::Expression => &mut self.expression,
+            SymbolicField::Variable => &mut self.variable,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_ok(input: &str) -> String {
+        run(input).unwrap()
+    }
+
+    #[test]
+    fn simplification_combines_like_terms() {
+        assert_eq!(run_ok("simplify(x + x + 2x)"), "4 x");
+        assert_eq!(run_ok("simplify(2/4 + 1/4)"), "3/4");
+        assert_eq!(run_ok("simplify(x*x*x)"), "x^3");
+        assert_eq!(exact_value("1/3 + 1/6"), Rational::new(1, 2));
+        assert_eq!(exact_value("pi"), None);
+    }
+
+    #[test]
+    fn expansion() {
+        assert_eq!(run_ok("expand((x+1)^2)"), "x^2 + 2 x + 1");
+        assert_eq!(run_ok("expand((a+b)^3)"), "a^3 + 3 a^2 b + 3 a b^2 + b^3");
+        assert_eq!(run_ok("expand((x-1)(x+1))"), "x^2 - 1");
+        assert!(run("expand((a+b+c)^100)").is_err());
+        assert!(run("expand((x+1)^100000000000000000000)").is_err());
+        assert_eq!(run_ok("expand((a+b+c)^3)").matches('+').count(), 9);
+    }
+
+    #[test]
+    fn factoring_over_the_rationals() {
+        assert_eq!(run_ok("factor(x^2 - 1)"), "(x - 1) (x + 1)");
+        assert_eq!(run_ok("factor(x^3 - 6x^2 + 11x - 6)"), "(x - 3) (x - 2) (x - 1)");
+        assert_eq!(run_ok("factor(2x^2 + 4x)"), "2 x (x + 2)");
+        assert_eq!(run_ok("factor(a x^2 - a, x)"), "a (x - 1) (x + 1)");
+        assert_eq!(run_ok("factor(x^2 + 1)"), "x^2 + 1");
+    }
+
+    #[test]
+    fn derivatives() {
+        assert_eq!(run_ok("d/dx sin(x)^2"), "2 sin(x) cos(x)");
+        assert_eq!(run_ok("d/dx ln(x)"), "1/x");
+        assert_eq!(run_ok("diff(exp(2x))"), "2 exp(2 x)");
+        assert_eq!(run_ok("diff(x^3, x, 2)"), "6 x");
+        assert_eq!(run_ok("diff(x^x)"), "x^x (ln(x) + 1)");
+        assert!(run("diff(x, x, 21)").is_err());
+    }
+
+    #[test]
+    fn commands() {
+        assert!(is_command("expand ((x+1)^2)"));
+        assert!(is_command("d/dx x^2"));
+        assert!(!is_command("factorial(5)"));
+        assert!(run("foo(x)").is_err());
+        assert!(run("x + 1").is_err());
+    }
+}
@@ -0,0 +1,335 @@
+// Time value of money, using the financial calculator sign convention:
+// money received is positive, money paid out is negative, and
+//
+//     pv·(1+i)ⁿ + pmt·(1+i·due)·((1+i)ⁿ − 1)/i + fv = 0
+//
+// where `i` is the rate per payment period and `due` is 1 for payments at the
+// beginning of the period. The functions mirror their spreadsheet namesakes.
+
+use crate::expr::Context;
+
+fn due_factor(rate: f64, due: bool) -> f64 {
+    if due {
+        1.0 + rate
+    } else {
+        1.0
+    }
+}
+
+// ((1+i)ⁿ − 1)/i, with its limit n at i = 0.
+fn annuity_factor(rate: f64, nper: f64) -> f64 {
+    if rate == 0.0 {
+        nper
+    } else {
+        ((1.0 + rate).powf(nper) - 1.0) / rate
+    }
+}
+
+pub fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, due: bool) -> f64 {
+    -(pv * (1.0 + rate).powf(nper) + pmt * due_factor(rate, due) * annuity_factor(rate, nper))
+}
+
+pub fn pv(rate: f64, nper: f64, pmt: f64, fv: f64, due: bool) -> f64 {
+    -(fv + pmt * due_factor(rate, due) * annuity_factor(rate, nper)) / (1.0 + rate).powf(nper)
+}
+
+pub fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, due: bool) -> f64 {
+    -(fv + pv * (1.0 + rate).powf(nper)) / (due_factor(rate, due) * annuity_factor(rate, nper))
+}
+
+pub fn nper(rate: f64, pmt: f64, pv: f64, fv: f64, due: bool) -> f64 {
+    if rate == 0.0 {
+        return -(pv + fv) / pmt;
+    }
+
+    let payment = pmt * due_factor(rate, due);
+    ((payment - fv * rate) / (payment + pv * rate)).ln() / (1.0 + rate).ln()
+}
+
+/// Periodic rate solving the TVM equation, or NaN when no rate converges.
+pub fn rate(nper: f64, pmt: f64, pv: f64, fv: f64, due: bool, guess: f64) -> f64 {
+    solve_rate(
+        |i| pv * (1.0 + i).powf(nper) + pmt * due_factor(i, due) * annuity_factor(i, nper) + fv,
+        guess,
+    )
+}
+
+/// Finds a rate `i > -1` with `balance(i) = 0`, or NaN when there is none.
+pub fn solve_rate(balance: impl Fn(f64) -> f64, guess: f64) -> f64 {
+    // Newton's method with a numeric derivative converges for typical inputs.
+    let mut i = guess;
+    for _ in 0..100 {
+        let f = balance(i);
+        let h = 1e-7 * i.abs().max(1e-4);
+        let derivative = (balance(i + h) - balance(i - h)) / (2.0 * h);
+        if derivative == 0.0 || !derivative.is_finite() {
+            break;
+        }
+
+        let next = i - f / derivative;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - i).abs() < 1e-12 {
+            return next;
+        }
+        i = next;
+    }
+
+    // Otherwise look for a sign change and bisect it.
+    let mut low = -0.99;
+    let mut step = 0.01;
+    while low < 10.0 {
+        let high = low + step;
+        if balance(low).signum() != balance(high).signum() {
+            return bisect(&balance, low, high);
+        }
+        low = high;
+        step *= 1.2;
+    }
+
+    f64::NAN
+}
+
+fn bisect(f: &impl Fn(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+    let f_low = f(low);
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if f(mid).signum() == f_low.signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TvmField {
+    N,
+    Rate,
+    Pv,
+    Pmt,
+    Fv,
+    PaymentsPerYear,
+    CompoundsPerYear,
+}
+
+impl TvmField {
+    pub const SOLVABLE: [TvmField; 5] = [TvmField::N, TvmField::Rate, TvmField::Pv, TvmField::Pmt, TvmField::Fv];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TvmField::N => "N (liczba okresów)",
+            TvmField::Rate => "I/Y (% rocznie)",
+            TvmField::Pv => "PV (wartość obecna)",
+            TvmField::Pmt => "PMT (płatność)",
+            TvmField::Fv => "FV (wartość przyszła)",
+            TvmField::PaymentsPerYear => "P/Y (płatności w roku)",
+            TvmField::CompoundsPerYear => "C/Y (kapitalizacje w roku)",
+        }
+    }
+}
+
+/// The TVM worksheet: fill four of the five main fields, solve the fifth.
+pub struct TvmForm {
+    pub n: String,
+    pub rate: String,
+    pub pv: String,
+    pub pmt: String,
+    pub fv: String,
+    pub payments_per_year: String,
+    pub compounds_per_year: String,
+    pub due: bool,
+    pub result: Option<Result<(TvmField, f64), String>>,
+}
+
+impl Default for TvmForm {
+    fn default() -> Self {
+        Self {
+            n: String::new(),
+            rate: String::new(),
+            pv: String::new(),
+            pmt: String::new(),
+            fv: String::new(),
+            payments_per_year: String::from("12"),
+            compounds_per_year: String::from("12"),
+            due: false,
+            result: None,
+        }
+    }
+}
+
+impl TvmForm {
+    pub fn field(&self, field: TvmField) -> &String {
+        match field {
+            TvmField::N => &self.n,
+            TvmField::Rate => &self.rate,
+            TvmField::Pv => &self.pv,
+            TvmField::Pmt => &self.pmt,
+            TvmField::Fv => &self.fv,
+            TvmField::PaymentsPerYear => &self.payments_per_year,
+            TvmField::CompoundsPerYear => &self.compounds_per_year,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: TvmField) -> &mut String {
+        match field {
+            TvmField::N => &mut self.n,
+            TvmField::Rate => &mut self.rate,
+            TvmField::Pv => &mut self.pv,
+            TvmField::Pmt => &mut self.pmt,
+            TvmField::Fv => &mut self.fv,
+            TvmField::PaymentsPerYear => &mut self.payments_per_year,
+            TvmField::CompoundsPerYear => &mut self.compounds_per_year,
+        }
+    }
+}
+
+/// Parsed worksheet values; exactly one of the five main values is `None`.
+pub struct Worksheet {
+    pub n: Option<f64>,
+    pub rate: Option<f64>,
+    pub pv: Option<f64>,
+    pub pmt: Option<f64>,
+    pub fv: Option<f64>,
+    pub payments_per_year: f64,
+    pub compounds_per_year: f64,
+    pub due: bool,
+}
+
+impl Worksheet {
+    // Nominal annual % to the effective rate per payment period.
+    fn periodic_rate(&self, annual: f64) -> f64 {
+        (1.0 + annual / 100.0 / self.compounds_per_year).powf(self.compounds_per_year / self.payments_per_year) - 1.0
+    }
+
+    fn annual_rate(&self, periodic: f64) -> f64 {
+        ((1.0 + periodic).powf(self.payments_per_year / self.compounds_per_year) - 1.0) * self.compounds_per_year * 100.0
+    }
+
+    pub fn solve(&self) -> Result<(TvmField, f64), String> {
+        if self.payments_per_year <= 0.0 || self.compounds_per_year <= 0.0 {
+            return Err("P/Y i C/Y muszą być dodatnie".to_string());
+        }
+
+        let values = [self.n, self.rate, self.pv, self.pmt, self.fv];
+        let missing: Vec<TvmField> = TvmField::SOLVABLE
+            .iter()
+            .zip(values)
+            .filter(|(_, v)| v.is_none())
+            .map(|(f, _)| *f)
+            .collect();
+
+        let field = match missing.as_slice() {
+            [field] => *field,
+            [] => return Err("Zostaw jedno z pól N, I/Y, PV, PMT, FV puste".to_string()),
+            _ => return Err("Uzupełnij cztery z pól N, I/Y, PV, PMT, FV".to_string()),
+        };
+
+        let n = self.n.unwrap_or(0.0);
+        let i = self.periodic_rate(self.rate.unwrap_or(0.0));
+        let pv_value = self.pv.unwrap_or(0.0);
+        let pmt_value = self.pmt.unwrap_or(0.0);
+        let fv_value = self.fv.unwrap_or(0.0);
+
+        let result = match field {
+            TvmField::N => nper(i, pmt_value, pv_value, fv_value, self.due),
+            TvmField::Rate => self.annual_rate(rate(n, pmt_value, pv_value, fv_value, self.due, 0.01)),
+            TvmField::Pv => pv(i, n, pmt_value, fv_value, self.due),
+            TvmField::Pmt => pmt(i, n, pv_value, fv_value, self.due),
+            _ => fv(i, n, pmt_value, pv_value, self.due),
+        };
+
+        if result.is_finite() {
+            Ok((field, result))
+        } else {
+            Err("Brak rozwiązania – sprawdź znaki przepływów (wpłaty ujemne)".to_string())
+        }
+    }
+}
+
+/// Registers `pv`, `fv`, `pmt`, `nper` and `rate` for the expression evaluator.
+/// Rates are per period as decimals; the trailing `fv`/`pv` and `type`
+/// (1 = payment at the beginning) arguments are optional, as in spreadsheets.
+pub fn register(ctx: &mut Context) {
+    fn opt(args: &[f64], index: usize) -> f64 {
+        args.get(index).copied().unwrap_or(0.0)
+    }
+    fn due(args: &[f64], index: usize) -> bool {
+        args.get(index).is_some_and(|t| *t != 0.0)
+    }
+
+    ctx.funcn("pv", |a| pv(a[0], a[1], a[2], opt(a, 3), due(a, 4)), 3..6);
+    ctx.funcn("fv", |a| fv(a[0], a[1], a[2], opt(a, 3), due(a, 4)), 3..6);
+    ctx.funcn("pmt", |a| pmt(a[0], a[1], a[2], opt(a, 3), due(a, 4)), 3..6);
+    ctx.funcn("nper", |a| nper(a[0], a[1], a[2], opt(a, 3), due(a, 4)), 3..6);
+    ctx.funcn(
+        "rate",
+        |a| rate(a[0], a[1], a[2], opt(a, 3), due(a, 4), a.get(5).copied().unwrap_or(0.1)),
+        3..7,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64, tolerance: f64) -> bool {
+        (actual - expected).abs() <= tolerance
+    }
+
+    #[test]
+    fn spreadsheet_values() {
+        assert!(close(pmt(0.05 / 12.0, 360.0, 200_000.0, 0.0, false), -1073.64, 0.005));
+        assert!(close(fv(0.06 / 12.0, 120.0, -100.0, 0.0, false), 16387.93, 0.005));
+        assert!(close(pv(0.08 / 12.0, 240.0, 500.0, 0.0, false), -59777.15, 0.005));
+        assert!(close(nper(0.01, -100.0, 1000.0, 0.0, false), 10.5886, 1e-4));
+        assert!(close(rate(360.0, -1073.64, 200_000.0, 0.0, false, 0.1), 0.05 / 12.0, 1e-7));
+    }
+
+    #[test]
+    fn payments_in_advance_and_zero_rate() {
+        let arrears = fv(0.05, 10.0, -100.0, 0.0, false);
+        assert!(close(fv(0.05, 10.0, -100.0, 0.0, true), arrears * 1.05, 1e-9));
+        assert_eq!(fv(0.0, 10.0, -100.0, -50.0, false), 1050.0);
+        assert_eq!(nper(0.0, -100.0, 1000.0, 0.0, false), 10.0);
+    }
+
+    #[test]
+    fn worksheet_solves_the_empty_field() {
+        let sheet = Worksheet {
+            n: Some(360.0),
+            rate: Some(6.0),
+            pv: Some(100_000.0),
+            pmt: None,
+            fv: Some(0.0),
+            payments_per_year: 12.0,
+            compounds_per_year: 12.0,
+            due: false,
+        };
+        let (field, payment) = sheet.solve().unwrap();
+        assert_eq!(field, TvmField::Pmt);
+        assert!(close(payment, -599.55, 0.005));
+
+        let sheet = Worksheet { pmt: Some(payment), rate: None, ..sheet };
+        let (field, rate) = sheet.solve().unwrap();
+        assert_eq!(field, TvmField::Rate);
+        assert!(close(rate, 6.0, 1e-6));
+    }
+
+    #[test]
+    fn worksheet_needs_one_empty_field() {
+        let sheet = Worksheet {
+            n: None,
+            rate: None,
+            pv: Some(1000.0),
+            pmt: Some(-100.0),
+            fv: Some(0.0),
+            payments_per_year: 12.0,
+            compounds_per_year: 12.0,
+            due: false,
+        };
+        assert!(sheet.solve().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+// VAT calculations against an editable table of named rates.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VatRate {
+    pub name: String,
+    pub rate: f64,
+}
+
+impl VatRate {
+    fn new(name: &str, rate: f64) -> Self {
+        Self { name: name.to_string(), rate }
+    }
+
+    /// Parses the `name;rate` form used in the config file.
+    pub fn parse(input: &str) -> Option<Self> {
+        let (name, rate) = input.rsplit_once(';')?;
+        let rate = rate.trim().parse().ok()?;
+        Some(Self { name: name.trim().to_string(), rate })
+    }
+}
+
+impl fmt::Display for VatRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};{}", self.name, self.rate)
+    }
+}
+
+pub fn default_rates() -> Vec<VatRate> {
+    vec![
+        VatRate::new("PL podstawowa", 23.0),
+        VatRate::new("PL obniżona 8%", 8.0),
+        VatRate::new("PL obniżona 5%", 5.0),
+        VatRate::new("PL zwolniona", 0.0),
+        VatRate::new("DE", 19.0),
+        VatRate::new("DE obniżona", 7.0),
+        VatRate::new("CZ", 21.0),
+        VatRate::new("SK", 23.0),
+        VatRate::new("FR", 20.0),
+        VatRate::new("UK", 20.0),
+        VatRate::new("HU", 27.0),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VatMode {
+    NetToGross,
+    GrossToNet,
+    FromTax,
+}
+
+impl VatMode {
+    pub const ALL: [VatMode; 3] = [VatMode::NetToGross, VatMode::GrossToNet, VatMode::FromTax];
+}
+
+impl fmt::Display for VatMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VatMode::NetToGross => "Netto → brutto",
+                VatMode::GrossToNet => "Brutto → netto",
+                VatMode::FromTax => "Z kwoty podatku",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Breakdown {
+    pub net: f64,
+    pub tax: f64,
+    pub gross: f64,
+}
+
+/// Splits `amount` into net, tax and gross; its meaning depends on `mode`.
+pub fn breakdown(amount: f64, rate: f64, mode: VatMode) -> Result<Breakdown, String> {
+    let rate = rate / 100.0;
+    let net = match mode {
+        VatMode::NetToGross => amount,
+        VatMode::GrossToNet => amount / (1.0 + rate),
+        VatMode::FromTax if rate == 0.0 => {
+            return Err("Przy stawce 0% nie da się wyznaczyć netto z podatku".to_string())
+        }
+        VatMode::FromTax => amount / rate,
+    };
+    let tax = net * rate;
+
+    Ok(Breakdown { net, tax, gross: net + tax })
+}
+
+/// One editable row of the rate table.
+pub struct VatRateRow {
+    pub name: String,
+    pub rate: String,
+}
+
+pub struct VatForm {
+    pub amount: String,
+    pub mode: VatMode,
+    pub selected: usize,
+    pub rows: Vec<VatRateRow>,
+    pub status: Option<String>,
+}
+
+impl VatForm {
+    pub fn new(rates: &[VatRate]) -> Self {
+        let mut form = Self {
+            amount: String::new(),
+            mode: VatMode::NetToGross,
+            selected: 0,
+            rows: Vec::new(),
+            status: None,
+        };
+        form.set_rates(rates);
+        form
+    }
+
+    pub fn set_rates(&mut self, rates: &[VatRate]) {
+        self.rows = rates
+            .iter()
+            .map(|r| VatRateRow { name: r.name.clone(), rate: r.rate.to_string() })
+            .collect();
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// The table as entered; fails on the first row that is not a number.
+    pub fn rates(&self) -> Result<Vec<VatRate>, String> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let rate = row.rate.trim().replace(',', ".").parse::<f64>();
+                match rate {
+                    Ok(rate) if rate >= 0.0 => Ok(VatRate { name: row.name.trim().to_string(), rate }),
+                    _ => Err(format!("Niepoprawna stawka: {}", row.name)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-9
+    }
+
+    #[test]
+    fn breakdown_in_every_mode() {
+        let b = breakdown(100.0, 23.0, VatMode::NetToGross).unwrap();
+        assert!(close(b.tax, 23.0) && close(b.gross, 123.0));
+
+        let b = breakdown(123.0, 23.0, VatMode::GrossToNet).unwrap();
+        assert!(close(b.net, 100.0) && close(b.tax, 23.0));
+
+        let b = breakdown(8.0, 8.0, VatMode::FromTax).unwrap();
+        assert!(close(b.net, 100.0) && close(b.gross, 108.0));
+
+        assert!(breakdown(10.0, 0.0, VatMode::FromTax).is_err());
+    }
+
+    #[test]
+    fn rate_round_trips_through_the_config_format() {
+        let rate = VatRate::new("PL obniżona", 5.5);
+        assert_eq!(VatRate::parse(&rate.to_string()), Some(rate));
+        assert_eq!(VatRate::parse(" a;b ; 7 "), Some(VatRate::new("a;b", 7.0)));
+        assert_eq!(VatRate::parse("bez stawki"), None);
+    }
+
+    #[test]
+    fn default_rates_have_distinct_names() {
+        let rates = default_rates();
+        let mut names: Vec<&str> = rates.iter().map(|rate| rate.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), rates.len());
+    }
+
+    #[test]
+    fn form_rejects_bad_rates() {
+        let mut form = VatForm::new(&default_rates());
+        form.rows[0].rate = "22,5".to_string();
+        assert_eq!(form.rates().unwrap()[0].rate, 22.5);
+
+        form.rows[1].rate = "-1".to_string();
+        assert!(form.rates().is_err());
+    }
+}